@@ -24,7 +24,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let backend = Box::new(CPUBackend);
     let mut graph = Graph::new(backend);
 
-    let input_node = graph.input(Tensor::new_zeros(&[5, 6]));
+    let input_node = graph.placeholder(&[5, 6]);
 
     let mut gb = GraphBuilder::new(&mut graph);
 
@@ -48,9 +48,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     for epoch in 0..5001 { // Reduced epochs for demo, original was 20000
 
         // Load data
-        if let gran_prix::graph::Node::Input(ref mut t) = graph.nodes_mut()[input_node.0] {
-             *t = inputs_data.clone();
-        }
+        graph.feed(input_node, &inputs_data)?;
 
         // Forward
         let prediction = graph.execute(output_node)?;
@@ -78,9 +76,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\nFinal Results (Predicted vs Target):");
 
     // Load data
-    if let gran_prix::graph::Node::Input(ref mut t) = graph.nodes_mut()[input_node.0] {
-             *t = inputs_data.clone();
-    }
+    graph.feed(input_node, &inputs_data)?;
 
     let final_output = graph.execute(output_node)?;
 
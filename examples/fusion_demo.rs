@@ -4,22 +4,37 @@ use gran_prix::backend::cpu::CPUBackend;
 use gran_prix::Tensor;
 
 fn main() -> anyhow::Result<()> {
-    println!("Gran-Prix Fused Operations Demo: AddReLU");
+    println!("Gran-Prix Fused Operations Demo");
 
     let backend = Box::new(CPUBackend);
     let mut graph = Graph::new(backend);
     let mut gb = GraphBuilder::new(&mut graph);
 
-    let a = gb.val(Tensor::from_shape_vec(&[1, 4], vec![1.0, -2.0, 0.5, -0.1])?);
-    let b = gb.val(Tensor::from_shape_vec(&[1, 4], vec![0.5, 0.5, -1.0, 0.2])?);
+    // relu(x·w + b) + skip, then relu again
+    let x = gb.placeholder(&[1, 2]);
+    let skip = gb.placeholder(&[1, 4]);
+    let w = gb.param(Tensor::from_shape_vec(&[2, 4], vec![1.0, -1.0, 0.5, 0.0, 0.5, 0.5, -1.0, 1.0])?);
+    let b = gb.param(Tensor::from_shape_vec(&[1, 4], vec![0.1, 0.1, 0.1, 0.1])?);
+    let hidden = gb.linear(x, w, b);
+    let hidden = gb.relu(hidden);
+    let sum = gb.add(hidden, skip);
+    let output = gb.relu(sum);
 
-    // Fused Add+ReLU: max(a + b, 0) in a single pass
-    let output = gb.node(gran_prix::graph::OpType::AddReLU, vec![a, b]);
+    let ops = |graph: &Graph| -> Vec<String> {
+        graph.nodes().iter().filter_map(|n| n.op()).map(|op| op.name().to_string()).collect()
+    };
+    println!("Before: {:?}", ops(&graph));
 
-    let result = graph.execute(output)?;
-    println!("AddReLU([1.0,-2.0,0.5,-0.1] + [0.5,0.5,-1.0,0.2]):");
-    println!("Result: {:?}", result.as_slice()?);
-    println!("Expected: [1.5, 0.0, 0.0, 0.1]");
+    // The optimizer rewrites MatMul+Add+ReLU into FusedLinear and Add+ReLU into AddReLU
+    let map = graph.optimize(&[output], &[x, skip])?;
+    println!("After:  {:?}", ops(&graph));
+
+    let input = Tensor::from_shape_vec(&[1, 2], vec![1.0, -2.0])?;
+    let residual = Tensor::from_shape_vec(&[1, 4], vec![0.5, 0.5, -1.0, 0.2])?;
+    let feeds = [(map.get(x).unwrap(), &input), (map.get(skip).unwrap(), &residual)];
+    let result = graph.run(&feeds, &[map.get(output).unwrap()])?;
+    println!("Result: {:?}", result[0].as_slice()?);
+    println!("Expected: [0.6, 0.5, 1.6, 0.2]");
 
     Ok(())
 }
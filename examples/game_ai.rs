@@ -10,7 +10,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let backend = Box::new(CPUBackend);
     let mut graph = Graph::new(backend);
 
-    let input_node = graph.input(Tensor::new_zeros(&[4, 2]));
+    let input_node = graph.placeholder(&[4, 2]);
 
     let mut gb = GraphBuilder::new(&mut graph);
 
@@ -50,9 +50,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 4. Training Loop
     println!("Training...");
     for frame in 0..200 {
-        if let gran_prix::graph::Node::Input(ref mut t) = graph.nodes_mut()[input_node.0] {
-             *t = inputs_data.clone();
-        }
+        graph.feed(input_node, &inputs_data)?;
 
         // Forward
         let prediction = graph.execute(output_node)?;
@@ -82,9 +80,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         0.15, 0.9, 0.15, 0.9, 0.15, 0.9, 0.15, 0.9,
     ])?; // Batch of 4 for shape match
 
-    if let gran_prix::graph::Node::Input(ref mut t) = graph.nodes_mut()[input_node.0] {
-         *t = new_danger.clone();
-    }
+    graph.feed(input_node, &new_danger)?;
     graph.clear_values();
     let action = graph.execute(output_node)?;
 
@@ -58,7 +58,7 @@ fn main() {
     // Flatten -> (1, 100)
     // Linear(100, 1) -> Sigmoid
 
-    let x = gb.val(Tensor::new_zeros(&[1, 1, img_size, img_size]));
+    let x = gb.placeholder(&[1, 1, img_size, img_size]);
 
     // Conv Layer - random weights for 4 filters of 1x3x3
     let conv_size = 4 * 1 * 3 * 3;
@@ -95,9 +95,7 @@ fn main() {
             let label = train_y[i];
 
             // 1. Set Input
-            if let gran_prix::graph::Node::Input(ref mut t) = graph.nodes_mut()[x.0] {
-                *t = input.clone();
-            }
+            graph.feed(x, input).unwrap();
 
             // 2. Clear Values and Gradients
             graph.clear_values();
@@ -135,9 +133,7 @@ fn main() {
     let mut test_correct = 0;
     for (i, input) in test_x.iter().enumerate() {
         let label = test_y[i];
-        if let gran_prix::graph::Node::Input(ref mut t) = graph.nodes_mut()[x.0] {
-            *t = input.clone();
-        }
+        graph.feed(x, input).unwrap();
         graph.clear_values();
         let out = graph.execute(prediction).unwrap();
         let pred_val = out.get_2d(0, 0).unwrap();
@@ -24,7 +24,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let backend = Box::new(CPUBackend);
     let mut graph = Graph::new(backend);
 
-    let input_node = graph.input(Tensor::new_zeros(&[3, 2]));
+    let input_node = graph.placeholder(&[3, 2]);
 
     let mut gb = GraphBuilder::new(&mut graph);
 
@@ -44,9 +44,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Training loop
     for i in 0..1000 {
-        if let gran_prix::graph::Node::Input(ref mut t) = graph.nodes_mut()[input_node.0] {
-             *t = inputs_data.clone();
-        }
+        graph.feed(input_node, &inputs_data)?;
 
         let pred = graph.execute(output_node)?;
         let loss = loss_fn.calculate(&pred, &targets_data)?;
@@ -25,7 +25,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let backend = Box::new(CPUBackend);
     let mut graph = Graph::new(backend);
 
-    let input_node = graph.input(Tensor::new_zeros(&[4, 2]));
+    let input_node = graph.placeholder(&[4, 2]);
 
     let mut gb = GraphBuilder::new(&mut graph);
 
@@ -47,9 +47,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 3. Training
     for epoch in 0..10001 {
         // Load data
-        if let gran_prix::graph::Node::Input(ref mut t) = graph.nodes_mut()[input_node.0] {
-             *t = inputs_data.clone();
-        }
+        graph.feed(input_node, &inputs_data)?;
 
         // Forward
         let prediction = graph.execute(output_node)?;
@@ -73,9 +71,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // 4. Test
     // Forward pass calls execute which returns the output tensor
-    if let gran_prix::graph::Node::Input(ref mut t) = graph.nodes_mut()[input_node.0] {
-             *t = inputs_data.clone();
-    }
+    graph.feed(input_node, &inputs_data)?;
     let final_pred = graph.execute(output_node)?;
     println!("Final Predictions:\n{:?}", final_pred);
 
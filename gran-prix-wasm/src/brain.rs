@@ -8,6 +8,8 @@
 //! - **Input → Hidden → Output** (2-layer MLP)
 //! - **Activations**: ReLU (hidden), Sigmoid (output)
 //! - **Custom kernel**: Optional 1D convolution preprocessing
+//! - **Named heads**: Any [`NetworkDef`] via `fromNetworkDef`, including
+//!   multi-input/multi-output graphs (e.g. policy and value heads)
 //!
 //! # Performance Optimizations
 //!
@@ -23,13 +25,14 @@
 //! - **RefCell**: Interior mutability for WASM single-threaded execution
 
 use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
 
 use wasm_bindgen::prelude::*;
 use serde::Serialize;
-use gran_prix::{Tensor, GPError, Layer};
+use gran_prix::{Tensor, GPError, Layer, NodeId};
 use gran_prix::graph::Graph;
 use gran_prix::backend::cpu::CPUBackend;
-use gran_prix::network_def::NetworkDef;
+use gran_prix::network_def::{CompiledNetwork, NetworkDef};
 use gran_prix::layers::ActivationType;
 
 use crate::mutation::{MutationStrategy, XorShift};
@@ -41,6 +44,24 @@ use crate::errors::IntoJsResult;
 /// (e.g., by WASM heap overflow, use-after-free, or memory reinterpretation).
 const BRAIN_MAGIC: u32 = 0xDEADC0DE;
 
+/// A named graph input or output with its pre-allocated buffer.
+struct Port {
+    name: String,
+    node: usize,
+    buffer: RefCell<Tensor>,
+}
+
+impl Port {
+    fn new(name: &str, node: NodeId, shape: &[usize]) -> Self {
+        let batched: Vec<usize> = std::iter::once(1).chain(shape.iter().copied()).collect();
+        Port {
+            name: name.to_string(),
+            node: node.0,
+            buffer: RefCell::new(Tensor::new_zeros(&batched)),
+        }
+    }
+}
+
 /// Neural network brain for evolutionary agents
 ///
 /// # Design
@@ -65,14 +86,10 @@ const BRAIN_MAGIC: u32 = 0xDEADC0DE;
 pub struct NeuralBrain {
     /// Computation graph holding network structure
     graph: RefCell<Graph>,
-    /// Input node ID in graph
-    input_node: usize,
-    /// Output node ID in graph
-    output_node: usize,
-    /// Pre-allocated input tensor (avoid allocation in compute)
-    input_tensor: RefCell<Tensor>,
-    /// Pre-allocated output tensor (avoid allocation in compute)
-    output_tensor: RefCell<Tensor>,
+    /// Named inputs with pre-allocated tensors (avoid allocation in compute)
+    inputs: Vec<Port>,
+    /// Named outputs with pre-allocated tensors (avoid allocation in compute)
+    outputs: Vec<Port>,
     /// Magic number for corruption detection
     magic: u32,
     /// Re-entrancy protection flag
@@ -133,42 +150,68 @@ impl NeuralBrain {
             Some(ActivationType::Sigmoid),
         );
 
-        // 2. Compile to live graph
-        let compiled = net.compile(Box::new(CPUBackend))
-            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        // 2. Compile to live graph (with alternating initial weights)
+        Self::build(&net, seed_offset).into_js()
+    }
+
+    /// Create a brain from a [`NetworkDef`] JSON string.
+    ///
+    /// Graph-form definitions may declare several named inputs and outputs
+    /// (e.g. `"policy"` and `"value"` heads); use `compute_named` to drive
+    /// them. Weights get the same alternating initialization as `new`.
+    #[wasm_bindgen(js_name = fromNetworkDef)]
+    pub fn from_network_def(seed_offset: usize, json: &str) -> Result<NeuralBrain, JsValue> {
+        let net = NetworkDef::from_json(json).into_js()?;
+        Self::build(&net, seed_offset).into_js()
+    }
 
-        let input_node = compiled.input_node.0;
-        let output_node = compiled.output_node.0;
-        let mut graph = compiled.graph;
+    fn build(net: &NetworkDef, seed_offset: usize) -> Result<NeuralBrain, GPError> {
+        let compiled = net.compile(Box::new(CPUBackend))?;
+        let CompiledNetwork { mut graph, input_nodes, output_nodes, stateful_layers, .. } = compiled;
 
-        // 3. Override weights with deterministic alternating pattern.
+        // Override weights with deterministic alternating pattern.
         // This GUARANTEES steering variance in the population at generation 0.
         // Without this, all agents would behave identically.
         {
             let params = graph.params_mut();
-            let mut flat = params.export_flat()
-                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+            let mut flat = params.export_flat()?;
             for (i, val) in flat.iter_mut().enumerate() {
                 let sign = if (i + seed_offset) % 2 == 0 { 1.0 } else { -1.0 };
                 *val = sign * 0.1;
             }
-            params.import_flat(&flat)
-                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+            params.import_flat(&flat)?;
         }
 
+        let input_shapes = net.input_shapes();
+        let output_shapes = net.output_shapes()?;
+        let inputs = input_nodes.iter().zip(&input_shapes)
+            .map(|((name, id), (_, shape))| Port::new(name, *id, shape))
+            .collect();
+        let outputs = output_nodes.iter().zip(&output_shapes)
+            .map(|((name, id), (_, shape))| Port::new(name, *id, shape))
+            .collect();
+
         Ok(NeuralBrain {
             graph: RefCell::new(graph),
-            input_node,
-            output_node,
-            input_tensor: RefCell::new(Tensor::new_zeros(&[1, num_inputs])),
-            output_tensor: RefCell::new(Tensor::new_zeros(&[1, num_outputs])),
+            inputs,
+            outputs,
             magic: BRAIN_MAGIC,
             computing: RefCell::new(false),
             custom_kernel: RefCell::new(vec![0.0, 1.0, 0.0]),
-            layers: RefCell::new(compiled.stateful_layers),
+            layers: RefCell::new(stateful_layers),
         })
     }
 
+    /// Names of the network inputs, in declaration order.
+    pub fn input_names(&self) -> Vec<String> {
+        self.inputs.iter().map(|p| p.name.clone()).collect()
+    }
+
+    /// Names of the network outputs, in declaration order.
+    pub fn output_names(&self) -> Vec<String> {
+        self.outputs.iter().map(|p| p.name.clone()).collect()
+    }
+
     /// Compute forward pass through the network
     ///
     /// # Arguments
@@ -177,7 +220,9 @@ impl NeuralBrain {
     ///
     /// # Returns
     ///
-    /// Output values (length = `num_outputs`) or error
+    /// Output values (length = `num_outputs`) or error. For networks with
+    /// several outputs, the heads are concatenated in declaration order.
+    /// Multi-input networks must use `compute_named`.
     ///
     /// # Errors
     ///
@@ -195,8 +240,36 @@ impl NeuralBrain {
         self.compute_typed(inputs).into_js()
     }
 
+    /// Compute forward pass for named inputs and outputs
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - Object mapping every input name to its values
+    ///   (e.g. `{ sensors: [...], goal: [...] }`)
+    ///
+    /// # Returns
+    ///
+    /// Object mapping every output name to its values
+    /// (e.g. `{ policy: [...], value: [...] }`). The custom kernel is not applied.
+    pub fn compute_named(&self, inputs: JsValue) -> Result<JsValue, JsValue> {
+        let feeds: HashMap<String, Vec<f32>> = serde_wasm_bindgen::from_value(inputs)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let outputs = self.compute_named_typed(&feeds).into_js()?;
+        serde_wasm_bindgen::to_value(&outputs).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Internal named compute logic returning structured errors
+    pub(crate) fn compute_named_typed(&self, feeds: &HashMap<String, Vec<f32>>) -> Result<BTreeMap<String, Vec<f32>>, GPError> {
+        self.guarded(|| self.compute_named_internal(feeds))
+    }
+
     /// Internal compute logic returning structured errors
     fn compute_typed(&self, inputs: &[f32]) -> Result<Vec<f32>, GPError> {
+        self.guarded(|| self.compute_internal(inputs))
+    }
+
+    /// Runs `f` between corruption checks, with re-entrancy protection
+    fn guarded<T>(&self, f: impl FnOnce() -> Result<T, GPError>) -> Result<T, GPError> {
         // Corruption check BEFORE any work
         if self.magic != BRAIN_MAGIC {
             return Err(GPError::CorruptedMemory { 
@@ -215,7 +288,7 @@ impl NeuralBrain {
             ComputingGuard(&self.computing)
         };
 
-        let result = self.compute_internal(inputs);
+        let result = f();
 
         // Corruption check AFTER computation
         if self.magic != BRAIN_MAGIC {
@@ -234,19 +307,30 @@ impl NeuralBrain {
     ///
     /// 1. Apply 1D convolution to inputs (optional preprocessing)
     /// 2. Copy processed inputs to graph input node
-    /// 3. Execute topological sorted graph
+    /// 3. Execute the cached execution plan of the graph
     /// 4. Extract output values
     ///
     /// # Panics
     ///
     /// Should not panic in normal operation. Uses `?` for error propagation.
     fn compute_internal(&self, inputs: &[f32]) -> Result<Vec<f32>, GPError> {
+        let port = match self.inputs.as_slice() {
+            [port] => port,
+            ports => return Err(GPError::InferenceError(format!(
+                "compute() needs a single-input network, this one has {}; use compute_named", ports.len()
+            ))),
+        };
         let num_inputs = inputs.len();
-        let mut input_buffer = self.input_tensor.borrow_mut();
+        let expected = port.buffer.borrow().len();
+        if num_inputs != expected {
+            return Err(GPError::ArrayLengthMismatch { expected, found: num_inputs });
+        }
 
-        // ── Optimized 1D Convolution ───────────────────────────────────────────
-        // Apply kernel in-place to the pre-allocated input_buffer to avoid Vec alloc.
         {
+            let mut input_buffer = port.buffer.borrow_mut();
+
+            // ── Optimized 1D Convolution ───────────────────────────────────────
+            // Apply kernel in-place to the pre-allocated input_buffer to avoid Vec alloc.
             let slice = input_buffer
                 .as_slice_mut()
                 .map_err(|e| GPError::TensorError(e.to_string()))?;
@@ -265,25 +349,63 @@ impl NeuralBrain {
             }
         }
 
+        self.execute()?;
+
+        // ── Concatenate Output Heads ───────────────────────────────────────────
+        let mut result = Vec::new();
+        for port in &self.outputs {
+            let buffer = port.buffer.borrow();
+            let slice = buffer
+                .as_slice()
+                .map_err(|e| GPError::TensorError(format!("Failed to get CPU view: {}", e)))?;
+            result.extend_from_slice(slice);
+        }
+        Ok(result)
+    }
+
+    /// Named compute logic (separated for RAII guard scope)
+    fn compute_named_internal(&self, feeds: &HashMap<String, Vec<f32>>) -> Result<BTreeMap<String, Vec<f32>>, GPError> {
+        if let Some(name) = feeds.keys().find(|name| !self.inputs.iter().any(|p| &p.name == *name)) {
+            return Err(GPError::InferenceError(format!("Unknown input '{}'", name)));
+        }
+        for port in &self.inputs {
+            let values = feeds.get(&port.name)
+                .ok_or_else(|| GPError::InferenceError(format!("Missing input '{}'", port.name)))?;
+            let mut buffer = port.buffer.borrow_mut();
+            let slice = buffer
+                .as_slice_mut()
+                .map_err(|e| GPError::TensorError(e.to_string()))?;
+            if values.len() != slice.len() {
+                return Err(GPError::ArrayLengthMismatch { expected: slice.len(), found: values.len() });
+            }
+            slice.copy_from_slice(values);
+        }
+
+        self.execute()?;
+
+        self.outputs.iter()
+            .map(|port| Ok((port.name.clone(), port.buffer.borrow().to_vec()?)))
+            .collect()
+    }
+
+    /// Injects the input buffers, executes every node the outputs depend on,
+    /// updates recurrent states and copies the results into the output buffers
+    fn execute(&self) -> Result<(), GPError> {
         let mut graph = self.graph.borrow_mut();
         graph.sync_params().map_err(|e: GPError| GPError::BackendError(e.to_string()))?;
 
-        // ── Inject input into graph ───────────────────────────────────────────
-        {
-            let nodes = graph.nodes_mut();
-            if let Some(gran_prix::graph::Node::Input(ref mut t)) = nodes.get_mut(self.input_node) {
-                t.copy_from(&input_buffer)
-                    .map_err(|e| GPError::TensorError(e.to_string()))?;
-            }
+        // ── Inject inputs into graph ──────────────────────────────────────────
+        for port in &self.inputs {
+            graph.feed(NodeId(port.node), &port.buffer.borrow())?;
         }
 
-        let output_id = gran_prix::NodeId(self.output_node);
-        let order = graph
-            .topological_sort(output_id)
+        let targets: Vec<NodeId> = self.outputs.iter().map(|port| NodeId(port.node)).collect();
+        let plan = graph
+            .plan(&targets)
             .map_err(|e: GPError| GPError::BackendError(e.to_string()))?;
 
         // ── Execute Graph ──────────────────────────────────────────────────────
-        for node_id in order {
+        for &node_id in plan.order() {
             if self.magic != BRAIN_MAGIC {
                 return Err(GPError::CorruptedMemory { expected: BRAIN_MAGIC, found: self.magic });
             }
@@ -293,33 +415,30 @@ impl NeuralBrain {
                 .map_err(|e| GPError::BackendError(format!("Node {} execution error: {}", node_id.0, e)))?;
         }
         
-        // ── Extract Temporal States for RNN/GRU ────────────────────────────────
+        // ── Extract Temporal States for RNN/GRU/LSTM ───────────────────────────
         let mut layers = self.layers.borrow_mut();
         for layer in layers.iter_mut() {
-            if let Some(state_node_id) = layer.state_node() {
-                if let Some(Some(ref tensor)) = graph.values().get(state_node_id.0) {
-                    let t: Tensor = tensor.clone();
-                    layer.update_state(t);
-                }
+            let states: Option<Vec<Tensor>> = layer.state_nodes().iter()
+                .map(|id| graph.values().get(id.0).cloned().flatten())
+                .collect();
+            if let Some(states) = states {
+                layer.update_states(states);
             }
         }
 
-        // ── Extract Output Efficiently ─────────────────────────────────────────
+        // ── Extract Outputs Efficiently ────────────────────────────────────────
         let values = graph.values();
-        let result_tensor = values
-            .get(self.output_node)
-            .and_then(|t: &Option<Tensor>| t.as_ref())
-            .ok_or_else(|| GPError::InferenceError("Output not found".to_string()))?;
-
-        let mut out_buffer = self.output_tensor.borrow_mut();
-        out_buffer.copy_from(result_tensor)
-            .map_err(|e| GPError::TensorError(e.to_string()))?;
+        for port in &self.outputs {
+            let result_tensor = values
+                .get(port.node)
+                .and_then(|t: &Option<Tensor>| t.as_ref())
+                .ok_or_else(|| GPError::InferenceError(format!("Output '{}' not found", port.name)))?;
 
-        let slice = out_buffer
-            .as_slice()
-            .map_err(|e| GPError::TensorError(format!("Failed to get CPU view: {}", e)))?;
+            port.buffer.borrow_mut().copy_from(result_tensor)
+                .map_err(|e| GPError::TensorError(e.to_string()))?;
+        }
 
-        Ok(slice.to_vec())
+        Ok(())
     }
 
     /// Reset cached values and gradients in the graph
@@ -499,3 +618,58 @@ struct NodeSnapshot {
     name: String,
     value: Option<Vec<f32>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TWO_HEADS: &str = r#"{
+        "inputs": [
+            {"name": "sensors", "shape": [3]},
+            {"name": "goal", "shape": [2]}
+        ],
+        "nodes": [
+            {"id": "joined", "type": "concat", "inputs": ["sensors", "goal"]},
+            {"id": "hidden", "type": "linear", "in_features": 5, "out_features": 4},
+            {"id": "policy", "type": "linear", "in_features": 4, "out_features": 2, "inputs": ["hidden"]},
+            {"id": "value", "type": "linear", "in_features": 4, "out_features": 1, "inputs": ["hidden"]}
+        ],
+        "outputs": ["policy", "value"]
+    }"#;
+
+    #[test]
+    fn test_named_inputs_and_outputs() {
+        let net = NetworkDef::from_json(TWO_HEADS).unwrap();
+        let brain = NeuralBrain::build(&net, 0).unwrap();
+        assert_eq!(brain.input_names(), vec!["sensors", "goal"]);
+        assert_eq!(brain.output_names(), vec!["policy", "value"]);
+
+        let feeds = HashMap::from([
+            ("sensors".to_string(), vec![1.0, 0.5, -0.3]),
+            ("goal".to_string(), vec![0.2, -0.1]),
+        ]);
+        let outputs = brain.compute_named_typed(&feeds).unwrap();
+        assert_eq!(outputs["policy"].len(), 2);
+        assert_eq!(outputs["value"].len(), 1);
+
+        // Missing and mis-sized inputs are rejected
+        let partial = HashMap::from([("sensors".to_string(), vec![1.0, 0.5, -0.3])]);
+        assert!(brain.compute_named_typed(&partial).is_err());
+        let wrong = HashMap::from([
+            ("sensors".to_string(), vec![1.0]),
+            ("goal".to_string(), vec![0.2, -0.1]),
+        ]);
+        assert!(brain.compute_named_typed(&wrong).is_err());
+
+        // The positional API needs a single input
+        assert!(brain.compute_typed(&[1.0, 0.5, -0.3]).is_err());
+    }
+
+    #[test]
+    fn test_single_input_concatenates_heads() {
+        let net = NetworkDef::mlp(3, &[4], 2, ActivationType::ReLU, Some(ActivationType::Sigmoid));
+        let brain = NeuralBrain::build(&net, 1).unwrap();
+        assert_eq!(brain.compute_typed(&[1.0, 0.5, -0.3]).unwrap().len(), 2);
+        assert!(brain.compute_typed(&[1.0]).is_err());
+    }
+}
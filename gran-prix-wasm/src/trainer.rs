@@ -18,7 +18,6 @@ pub struct Trainer {
     input_node: usize,
     output_node: usize,
     input_dim: usize,
-    input_tensor: RefCell<Tensor>,
     optimizer: RefCell<gran_prix::optim::Adam>,
 }
 
@@ -51,7 +50,6 @@ impl Trainer {
             input_node,
             output_node,
             input_dim,
-            input_tensor: RefCell::new(Tensor::new_zeros(&[1, input_dim])),
             optimizer: RefCell::new(gran_prix::optim::Adam::new(0.01)),
         })
     }
@@ -82,7 +80,6 @@ impl Trainer {
             input_node: compiled.input_node.0,
             output_node: compiled.output_node.0,
             input_dim,
-            input_tensor: RefCell::new(Tensor::new_zeros(&[1, input_dim])),
             optimizer: RefCell::new(gran_prix::optim::Adam::new(0.01)),
         })
     }
@@ -103,7 +100,6 @@ impl Trainer {
             input_node: compiled.input_node.0,
             output_node: compiled.output_node.0,
             input_dim,
-            input_tensor: RefCell::new(Tensor::new_zeros(&[1, input_dim])),
             optimizer: RefCell::new(gran_prix::optim::Adam::new(0.01)),
         })
     }
@@ -124,6 +120,10 @@ impl Trainer {
 
     // ── Training ───────────────────────────────────────────────────────────
 
+    /// Runs one optimizer step on a mini-batch of `targets.len()` samples.
+    ///
+    /// `inputs` holds the samples row by row; the whole batch goes through
+    /// the graph in a single `[batch, input_dim]` forward and backward pass.
     pub fn train_batch(&self, inputs: Vec<f32>, targets: Vec<f32>, lr: f32) -> Result<f32, JsValue> {
         let mut graph = self.graph.borrow_mut();
 
@@ -133,46 +133,27 @@ impl Trainer {
             return Err(JsValue::from_str("Input vector size mismatch"));
         }
 
-        let mut total_loss = 0.0;
-        let target = NodeId(self.output_node);
-        let order = graph.topological_sort(target).map_err(js_err)?;
-
-        graph.clear_gradients();
+        let batch = Tensor::from_shape_vec(&[batch_size, self.input_dim], inputs).map_err(js_err)?;
+        graph.feed(NodeId(self.input_node), &batch).map_err(js_err)?;
 
-        for i in 0..batch_size {
-            // Inject input
-            {
-                let mut input_buffer = self.input_tensor.borrow_mut();
-                if let Ok(slice) = input_buffer.as_slice_mut() {
-                    let start = i * self.input_dim;
-                    for d in 0..self.input_dim {
-                        slice[d] = inputs[start + d];
-                    }
-                }
-                if let Some(gran_prix::graph::Node::Input(ref mut t)) = graph.nodes_mut().get_mut(self.input_node) {
-                    t.copy_from(&input_buffer).map_err(js_err)?;
-                }
-            }
-
-            let result = graph.execute_with_order(&order, target).map_err(js_err)?;
-
-            let target_tensor = Tensor::from_shape_vec(&[1, 1], vec![targets[i]]).unwrap();
-            let loss_fn = gran_prix::loss::BCEWithLogits;
-
-            let mut grad = loss_fn.gradient(&result, &target_tensor).map_err(js_err)?;
-            grad.map_inplace(|v| *v /= batch_size as f32).unwrap();
+        let target = NodeId(self.output_node);
+        let result = graph.execute(target).map_err(js_err)?;
 
-            total_loss += loss_fn.calculate(&result, &target_tensor).map_err(js_err)?;
+        // BCEWithLogits averages over the batch, so this is the mean gradient.
+        let target_tensor = Tensor::from_shape_vec(&[batch_size, 1], targets).map_err(js_err)?;
+        let loss_fn = gran_prix::loss::BCEWithLogits;
+        let grad = loss_fn.gradient(&result, &target_tensor).map_err(js_err)?;
+        let loss = loss_fn.calculate(&result, &target_tensor).map_err(js_err)?;
 
-            graph.backward(NodeId(self.output_node), grad).map_err(js_err)?;
-        }
+        graph.clear_gradients();
+        graph.backward(target, grad).map_err(js_err)?;
 
         use gran_prix::optim::Optimizer;
         let mut opt = self.optimizer.borrow_mut();
         opt.lr = lr;
         opt.step_graph(&mut graph).map_err(js_err)?;
 
-        Ok(total_loss / batch_size as f32)
+        Ok(loss)
     }
 
     pub fn train_step(&self, features: Vec<f32>, target_val: f32, lr: f32) -> Result<f32, JsValue> {
@@ -186,16 +167,8 @@ impl Trainer {
             return Err(JsValue::from_str("Features dimension mismatch"));
         }
         let mut graph = self.graph.borrow_mut();
-        {
-            let mut input_buffer = self.input_tensor.borrow_mut();
-            let slice = input_buffer.as_slice_mut().map_err(js_err)?;
-            for d in 0..self.input_dim {
-                slice[d] = features[d];
-            }
-            if let Some(gran_prix::graph::Node::Input(ref mut t)) = graph.nodes_mut().get_mut(self.input_node) {
-                t.copy_from(&input_buffer).map_err(js_err)?;
-            }
-        }
+        let sample = Tensor::from_shape_vec(&[1, self.input_dim], features).map_err(js_err)?;
+        graph.feed(NodeId(self.input_node), &sample).map_err(js_err)?;
 
         let result = graph.execute(NodeId(self.output_node)).map_err(js_err)?;
         let slice = result.as_slice().map_err(js_err)?;
@@ -203,14 +176,12 @@ impl Trainer {
         Ok(1.0 / (1.0 + (-logit).exp()))
     }
 
+    /// Evaluates the model on a `resolution × resolution` grid over
+    /// `[-1, 1]²`, passing each point through `feature_map` first.
+    ///
+    /// The whole grid is evaluated as one batch.
     pub fn get_decision_boundary(&self, resolution: usize, feature_map: js_sys::Function) -> Result<Vec<f32>, JsValue> {
-        let mut graph = self.graph.borrow_mut();
-        let target = NodeId(self.output_node);
-        graph.sync_params().map_err(js_err)?;
-
-        let order = graph.topological_sort(target).map_err(js_err)?;
-
-        let mut results = Vec::with_capacity(resolution * resolution);
+        let mut features = Vec::with_capacity(resolution * resolution * self.input_dim);
         for j in 0..resolution {
             for i in 0..resolution {
                 let x = (i as f32 / resolution as f32) * 2.0 - 1.0;
@@ -220,27 +191,23 @@ impl Trainer {
                 let js_y = JsValue::from_f64(y as f64);
                 let expanded = feature_map.call2(&JsValue::NULL, &js_x, &js_y)?
                     .dyn_into::<js_sys::Float32Array>()?;
-                let features = expanded.to_vec();
-
-                {
-                    let mut input_buffer = self.input_tensor.borrow_mut();
-                    if let Ok(slice) = input_buffer.as_slice_mut() {
-                        for d in 0..self.input_dim {
-                            slice[d] = features[d];
-                        }
-                    }
-                    if let Some(gran_prix::graph::Node::Input(ref mut t)) = graph.nodes_mut().get_mut(self.input_node) {
-                        t.copy_from(&input_buffer).map_err(js_err)?;
-                    }
+                let point = expanded.to_vec();
+                if point.len() < self.input_dim {
+                    return Err(JsValue::from_str("Features dimension mismatch"));
                 }
-
-                let result = graph.execute_with_order(&order, target).map_err(js_err)?;
-                let slice = result.as_slice().map_err(js_err)?;
-                let logit = slice[0];
-                results.push(1.0 / (1.0 + (-logit).exp()));
+                features.extend_from_slice(&point[..self.input_dim]);
             }
         }
-        Ok(results)
+
+        let mut graph = self.graph.borrow_mut();
+        graph.sync_params().map_err(js_err)?;
+        let grid = Tensor::from_shape_vec(&[resolution * resolution, self.input_dim], features)
+            .map_err(js_err)?;
+        graph.feed(NodeId(self.input_node), &grid).map_err(js_err)?;
+
+        let result = graph.execute(NodeId(self.output_node)).map_err(js_err)?;
+        let logits = result.as_slice().map_err(js_err)?;
+        Ok(logits.iter().map(|&logit| 1.0 / (1.0 + (-logit).exp())).collect())
     }
 }
 
@@ -278,6 +245,11 @@ struct LegacyParams {
     output_size: Option<usize>,
     hidden_size: Option<usize>,
     activation_type: Option<String>,
+    /// Slope for LeakyReLU, alpha for ELU.
+    alpha: Option<f32>,
+    /// Clamping bounds for HardTanh.
+    min: Option<f32>,
+    max: Option<f32>,
 }
 
 #[derive(Deserialize)]
@@ -331,6 +303,17 @@ fn convert_legacy_architecture(json: &str) -> Result<NetworkDef, JsValue> {
                     "sigmoid" => ActivationType::Sigmoid,
                     "tanh" => ActivationType::Tanh,
                     "softmax" => ActivationType::Softmax,
+                    "leakyrelu" | "leaky_relu" => ActivationType::LeakyReLU {
+                        slope: layer.params.alpha.unwrap_or(0.01),
+                    },
+                    "elu" => ActivationType::ELU { alpha: layer.params.alpha.unwrap_or(1.0) },
+                    "gelu" => ActivationType::GELU,
+                    "silu" | "swish" => ActivationType::SiLU,
+                    "softplus" => ActivationType::Softplus,
+                    "hardtanh" => ActivationType::HardTanh {
+                        min: layer.params.min.unwrap_or(-1.0),
+                        max: layer.params.max.unwrap_or(1.0),
+                    },
                     _ => return Err(JsValue::from_str(&format!("Unknown activation: {}", act_str))),
                 };
                 layer_defs.push(LayerDef::Activation { function });
@@ -349,6 +332,13 @@ fn convert_legacy_architecture(json: &str) -> Result<NetworkDef, JsValue> {
                 layer_defs.push(LayerDef::Gru { input_size: in_s, hidden_size: h_s });
                 current_dim = h_s;
             }
+            "lstm" => {
+                let in_s = layer.params.input_size.unwrap_or(current_dim);
+                let h_s = layer.params.hidden_size
+                    .ok_or_else(|| JsValue::from_str("LSTM layer missing hiddenSize"))?;
+                layer_defs.push(LayerDef::Lstm { input_size: in_s, hidden_size: h_s });
+                current_dim = h_s;
+            }
             "output" | "dropout" | "batchnorm" | "input" => {
                 // Skip — these don't produce computation nodes
             }
@@ -371,3 +361,37 @@ fn convert_legacy_architecture(json: &str) -> Result<NetworkDef, JsValue> {
 
     Ok(NetworkDef::new(arch.input_dim, layer_defs))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activation_arch(params: &str) -> String {
+        format!(r#"{{
+            "inputDim": 2, "outputDim": 3,
+            "layers": [
+                {{"id": "in", "type": "input", "params": {{}}}},
+                {{"id": "fc", "type": "linear", "params": {{"outputSize": 3}}}},
+                {{"id": "act", "type": "activation", "params": {}}}
+            ],
+            "connections": [{{"from": "in", "to": "fc"}}, {{"from": "fc", "to": "act"}}]
+        }}"#, params)
+    }
+
+    fn activation(params: &str) -> ActivationType {
+        let def = convert_legacy_architecture(&activation_arch(params)).unwrap();
+        match &def.layers[1] {
+            LayerDef::Activation { function } => function.clone(),
+            other => panic!("expected an activation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_legacy_hardtanh_reads_bounds() {
+        assert_eq!(
+            activation(r#"{"activationType": "hardtanh", "min": -0.5, "max": 2.0}"#),
+            ActivationType::HardTanh { min: -0.5, max: 2.0 },
+        );
+        assert_eq!(activation(r#"{"activationType": "hardtanh"}"#), ActivationType::hardtanh());
+    }
+}
@@ -1,6 +1,10 @@
-use crate::backend::Backend;
+use crate::backend::{
+    Backend, sigmoid_scalar, leaky_relu_scalar, elu_scalar, gelu_scalar, gelu_grad_scalar,
+    silu_scalar, silu_grad_scalar, softplus_scalar,
+};
 use crate::{Tensor, GPResult, GPError};
-use ndarray::Zip;
+use crate::tensor::{broadcast_shapes, reduced_shape, reduction_axes};
+use ndarray::{IxDyn, Zip};
 
 #[derive(Debug)]
 pub struct CPUBackend;
@@ -375,53 +379,19 @@ impl Backend for CPUBackend {
     }
 
     fn add(&self, a: &Tensor, b: &Tensor) -> GPResult<Tensor> {
-        Ok((a.try_view()?.to_owned() + &b.try_view()?).into_dyn().into())
+        broadcast_binary(a, b, |x, y| x + y)
     }
 
     fn add_into(&self, a: &Tensor, b: &Tensor, out: &mut Tensor) -> GPResult<()> {
-        let a_view = a.try_view()?;
-        let b_view = b.try_view()?;
-        let mut out_view = out.try_view_mut()?;
-        
-        if a_view.shape() != b_view.shape() || a_view.shape() != out_view.shape() {
-             return Err(GPError::IncompatibleShapes { 
-                expected: a.shape().to_vec(), 
-                found: b.shape().to_vec(),
-                exp_len: a.len(),
-                found_len: b.len(),
-            });
-        }
-
-        Zip::from(&mut out_view).and(&a_view).and(&b_view).for_each(|o, &av, &bv| {
-            *o = av + bv;
-        });
-
-        Ok(())
+        broadcast_binary_into(a, b, out, |x, y| x + y)
     }
 
     fn mul(&self, a: &Tensor, b: &Tensor) -> GPResult<Tensor> {
-        Ok((a.try_view()?.to_owned() * &b.try_view()?).into_dyn().into())
+        broadcast_binary(a, b, |x, y| x * y)
     }
 
     fn mul_into(&self, a: &Tensor, b: &Tensor, out: &mut Tensor) -> GPResult<()> {
-        let a_view = a.try_view()?;
-        let b_view = b.try_view()?;
-        let mut out_view = out.try_view_mut()?;
-        
-        if a_view.shape() != b_view.shape() || a_view.shape() != out_view.shape() {
-             return Err(GPError::IncompatibleShapes { 
-                expected: a.shape().to_vec(), 
-                found: b.shape().to_vec(),
-                exp_len: a.len(),
-                found_len: b.len(),
-            });
-        }
-
-        Zip::from(&mut out_view).and(&a_view).and(&b_view).for_each(|o, &av, &bv| {
-            *o = av * bv;
-        });
-
-        Ok(())
+        broadcast_binary_into(a, b, out, |x, y| x * y)
     }
 
     fn mul_backward(&self, a: &Tensor, b: &Tensor, grad_output: &Tensor) -> GPResult<(Tensor, Tensor)> {
@@ -430,6 +400,66 @@ impl Backend for CPUBackend {
         Ok((grad_a, grad_b))
     }
 
+    fn sub(&self, a: &Tensor, b: &Tensor) -> GPResult<Tensor> {
+        broadcast_binary(a, b, |x, y| x - y)
+    }
+
+    fn div(&self, a: &Tensor, b: &Tensor) -> GPResult<Tensor> {
+        broadcast_binary(a, b, |x, y| x / y)
+    }
+
+    fn div_backward(&self, a: &Tensor, b: &Tensor, grad_output: &Tensor) -> GPResult<(Tensor, Tensor)> {
+        let grad_a = self.div(grad_output, b)?;
+        // -g * a / b^2 == -(g / b) * (a / b)
+        let a_over_b = self.div(a, b)?;
+        let grad_b = broadcast_binary(&grad_a, &a_over_b, |g, q| -g * q)?;
+        Ok((grad_a, grad_b))
+    }
+
+    fn neg(&self, x: &Tensor) -> GPResult<Tensor> {
+        Ok(x.mapv(|v: f32| -v))
+    }
+
+    fn exp(&self, x: &Tensor) -> GPResult<Tensor> {
+        Ok(x.mapv(|v: f32| v.exp()))
+    }
+
+    fn log(&self, x: &Tensor) -> GPResult<Tensor> {
+        Ok(x.mapv(|v: f32| v.ln()))
+    }
+
+    fn sqrt(&self, x: &Tensor) -> GPResult<Tensor> {
+        Ok(x.mapv(|v: f32| v.sqrt()))
+    }
+
+    fn abs(&self, x: &Tensor) -> GPResult<Tensor> {
+        Ok(x.mapv(|v: f32| v.abs()))
+    }
+
+    fn pow(&self, x: &Tensor, exponent: f32) -> GPResult<Tensor> {
+        Ok(x.mapv(|v: f32| v.powf(exponent)))
+    }
+
+    fn clamp(&self, x: &Tensor, min: f32, max: f32) -> GPResult<Tensor> {
+        Ok(x.mapv(|v: f32| v.max(min).min(max)))
+    }
+
+    fn abs_backward(&self, input: &Tensor, grad_output: &Tensor) -> GPResult<Tensor> {
+        let mut grad = grad_output.try_view()?.to_owned();
+        Zip::from(grad.view_mut()).and(input.try_view()?).for_each(|g, &i| {
+            if i < 0.0 { *g = -*g; } else if i == 0.0 { *g = 0.0; }
+        });
+        Ok(grad.into_dyn().into())
+    }
+
+    fn clamp_backward(&self, input: &Tensor, grad_output: &Tensor, min: f32, max: f32) -> GPResult<Tensor> {
+        let mut grad = grad_output.try_view()?.to_owned();
+        Zip::from(grad.view_mut()).and(input.try_view()?).for_each(|g, &i| {
+            if i < min || i > max { *g = 0.0; }
+        });
+        Ok(grad.into_dyn().into())
+    }
+
     fn sigmoid(&self, x: &Tensor) -> GPResult<Tensor> {
         let mut res = x.clone();
         self.sigmoid_inplace(&mut res)?;
@@ -467,10 +497,48 @@ impl Backend for CPUBackend {
         Ok(())
     }
 
+    fn leaky_relu(&self, x: &Tensor, slope: f32) -> GPResult<Tensor> {
+        Ok(x.mapv(|v: f32| leaky_relu_scalar(v, slope)))
+    }
+
+    fn elu(&self, x: &Tensor, alpha: f32) -> GPResult<Tensor> {
+        Ok(x.mapv(|v: f32| elu_scalar(v, alpha)))
+    }
+
+    fn gelu(&self, x: &Tensor) -> GPResult<Tensor> {
+        Ok(x.mapv(gelu_scalar))
+    }
+
+    fn silu(&self, x: &Tensor) -> GPResult<Tensor> {
+        Ok(x.mapv(silu_scalar))
+    }
+
+    fn softplus(&self, x: &Tensor) -> GPResult<Tensor> {
+        Ok(x.mapv(softplus_scalar))
+    }
+
+    fn leaky_relu_backward(&self, input: &Tensor, grad_output: &Tensor, slope: f32) -> GPResult<Tensor> {
+        activation_backward(input, grad_output, |x| if x > 0.0 { 1.0 } else { slope })
+    }
+
+    fn elu_backward(&self, input: &Tensor, grad_output: &Tensor, alpha: f32) -> GPResult<Tensor> {
+        activation_backward(input, grad_output, |x| if x > 0.0 { 1.0 } else { alpha * x.exp() })
+    }
+
+    fn gelu_backward(&self, input: &Tensor, grad_output: &Tensor) -> GPResult<Tensor> {
+        activation_backward(input, grad_output, gelu_grad_scalar)
+    }
+
+    fn silu_backward(&self, input: &Tensor, grad_output: &Tensor) -> GPResult<Tensor> {
+        activation_backward(input, grad_output, silu_grad_scalar)
+    }
+
+    fn softplus_backward(&self, input: &Tensor, grad_output: &Tensor) -> GPResult<Tensor> {
+        activation_backward(input, grad_output, sigmoid_scalar)
+    }
+
     fn add_relu(&self, a: &Tensor, b: &Tensor) -> GPResult<Tensor> {
-        let mut res = a.try_view()?.to_owned() + &b.try_view()?;
-        res.map_inplace(|v| if *v < 0.0 { *v = 0.0 });
-        Ok(res.into_dyn().into())
+        broadcast_binary(a, b, |x, y| (x + y).max(0.0))
     }
 
     fn update_parameter(&self, param: &mut Tensor, grad: &Tensor, lr: f32) -> GPResult<()> {
@@ -555,4 +623,162 @@ impl Backend for CPUBackend {
         Ok(curr.into_dyn().into())
     }
 
+    fn reduce_max(&self, input: &Tensor, axes: &[usize], keep_dims: bool) -> GPResult<Tensor> {
+        let groups = ReductionGroups::new(input, axes)?;
+        let data = groups.values(input)?;
+        let out: Vec<f32> = data.chunks(groups.inner)
+            .map(|g| g[first_max(g)])
+            .collect();
+        Tensor::from_shape_vec(&reduced_shape(input.shape(), &groups.axes, keep_dims), out)
+    }
+
+    fn reduce_max_backward(&self, input: &Tensor, axes: &[usize], grad_output: &Tensor) -> GPResult<Tensor> {
+        let groups = ReductionGroups::new(input, axes)?;
+        let data = groups.values(input)?;
+        let go = grad_output.try_view()?.iter().copied().collect::<Vec<f32>>();
+        if go.len() != data.len() / groups.inner {
+            return Err(GPError::IncompatibleShapes {
+                expected: reduced_shape(input.shape(), &groups.axes, true),
+                found: grad_output.shape().to_vec(),
+                exp_len: data.len() / groups.inner,
+                found_len: go.len(),
+            });
+        }
+
+        // Build the gradient in permuted layout, then scatter back.
+        let mut permuted = vec![0.0f32; data.len()];
+        for (g, (chunk, out)) in data.chunks(groups.inner).zip(permuted.chunks_mut(groups.inner)).enumerate() {
+            out[first_max(chunk)] = go[g];
+        }
+        let permuted_shape: Vec<usize> = groups.perm.iter().map(|&p| input.shape()[p]).collect();
+        let permuted = ndarray::ArrayD::from_shape_vec(IxDyn(&permuted_shape), permuted)
+            .map_err(|e| GPError::TensorError(e.to_string()))?;
+        let mut inverse = vec![0; groups.perm.len()];
+        for (i, &p) in groups.perm.iter().enumerate() {
+            inverse[p] = i;
+        }
+        Ok(permuted.permuted_axes(inverse).as_standard_layout().into_owned().into())
+    }
+
+    fn argmax(&self, input: &Tensor, axis: usize, keep_dims: bool) -> GPResult<Tensor> {
+        let groups = ReductionGroups::new(input, &[axis])?;
+        let data = groups.values(input)?;
+        let out: Vec<f32> = data.chunks(groups.inner)
+            .map(|g| first_max(g) as f32)
+            .collect();
+        Tensor::from_shape_vec(&reduced_shape(input.shape(), &groups.axes, keep_dims), out)
+    }
+
+    fn broadcast_to(&self, x: &Tensor, shape: &[usize]) -> GPResult<Tensor> {
+        let view = x.try_view()?;
+        let bc = view.broadcast(IxDyn(shape)).ok_or_else(|| GPError::IncompatibleShapes {
+            expected: shape.to_vec(),
+            found: x.shape().to_vec(),
+            exp_len: shape.iter().product(),
+            found_len: x.len(),
+        })?;
+        Ok(bc.to_owned().into())
+    }
+}
+
+// ── Activation Helpers ─────────────────────────────────────────────────────
+
+/// `dL/dX = dL/dY * f'(X)` for an element-wise activation with derivative `df`.
+fn activation_backward(input: &Tensor, grad_output: &Tensor, df: impl Fn(f32) -> f32 + Send + Sync) -> GPResult<Tensor> {
+    let mut grad = grad_output.try_view()?.to_owned();
+    #[cfg(feature = "rayon")]
+    Zip::from(grad.view_mut()).and(input.try_view()?).par_for_each(|g, &x| {
+        *g *= df(x);
+    });
+    #[cfg(not(feature = "rayon"))]
+    Zip::from(grad.view_mut()).and(input.try_view()?).for_each(|g, &x| {
+        *g *= df(x);
+    });
+    Ok(grad.into_dyn().into())
+}
+
+// ── Reduction Helpers ──────────────────────────────────────────────────────
+
+/// Axis permutation that moves the reduced axes last, so that iterating the
+/// permuted view in logical order yields each reduction group contiguously.
+struct ReductionGroups {
+    axes: Vec<usize>,
+    perm: Vec<usize>,
+    /// Number of elements per group (product of the reduced dims).
+    inner: usize,
+}
+
+impl ReductionGroups {
+    fn new(input: &Tensor, axes: &[usize]) -> GPResult<Self> {
+        let shape = input.shape();
+        let axes = reduction_axes(shape, axes)?;
+        let mut perm: Vec<usize> = (0..shape.len()).filter(|a| !axes.contains(a)).collect();
+        perm.extend_from_slice(&axes);
+        let inner = axes.iter().map(|&a| shape[a]).product();
+        if inner == 0 {
+            return Err(GPError::TensorError(format!(
+                "Cannot take max over empty axes {:?} of shape {:?}", axes, shape
+            )));
+        }
+        Ok(Self { axes, perm, inner })
+    }
+
+    /// Input values in permuted order: `len / inner` groups of `inner` elements.
+    fn values(&self, input: &Tensor) -> GPResult<Vec<f32>> {
+        Ok(input.try_view()?.permuted_axes(self.perm.clone()).iter().copied().collect())
+    }
+}
+
+/// Index of the first maximum in a non-empty slice (NaN-propagating).
+fn first_max(values: &[f32]) -> usize {
+    let mut best = 0;
+    for (i, &v) in values.iter().enumerate().skip(1) {
+        if v > values[best] || (v.is_nan() && !values[best].is_nan()) {
+            best = i;
+        }
+    }
+    best
+}
+
+// ── Broadcasting Helpers ───────────────────────────────────────────────────
+
+/// Element-wise binary op with NumPy-style broadcasting of both operands.
+fn broadcast_binary(a: &Tensor, b: &Tensor, f: fn(f32, f32) -> f32) -> GPResult<Tensor> {
+    let shape = broadcast_shapes(a.shape(), b.shape())?;
+    let mut out = Tensor::new_zeros(&shape);
+    broadcast_binary_into(a, b, &mut out, f)?;
+    Ok(out)
+}
+
+/// Writes `f(a, b)` into `out`, broadcasting `a` and `b` to `out`'s shape.
+///
+/// `out` must already have exactly the broadcast shape of `a` and `b`.
+fn broadcast_binary_into(a: &Tensor, b: &Tensor, out: &mut Tensor, f: fn(f32, f32) -> f32) -> GPResult<()> {
+    let shape = broadcast_shapes(a.shape(), b.shape())?;
+    if out.shape() != shape.as_slice() {
+        return Err(GPError::IncompatibleShapes {
+            expected: shape.clone(),
+            found: out.shape().to_vec(),
+            exp_len: shape.iter().product(),
+            found_len: out.len(),
+        });
+    }
+
+    let a_view = a.try_view()?;
+    let b_view = b.try_view()?;
+    let dim = IxDyn(&shape);
+    // Both broadcasts are infallible here: `shape` was derived from the operands.
+    let a_bc = a_view.broadcast(dim.clone()).ok_or_else(|| GPError::TensorError(
+        format!("Cannot broadcast {:?} to {:?}", a.shape(), shape)
+    ))?;
+    let b_bc = b_view.broadcast(dim).ok_or_else(|| GPError::TensorError(
+        format!("Cannot broadcast {:?} to {:?}", b.shape(), shape)
+    ))?;
+    let mut out_view = out.try_view_mut()?;
+
+    Zip::from(&mut out_view).and(&a_bc).and(&b_bc).for_each(|o, &av, &bv| {
+        *o = f(av, bv);
+    });
+
+    Ok(())
 }
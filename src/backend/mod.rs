@@ -46,6 +46,33 @@ pub trait Backend: Send + Sync + std::fmt::Debug {
     fn mul_into(&self, a: &Tensor, b: &Tensor, out: &mut Tensor) -> GPResult<()>;
     fn mul_backward(&self, a: &Tensor, b: &Tensor, grad_output: &Tensor) -> GPResult<(Tensor, Tensor)>;
 
+    /// Element-wise `a - b` with broadcasting.
+    fn sub(&self, a: &Tensor, b: &Tensor) -> GPResult<Tensor>;
+
+    /// Element-wise `a / b` with broadcasting.
+    fn div(&self, a: &Tensor, b: &Tensor) -> GPResult<Tensor>;
+
+    /// Div backward: returns (grad_a, grad_b) at the broadcast shape.
+    /// dL/dA = dL/dY / B, dL/dB = -dL/dY * A / B^2
+    fn div_backward(&self, a: &Tensor, b: &Tensor, grad_output: &Tensor) -> GPResult<(Tensor, Tensor)>;
+
+    fn neg(&self, x: &Tensor) -> GPResult<Tensor>;
+    fn exp(&self, x: &Tensor) -> GPResult<Tensor>;
+    /// Natural logarithm. Non-positive inputs yield NaN/-inf, as in IEEE 754.
+    fn log(&self, x: &Tensor) -> GPResult<Tensor>;
+    fn sqrt(&self, x: &Tensor) -> GPResult<Tensor>;
+    fn abs(&self, x: &Tensor) -> GPResult<Tensor>;
+    /// Element-wise `x^exponent`.
+    fn pow(&self, x: &Tensor, exponent: f32) -> GPResult<Tensor>;
+    /// Element-wise clamp into `[min, max]`.
+    fn clamp(&self, x: &Tensor, min: f32, max: f32) -> GPResult<Tensor>;
+
+    /// Abs Backward: dL/dX = dL/dY * sign(X), with sign(0) = 0
+    fn abs_backward(&self, input: &Tensor, grad_output: &Tensor) -> GPResult<Tensor>;
+
+    /// Clamp Backward: dL/dX = dL/dY where min <= X <= max, else 0
+    fn clamp_backward(&self, input: &Tensor, grad_output: &Tensor, min: f32, max: f32) -> GPResult<Tensor>;
+
     fn relu(&self, x: &Tensor) -> GPResult<Tensor>;
     fn relu_inplace(&self, x: &mut Tensor) -> GPResult<()>;
     fn sigmoid(&self, x: &Tensor) -> GPResult<Tensor>;
@@ -62,6 +89,32 @@ pub trait Backend: Send + Sync + std::fmt::Debug {
     /// Tanh Backward: dL/dX = dL/dY * (1 - Y^2)
     fn tanh_backward(&self, output: &Tensor, grad_output: &Tensor) -> GPResult<Tensor>;
 
+    /// Leaky ReLU: `x` for positive inputs, `slope * x` otherwise.
+    fn leaky_relu(&self, x: &Tensor, slope: f32) -> GPResult<Tensor>;
+    /// ELU: `x` for positive inputs, `alpha * (exp(x) - 1)` otherwise.
+    fn elu(&self, x: &Tensor, alpha: f32) -> GPResult<Tensor>;
+    /// GELU (tanh approximation).
+    fn gelu(&self, x: &Tensor) -> GPResult<Tensor>;
+    /// SiLU / Swish: `x * sigmoid(x)`.
+    fn silu(&self, x: &Tensor) -> GPResult<Tensor>;
+    /// Softplus: `ln(1 + exp(x))`, computed without overflow.
+    fn softplus(&self, x: &Tensor) -> GPResult<Tensor>;
+
+    /// Leaky ReLU Backward: dL/dX = dL/dY * (X > 0 ? 1 : slope)
+    fn leaky_relu_backward(&self, input: &Tensor, grad_output: &Tensor, slope: f32) -> GPResult<Tensor>;
+
+    /// ELU Backward: dL/dX = dL/dY * (X > 0 ? 1 : alpha * exp(X))
+    fn elu_backward(&self, input: &Tensor, grad_output: &Tensor, alpha: f32) -> GPResult<Tensor>;
+
+    /// GELU Backward: dL/dX = dL/dY * GELU'(X)
+    fn gelu_backward(&self, input: &Tensor, grad_output: &Tensor) -> GPResult<Tensor>;
+
+    /// SiLU Backward: dL/dX = dL/dY * s * (1 + X * (1 - s)), with s = sigmoid(X)
+    fn silu_backward(&self, input: &Tensor, grad_output: &Tensor) -> GPResult<Tensor>;
+
+    /// Softplus Backward: dL/dX = dL/dY * sigmoid(X)
+    fn softplus_backward(&self, input: &Tensor, grad_output: &Tensor) -> GPResult<Tensor>;
+
     /// Fused kernel: ReLU(A + B)
     /// Goal: Minimize memory bandwidth by doing addition and activation in one sweep.
     fn add_relu(&self, a: &Tensor, b: &Tensor) -> GPResult<Tensor>;
@@ -71,10 +124,70 @@ pub trait Backend: Send + Sync + std::fmt::Debug {
     /// Sums the tensor over the specified axes.
     fn reduce_sum(&self, input: &Tensor, axes: &[usize], keep_dims: bool) -> GPResult<Tensor>;
 
+    /// Max over the specified axes.
+    fn reduce_max(&self, input: &Tensor, axes: &[usize], keep_dims: bool) -> GPResult<Tensor>;
+
+    /// ReduceMax Backward: routes each reduced gradient to the (first) maximum
+    /// element of its group. `grad_output` has the keep_dims shape.
+    fn reduce_max_backward(&self, input: &Tensor, axes: &[usize], grad_output: &Tensor) -> GPResult<Tensor>;
+
+    /// Index of the (first) maximum along `axis`, returned as f32.
+    fn argmax(&self, input: &Tensor, axis: usize, keep_dims: bool) -> GPResult<Tensor>;
+
+    /// Broadcasts `x` to `shape` (NumPy rules), materializing the result.
+    fn broadcast_to(&self, x: &Tensor, shape: &[usize]) -> GPResult<Tensor>;
+
     /// Updates a parameter tensor using its gradient and a learning rate.
     /// Standard SGD update: param = param - lr * grad
     fn update_parameter(&self, param: &mut Tensor, grad: &Tensor, learning_rate: f32) -> GPResult<()>;
 }
 
+// ── Scalar Activation Kernels ──────────────────────────────────────────────
+//
+// Shared by `CPUBackend` and the graph's in-place forward path so both
+// compute bit-identical activations.
+
+/// `sqrt(2 / pi)`, used by the tanh approximation of GELU.
+pub(crate) const GELU_COEFF: f32 = 0.797_884_6;
+pub(crate) const GELU_CUBIC: f32 = 0.044_715;
+
+pub(crate) fn sigmoid_scalar(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+pub(crate) fn leaky_relu_scalar(x: f32, slope: f32) -> f32 {
+    if x > 0.0 { x } else { slope * x }
+}
+
+pub(crate) fn elu_scalar(x: f32, alpha: f32) -> f32 {
+    if x > 0.0 { x } else { alpha * x.exp_m1() }
+}
+
+pub(crate) fn gelu_scalar(x: f32) -> f32 {
+    let inner = GELU_COEFF * (x + GELU_CUBIC * x * x * x);
+    0.5 * x * (1.0 + inner.tanh())
+}
+
+pub(crate) fn gelu_grad_scalar(x: f32) -> f32 {
+    let inner = GELU_COEFF * (x + GELU_CUBIC * x * x * x);
+    let t = inner.tanh();
+    let d_inner = GELU_COEFF * (1.0 + 3.0 * GELU_CUBIC * x * x);
+    0.5 * (1.0 + t) + 0.5 * x * (1.0 - t * t) * d_inner
+}
+
+pub(crate) fn silu_scalar(x: f32) -> f32 {
+    x * sigmoid_scalar(x)
+}
+
+pub(crate) fn silu_grad_scalar(x: f32) -> f32 {
+    let s = sigmoid_scalar(x);
+    s * (1.0 + x * (1.0 - s))
+}
+
+/// `max(x, 0) + ln(1 + exp(-|x|))` — stable for large `|x|`.
+pub(crate) fn softplus_scalar(x: f32) -> f32 {
+    x.max(0.0) + (-x.abs()).exp().ln_1p()
+}
+
 pub mod cpu;
 pub mod cuda;
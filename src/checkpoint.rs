@@ -0,0 +1,437 @@
+//! Full training checkpoints: parameters, optimizer state and scheduler state.
+//!
+//! A [`TrainingCheckpoint`] bundles everything needed to kill a training run
+//! and resume it with bit-identical results:
+//!
+//! - parameter values, keyed by name (see [`ParamStore::state_dict`])
+//! - optimizer state (momentum buffers, Adam moments, step counter)
+//! - LR scheduler progress (epoch counter, current LR)
+//! - the execution engine's Dropout RNG counter
+//!
+//! # File format
+//!
+//! Checkpoints are stored in the safetensors container (see
+//! [`crate::safetensors`]). Parameters are written as `param/{name}`,
+//! optimizer buffers as `optim/{slot}/{name}`, and the remaining scalar state
+//! is a JSON document under the `gran_prix.checkpoint` metadata key.
+//!
+//! # Example
+//!
+//! ```rust
+//! use gran_prix::checkpoint::TrainingCheckpoint;
+//! use gran_prix::optim::{Adam, Optimizer};
+//! use gran_prix::scheduler::{StepLR, LRScheduler};
+//! use gran_prix::{ParamStore, Tensor};
+//!
+//! let mut params = ParamStore::new();
+//! params.register(Tensor::new_zeros(&[2, 2]), "w");
+//! let mut opt = Adam::new(0.01);
+//! let mut sched = StepLR::new(0.01, 10, 0.5);
+//!
+//! let ckpt = TrainingCheckpoint::capture(&params, &opt, Some(&sched)).unwrap();
+//! let bytes = ckpt.to_bytes().unwrap();
+//!
+//! let restored = TrainingCheckpoint::from_bytes(&bytes).unwrap();
+//! restored.restore(&mut params, &mut opt, Some(&mut sched)).unwrap();
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use serde::{Serialize, Deserialize};
+use crate::{GPError, GPResult};
+use crate::graph::Graph;
+use crate::optim::{Optimizer, OptimizerState};
+use crate::params::{ParamStore, StateDict};
+use crate::safetensors::{self, Metadata};
+use crate::scheduler::{LRScheduler, SchedulerState};
+
+/// Metadata key holding the JSON-encoded scalar state.
+const HEADER_KEY: &str = "gran_prix.checkpoint";
+
+/// Bumped whenever the on-disk layout changes incompatibly.
+const FORMAT_VERSION: u32 = 1;
+
+const PARAM_PREFIX: &str = "param/";
+const OPTIM_PREFIX: &str = "optim/";
+
+/// A complete snapshot of a training run.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TrainingCheckpoint {
+    /// Parameter values keyed by name.
+    pub params: StateDict,
+    /// Optimizer state (buffers keyed by parameter name).
+    pub optimizer: OptimizerState,
+    /// LR scheduler state, if a scheduler is in use.
+    pub scheduler: Option<SchedulerState>,
+    /// Execution engine Dropout RNG counter.
+    pub rng_counter: u64,
+}
+
+/// Scalar part of the checkpoint, stored as JSON in the safetensors metadata.
+#[derive(Serialize, Deserialize)]
+struct Header {
+    version: u32,
+    optimizer_kind: String,
+    optimizer_lr: f32,
+    optimizer_step: u64,
+    optimizer_hyperparams: BTreeMap<String, f32>,
+    scheduler: Option<SchedulerState>,
+    rng_counter: u64,
+}
+
+impl TrainingCheckpoint {
+    /// Captures parameters, optimizer and (optionally) scheduler state.
+    ///
+    /// The RNG counter is left at 0; use [`capture_graph`](Self::capture_graph)
+    /// to include it when training through a [`Graph`].
+    pub fn capture(
+        params: &ParamStore,
+        optimizer: &dyn Optimizer,
+        scheduler: Option<&dyn LRScheduler>,
+    ) -> GPResult<Self> {
+        Ok(Self {
+            params: params.state_dict()?,
+            optimizer: optimizer.save_state(params)?,
+            scheduler: scheduler.map(|s| s.save_state()),
+            rng_counter: 0,
+        })
+    }
+
+    /// Like [`capture`](Self::capture), but also records the graph engine's
+    /// Dropout RNG counter so stochastic layers resume identically.
+    pub fn capture_graph(
+        graph: &Graph,
+        optimizer: &dyn Optimizer,
+        scheduler: Option<&dyn LRScheduler>,
+    ) -> GPResult<Self> {
+        let mut ckpt = Self::capture(graph.params(), optimizer, scheduler)?;
+        ckpt.rng_counter = graph.engine().map_or(0, |e| e.rng_counter());
+        Ok(ckpt)
+    }
+
+    /// Restores parameters, optimizer and scheduler state.
+    ///
+    /// Parameters are loaded strictly by name, and optimizer buffers are
+    /// matched to them by name. If `scheduler` is given, the checkpoint must
+    /// contain scheduler state of the same kind. Everything is checked before
+    /// anything is applied, so a failed restore leaves all three unchanged.
+    pub fn restore(
+        &self,
+        params: &mut ParamStore,
+        optimizer: &mut dyn Optimizer,
+        scheduler: Option<&mut dyn LRScheduler>,
+    ) -> GPResult<()> {
+        let mut scheduler = scheduler;
+        let sched_state = match (&mut scheduler, &self.scheduler) {
+            (Some(_), None) => return Err(GPError::SerializationError(
+                "Checkpoint has no scheduler state".to_string()
+            )),
+            (Some(sched), Some(state)) => {
+                let current = sched.save_state();
+                state.expect_kind(&current.kind)?;
+                // Restoring its own state changes nothing, but fails here
+                // for schedulers without restore support
+                sched.restore_state(&current)?;
+                Some(state)
+            }
+            (None, _) => None,
+        };
+        params.check_state_dict(&self.params, true)?;
+
+        // Loading by name keeps keys and shapes, so buffers match the store
+        // the same way before and after. The optimizer restore is the last
+        // fallible step and leaves the optimizer unchanged on error.
+        optimizer.restore_state(&self.optimizer, params)?;
+        if let (Some(sched), Some(state)) = (scheduler, sched_state) {
+            sched.restore_state(state)?;
+        }
+        params.load_state_dict(&self.params, true)?;
+        Ok(())
+    }
+
+    /// Like [`restore`](Self::restore), and also restores the graph engine's
+    /// Dropout RNG counter.
+    pub fn restore_graph(
+        &self,
+        graph: &mut Graph,
+        optimizer: &mut dyn Optimizer,
+        scheduler: Option<&mut dyn LRScheduler>,
+    ) -> GPResult<()> {
+        self.restore(graph.params_mut(), optimizer, scheduler)?;
+        if let Some(engine) = graph.engine_mut() {
+            engine.set_rng_counter(self.rng_counter);
+        }
+        Ok(())
+    }
+
+    // ── Serialization ──────────────────────────────────────────────────────
+
+    /// Encodes the checkpoint as a single safetensors buffer.
+    pub fn to_bytes(&self) -> GPResult<Vec<u8>> {
+        let mut tensors = StateDict::new();
+        for (name, tensor) in &self.params {
+            tensors.insert(format!("{}{}", PARAM_PREFIX, name), tensor.clone());
+        }
+        for (slot, buffers) in &self.optimizer.slots {
+            if slot.contains('/') {
+                return Err(GPError::SerializationError(format!(
+                    "Optimizer slot name '{}' must not contain '/'", slot
+                )));
+            }
+            for (key, tensor) in buffers {
+                tensors.insert(format!("{}{}/{}", OPTIM_PREFIX, slot, key), tensor.clone());
+            }
+        }
+
+        let header = Header {
+            version: FORMAT_VERSION,
+            optimizer_kind: self.optimizer.kind.clone(),
+            optimizer_lr: self.optimizer.lr,
+            optimizer_step: self.optimizer.step,
+            optimizer_hyperparams: self.optimizer.hyperparams.clone(),
+            scheduler: self.scheduler.clone(),
+            rng_counter: self.rng_counter,
+        };
+        let mut metadata = Metadata::new();
+        metadata.insert(
+            HEADER_KEY.to_string(),
+            serde_json::to_string(&header).map_err(|e| GPError::SerializationError(e.to_string()))?,
+        );
+        safetensors::serialize(&tensors, Some(&metadata))
+    }
+
+    /// Decodes a checkpoint written by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> GPResult<Self> {
+        let (tensors, metadata) = safetensors::deserialize(bytes)?;
+        let header_json = metadata.get(HEADER_KEY).ok_or_else(|| GPError::SerializationError(
+            format!("Not a training checkpoint: missing '{}' metadata", HEADER_KEY)
+        ))?;
+        let header: Header = serde_json::from_str(header_json)
+            .map_err(|e| GPError::SerializationError(e.to_string()))?;
+        if header.version != FORMAT_VERSION {
+            return Err(GPError::SerializationError(format!(
+                "Unsupported checkpoint version {} (expected {})", header.version, FORMAT_VERSION
+            )));
+        }
+
+        let mut optimizer = OptimizerState::new(&header.optimizer_kind, header.optimizer_lr);
+        optimizer.step = header.optimizer_step;
+        optimizer.hyperparams = header.optimizer_hyperparams;
+
+        let mut params = StateDict::new();
+        for (name, tensor) in tensors {
+            if let Some(key) = name.strip_prefix(PARAM_PREFIX) {
+                params.insert(key.to_string(), tensor);
+            } else if let Some(rest) = name.strip_prefix(OPTIM_PREFIX) {
+                let (slot, key) = rest.split_once('/').ok_or_else(|| GPError::SerializationError(
+                    format!("Malformed optimizer tensor name '{}'", name)
+                ))?;
+                optimizer.slots.entry(slot.to_string()).or_default().insert(key.to_string(), tensor);
+            } else {
+                return Err(GPError::SerializationError(format!(
+                    "Unexpected tensor '{}' in checkpoint", name
+                )));
+            }
+        }
+
+        Ok(Self { params, optimizer, scheduler: header.scheduler, rng_counter: header.rng_counter })
+    }
+
+    /// Writes the checkpoint to a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> GPResult<()> {
+        std::fs::write(path, self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Reads a checkpoint from a file.
+    pub fn load<P: AsRef<Path>>(path: P) -> GPResult<Self> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::cpu::CPUBackend;
+    use crate::graph::dsl::GraphBuilder;
+    use crate::loss::{Loss, MSE};
+    use crate::optim::{Adam, SGD};
+    use crate::scheduler::{ExponentialLR, StepLR};
+    use crate::{NodeId, ParamId, Tensor};
+
+    /// Builds `dropout(tanh(x @ w1 + b1)) @ w2 + b2` with fixed weights.
+    fn build() -> (Graph, NodeId) {
+        let mut graph = Graph::new(Box::new(CPUBackend));
+        let mut gb = GraphBuilder::new(&mut graph);
+        let x = gb.val(Tensor::from_shape_vec(&[1, 2], vec![0.7, -0.2]).unwrap());
+        let w1 = gb.named_param(Tensor::from_shape_vec(&[2, 3], vec![0.2, -0.1, 0.4, 0.3, 0.5, -0.6]).unwrap(), "l1.weight");
+        let b1 = gb.named_param(Tensor::new_zeros(&[1, 3]), "l1.bias");
+        let h = gb.linear(x, w1, b1);
+        let h = gb.tanh(h);
+        let h = gb.dropout(h, 0.3);
+        let w2 = gb.named_param(Tensor::from_shape_vec(&[3, 1], vec![0.3, -0.2, 0.1]).unwrap(), "l2.weight");
+        let b2 = gb.named_param(Tensor::new_zeros(&[1, 1]), "l2.bias");
+        let out = gb.linear(h, w2, b2);
+        graph.set_training(true);
+        (graph, out)
+    }
+
+    fn train_step(graph: &mut Graph, out: NodeId, opt: &mut dyn Optimizer, sched: &mut dyn LRScheduler) {
+        let target = Tensor::from_elem(&[1, 1], 0.5);
+        graph.clear_gradients();
+        let pred = graph.execute(out).unwrap();
+        graph.backward(out, MSE.gradient(&pred, &target).unwrap()).unwrap();
+        opt.step_graph(graph).unwrap();
+        sched.step_optimizer(opt);
+    }
+
+    fn run_resume_check(make_opt: fn() -> Box<dyn Optimizer>) {
+        // Uninterrupted run: 10 steps
+        let (mut graph, out) = build();
+        let mut opt = make_opt();
+        let mut sched = StepLR::new(0.05, 3, 0.5);
+        let mut bytes = Vec::new();
+        for step in 0..10 {
+            if step == 4 {
+                bytes = TrainingCheckpoint::capture_graph(&graph, opt.as_ref(), Some(&sched))
+                    .unwrap().to_bytes().unwrap();
+            }
+            train_step(&mut graph, out, opt.as_mut(), &mut sched);
+        }
+
+        // Resumed run: restore at step 4 into fresh objects, run the remaining 6
+        let (mut resumed, out2) = build();
+        let mut opt2 = make_opt();
+        let mut sched2 = StepLR::new(0.05, 3, 0.5);
+        TrainingCheckpoint::from_bytes(&bytes).unwrap()
+            .restore_graph(&mut resumed, opt2.as_mut(), Some(&mut sched2)).unwrap();
+        for _ in 4..10 {
+            train_step(&mut resumed, out2, opt2.as_mut(), &mut sched2);
+        }
+
+        assert_eq!(graph.params().state_dict().unwrap(), resumed.params().state_dict().unwrap());
+        assert_eq!(opt.save_state(graph.params()).unwrap(), opt2.save_state(resumed.params()).unwrap());
+        assert_eq!(sched.save_state(), sched2.save_state());
+    }
+
+    #[test]
+    fn test_resume_is_bit_exact_adam() {
+        run_resume_check(|| Box::new(Adam::with_params(0.05, 0.9, 0.99, 1e-8, 0.01)));
+    }
+
+    #[test]
+    fn test_resume_is_bit_exact_sgd_momentum() {
+        run_resume_check(|| Box::new(SGD::new(0.05, 0.9, 0.001)));
+    }
+
+    /// An optimizer and scheduler implementing only the required methods.
+    struct Plain(f32);
+
+    impl Optimizer for Plain {
+        fn step(&mut self, _params: &mut ParamStore) -> GPResult<()> { Ok(()) }
+        fn set_lr(&mut self, lr: f32) { self.0 = lr; }
+        fn get_lr(&self) -> f32 { self.0 }
+    }
+
+    impl LRScheduler for Plain {
+        fn step(&mut self) -> f32 { self.0 }
+        fn current_lr(&self) -> f32 { self.0 }
+    }
+
+    #[test]
+    fn test_default_state_hooks_capture_but_do_not_restore() {
+        let (graph, _) = build();
+        let ckpt = TrainingCheckpoint::capture(graph.params(), &Plain(0.1), Some(&Plain(0.2))).unwrap();
+        assert_eq!(ckpt.scheduler.as_ref().map(|s| s.lr), Some(0.2));
+
+        let mut params = graph.params().clone();
+        let result = ckpt.restore(&mut params, &mut Plain(0.0), None);
+        assert!(matches!(result, Err(GPError::NotImplemented(_))));
+
+        // A scheduler without restore support fails before the optimizer is touched
+        let adam_ckpt = TrainingCheckpoint::capture(graph.params(), &Adam::new(0.01), Some(&Plain(0.2))).unwrap();
+        let mut adam = Adam::new(0.5);
+        let result = adam_ckpt.restore(&mut params, &mut adam, Some(&mut Plain(0.0)));
+        assert!(matches!(result, Err(GPError::NotImplemented(_))));
+        assert_eq!(adam.get_lr(), 0.5);
+        let restored = LRScheduler::restore_state(&mut Plain(0.0), ckpt.scheduler.as_ref().unwrap());
+        assert!(matches!(restored, Err(GPError::NotImplemented(_))));
+    }
+
+    #[test]
+    fn test_restore_rejects_wrong_optimizer_kind() {
+        let (graph, _) = build();
+        let ckpt = TrainingCheckpoint::capture(graph.params(), &Adam::new(0.01), None).unwrap();
+        let mut params = graph.params().clone();
+        assert!(ckpt.restore(&mut params, &mut SGD::new(0.01, 0.0, 0.0), None).is_err());
+    }
+
+    #[test]
+    fn test_restore_requires_scheduler_state_when_requested() {
+        let (graph, _) = build();
+        let ckpt = TrainingCheckpoint::capture(graph.params(), &Adam::new(0.01), None).unwrap();
+        let mut params = graph.params().clone();
+        let mut sched = StepLR::new(0.01, 1, 0.5);
+        assert!(ckpt.restore(&mut params, &mut Adam::new(0.01), Some(&mut sched)).is_err());
+    }
+
+    #[test]
+    fn test_optimizer_state_follows_parameter_names() {
+        let (mut graph, out) = build();
+        let mut opt = Adam::new(0.05);
+        let mut sched = StepLR::new(0.05, 3, 0.5);
+        for _ in 0..2 {
+            train_step(&mut graph, out, &mut opt, &mut sched);
+        }
+        let ckpt = TrainingCheckpoint::capture(graph.params(), &opt, None).unwrap();
+        assert!(ckpt.optimizer.slots["m"].contains_key("l2.weight"));
+
+        // Same parameters registered in reverse order
+        let mut reordered = ParamStore::new();
+        for (name, tensor) in graph.params().state_dict().unwrap().into_iter().rev() {
+            reordered.register(Tensor::new_zeros(tensor.shape()), &name);
+        }
+        let mut opt2 = Adam::new(0.05);
+        ckpt.restore(&mut reordered, &mut opt2, None).unwrap();
+        assert_eq!(reordered.state_dict().unwrap(), graph.params().state_dict().unwrap());
+        assert_eq!(opt2.save_state(&reordered).unwrap(), opt.save_state(graph.params()).unwrap());
+    }
+
+    #[test]
+    fn test_failed_restore_applies_nothing() {
+        let (graph, _) = build();
+        let sched = StepLR::new(0.05, 3, 0.5);
+        let ckpt = TrainingCheckpoint::capture(graph.params(), &SGD::new(0.5, 0.9, 0.0), Some(&sched)).unwrap();
+
+        let mut params = graph.params().clone();
+        let shape = params.tensor(ParamId(0)).shape().to_vec();
+        *params.tensor_mut(ParamId(0)) = Tensor::from_elem(&shape, 3.0);
+        let before = params.state_dict().unwrap();
+        let mut opt = SGD::new(0.1, 0.0, 0.0);
+        let mut wrong = ExponentialLR::new(0.1, 0.9);
+        assert!(ckpt.restore(&mut params, &mut opt, Some(&mut wrong)).is_err());
+        assert_eq!(params.state_dict().unwrap(), before);
+        assert_eq!(opt.get_lr(), 0.1);
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_plain_safetensors() {
+        let (graph, _) = build();
+        let bytes = graph.params().to_safetensors(None).unwrap();
+        assert!(TrainingCheckpoint::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_file_roundtrip() {
+        let (graph, _) = build();
+        let path = std::env::temp_dir().join(format!("gp_ckpt_{}.safetensors", std::process::id()));
+        let ckpt = TrainingCheckpoint::capture_graph(&graph, &SGD::new(0.1, 0.9, 0.0), None).unwrap();
+        ckpt.save(&path).unwrap();
+        let loaded = TrainingCheckpoint::load(&path).unwrap();
+        assert_eq!(loaded.params, ckpt.params);
+        assert_eq!(loaded.optimizer, ckpt.optimizer);
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -36,6 +36,18 @@ pub enum GPError {
     EmptyPopulation,
     #[error("Evolution failure: {0}")]
     EvolutionError(String),
+    #[error("Duplicate parameter name in store: '{0}'")]
+    DuplicateParamName(String),
+    #[error("Parameter name '{0}' uses the reserved 'param.' prefix of unnamed parameters")]
+    ReservedParamName(String),
+    #[error("Missing keys in state dict: {0:?}")]
+    MissingKeys(Vec<String>),
+    #[error("Unexpected keys in state dict: {0:?}")]
+    UnexpectedKeys(Vec<String>),
+    #[error("Shape mismatch for parameter '{name}': expected {expected:?}, found {found:?}")]
+    ParamShapeMismatch { name: String, expected: Vec<usize>, found: Vec<usize> },
+    #[error("Layer parameters are not bound to this parameter store (expected {expected} tensors, bound {bound})")]
+    UnboundParams { expected: usize, bound: usize },
 }
 
 pub type GPResult<T> = Result<T, GPError>;
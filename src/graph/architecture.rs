@@ -22,6 +22,8 @@
 //! - Inspected for visualization without a backend
 
 use serde::{Serialize, Deserialize};
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicU64, Ordering};
 use crate::{GPError, GPResult, Tensor, NodeId};
 use crate::params::ParamId;
 use super::{Node, OpType};
@@ -37,15 +39,44 @@ use super::{Node, OpType};
 /// - The graph is a DAG: `topological_sort` will detect cycles.
 /// - `Node::Input` nodes hold a tensor that serves as the mutable input buffer.
 /// - `Node::Param` nodes reference a [`ParamId`] in an external [`ParamStore`].
+/// - Batched inputs are `Node::Input` nodes whose first dimension is symbolic.
+/// - Every structural change assigns a new [`version`](Self::version).
 #[derive(Serialize, Deserialize)]
 pub struct Architecture {
     nodes: Vec<Node>,
+    /// Inputs whose first dimension is the symbolic batch size.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    batched_inputs: Vec<NodeId>,
+    /// Inputs declared via [`placeholder`](Self::placeholder), fed between runs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    placeholders: Vec<NodeId>,
+    /// Identifies the current topology (see [`version`](Self::version)).
+    #[serde(skip, default = "next_version")]
+    version: u64,
+}
+
+/// Versions are unique across all architectures, so a plan can never be
+/// mistaken for one built from a different graph.
+static NEXT_VERSION: AtomicU64 = AtomicU64::new(0);
+
+fn next_version() -> u64 {
+    NEXT_VERSION.fetch_add(1, Ordering::Relaxed)
 }
 
 impl Architecture {
     /// Creates an empty architecture with no nodes.
     pub fn new() -> Self {
-        Self { nodes: Vec::new() }
+        Self { nodes: Vec::new(), batched_inputs: Vec::new(), placeholders: Vec::new(), version: next_version() }
+    }
+
+    /// Returns the topology version, which changes whenever nodes are
+    /// added, removed or exposed through [`nodes_mut`](Self::nodes_mut).
+    /// Feeding inputs does not change it.
+    ///
+    /// Execution plans record the version they were built from and are
+    /// rebuilt when it no longer matches.
+    pub fn version(&self) -> u64 {
+        self.version
     }
 
     /// Returns the number of nodes in the graph.
@@ -66,13 +97,51 @@ impl Architecture {
     /// The tensor data can be overwritten before each forward pass.
     pub fn input(&mut self, tensor: Tensor) -> NodeId {
         let id = NodeId(self.nodes.len());
+        self.version = next_version();
         self.nodes.push(Node::Input(tensor));
         id
     }
 
+    /// Adds an input node whose first dimension is the symbolic batch size.
+    ///
+    /// The tensor's leading dimension is only the initial batch: the same
+    /// graph accepts inputs with any leading dimension, and the `Verifier`
+    /// tracks it as [`Dim::Batch`](super::verifier::Dim::Batch).
+    pub fn batched_input(&mut self, tensor: Tensor) -> NodeId {
+        let id = self.input(tensor);
+        self.batched_inputs.push(id);
+        id
+    }
+
+    /// Returns true if `id` is an input with a symbolic batch dimension.
+    pub fn is_batched(&self, id: NodeId) -> bool {
+        self.batched_inputs.contains(&id)
+    }
+
+    /// Adds a zero-filled input node of the declared `shape`, to be set
+    /// via [`feed`](Self::feed).
+    pub fn placeholder(&mut self, shape: &[usize]) -> NodeId {
+        let id = self.input(Tensor::new_zeros(shape));
+        self.placeholders.push(id);
+        id
+    }
+
+    /// Returns true if `id` is a placeholder, batched or not: an input whose
+    /// value is fed between runs rather than fixed at construction.
+    pub fn is_placeholder(&self, id: NodeId) -> bool {
+        self.placeholders.contains(&id) || self.is_batched(id)
+    }
+
+    /// Adds a placeholder whose first dimension is the symbolic batch size
+    /// (see [`batched_input`](Self::batched_input)).
+    pub fn batched_placeholder(&mut self, shape: &[usize]) -> NodeId {
+        self.batched_input(Tensor::new_zeros(shape))
+    }
+
     /// Adds a parameter node referencing a [`ParamId`] in a [`ParamStore`].
     pub fn param(&mut self, param_id: ParamId) -> NodeId {
         let id = NodeId(self.nodes.len());
+        self.version = next_version();
         self.nodes.push(Node::Param(param_id));
         id
     }
@@ -80,6 +149,7 @@ impl Architecture {
     /// Adds an operation node with the given op type and input node IDs.
     pub fn op(&mut self, op: OpType, inputs: Vec<NodeId>) -> NodeId {
         let id = NodeId(self.nodes.len());
+        self.version = next_version();
         self.nodes.push(Node::Op { op, inputs });
         id
     }
@@ -93,8 +163,10 @@ impl Architecture {
 
     /// Returns a mutable slice of all nodes.
     ///
-    /// Primarily used for mutating `Node::Input` tensors before execution.
+    /// To set input values, prefer [`feed`](Self::feed), which validates
+    /// the tensor against the input.
     pub fn nodes_mut(&mut self) -> &mut [Node] {
+        self.version = next_version();
         &mut self.nodes
     }
 
@@ -103,6 +175,93 @@ impl Architecture {
         self.nodes.get(id.0)
     }
 
+    /// Removes every node `i` with `keep[i] == false`, renumbering the rest
+    /// in their original order.
+    ///
+    /// Returns the new id of each old node (`None` if removed). Kept nodes
+    /// must not depend on removed ones.
+    pub fn retain_nodes(&mut self, keep: &[bool]) -> GPResult<Vec<Option<NodeId>>> {
+        let mut map = Vec::with_capacity(self.nodes.len());
+        let mut next = 0;
+        for i in 0..self.nodes.len() {
+            if keep.get(i).copied().unwrap_or(false) {
+                map.push(Some(NodeId(next)));
+                next += 1;
+            } else {
+                map.push(None);
+            }
+        }
+
+        for (i, node) in self.nodes.iter().enumerate().filter(|(i, _)| map[*i].is_some()) {
+            if let Some(&removed) = node.inputs().unwrap_or(&[]).iter().find(|id| map.get(id.0).copied().flatten().is_none()) {
+                return Err(GPError::InferenceError(format!(
+                    "Node {} depends on removed node {}", i, removed.0
+                )));
+            }
+        }
+
+        let old = std::mem::take(&mut self.nodes);
+        for (mut node, new_id) in old.into_iter().zip(&map) {
+            if new_id.is_none() {
+                continue;
+            }
+            if let Node::Op { inputs, .. } = &mut node {
+                for input in inputs.iter_mut() {
+                    *input = map[input.0].expect("checked above");
+                }
+            }
+            self.nodes.push(node);
+        }
+        self.batched_inputs = self.batched_inputs.iter().filter_map(|id| map[id.0]).collect();
+        self.placeholders = self.placeholders.iter().filter_map(|id| map[id.0]).collect();
+        self.version = next_version();
+        Ok(map)
+    }
+
+    // ── Input Feeding ──────────────────────────────────────────────────────
+
+    /// Sets the value of input node `id`.
+    ///
+    /// `tensor` must be on the same device as the input and have its
+    /// declared shape; batched inputs accept any leading dimension.
+    pub fn feed(&mut self, id: NodeId, tensor: &Tensor) -> GPResult<()> {
+        let batched = self.is_batched(id);
+        let current = match self.nodes.get_mut(id.0) {
+            Some(Node::Input(t)) => t,
+            _ => return Err(GPError::InferenceError(format!(
+                "Cannot feed node {}: not an input node", id.0
+            ))),
+        };
+
+        if tensor.device() != current.device() {
+            return Err(GPError::DeviceMismatch(format!(
+                "{:?} (input node {} is on {:?})", tensor.device(), id.0, current.device()
+            )));
+        }
+
+        if tensor.shape() == current.shape() {
+            current.copy_from(tensor)
+        } else if batched && tensor.ndim() == current.ndim() && tensor.shape()[1..] == current.shape()[1..] {
+            *current = tensor.clone();
+            Ok(())
+        } else {
+            Err(GPError::IncompatibleShapes {
+                expected: current.shape().to_vec(),
+                found: tensor.shape().to_vec(),
+                exp_len: current.len(),
+                found_len: tensor.len(),
+            })
+        }
+    }
+
+    /// Returns the current value of input node `id`.
+    pub fn input_value(&self, id: NodeId) -> Option<&Tensor> {
+        match self.nodes.get(id.0) {
+            Some(Node::Input(t)) => Some(t),
+            _ => None,
+        }
+    }
+
     // ── Topological Sort ───────────────────────────────────────────────────
 
     /// Computes the topological execution order for the subgraph rooted at `target`.
@@ -157,6 +316,19 @@ impl Architecture {
         Ok(order)
     }
 
+    /// Computes an execution order covering every node that `targets`
+    /// depend on, each node appearing once.
+    ///
+    /// Nodes can only reference nodes created before them, so the union
+    /// of the targets' dependencies sorted by id is a valid order.
+    pub fn execution_order(&self, targets: &[NodeId]) -> GPResult<Vec<NodeId>> {
+        let mut needed = BTreeSet::new();
+        for &target in targets {
+            needed.extend(self.topological_sort(target)?.into_iter().map(|id| id.0));
+        }
+        Ok(needed.into_iter().map(NodeId).collect())
+    }
+
     // ── Query Methods ──────────────────────────────────────────────────────
 
     /// Returns the indices of all parameter nodes.
@@ -295,4 +467,25 @@ mod tests {
         let restored: Architecture = serde_json::from_str(&json).unwrap();
         assert_eq!(restored.node_count(), 3);
     }
+    #[test]
+    fn test_feed_validates_input() {
+        let mut arch = Architecture::new();
+        let x = arch.placeholder(&[2, 3]);
+        let batch = arch.batched_placeholder(&[1, 3]);
+        let param = arch.param(ParamId(0));
+
+        let value = Tensor::new_ones(&[2, 3]);
+        arch.feed(x, &value).unwrap();
+        assert_eq!(arch.input_value(x), Some(&value));
+
+        // Fixed inputs keep their declared shape; batched ones only fix the rest
+        assert!(matches!(arch.feed(x, &Tensor::new_zeros(&[4, 3])), Err(GPError::IncompatibleShapes { .. })));
+        arch.feed(batch, &Tensor::new_zeros(&[4, 3])).unwrap();
+        assert_eq!(arch.input_value(batch).unwrap().shape(), &[4, 3]);
+        assert!(arch.feed(batch, &Tensor::new_zeros(&[4, 2])).is_err());
+        assert!(arch.feed(batch, &Tensor::new_zeros(&[12])).is_err());
+
+        assert!(matches!(arch.feed(param, &value), Err(GPError::InferenceError(_))));
+        assert!(arch.input_value(param).is_none());
+    }
 }
@@ -0,0 +1,397 @@
+//! Symbolic differentiation — gradients as graph nodes.
+//!
+//! [`ExecutionEngine::backward`](super::ExecutionEngine::backward) computes
+//! gradients eagerly as tensors, so they cannot be differentiated again.
+//! [`gradients`] instead appends the backward computation to the
+//! [`Architecture`] as ordinary op nodes. A gradient node is evaluated like
+//! any other node, and differentiating it gives second derivatives:
+//! Hessian-vector products, gradient penalties, or meta-learning through an
+//! inner update step.
+//!
+//! Element-wise, broadcasting, reduction, reshaping and matrix ops, the
+//! activations, Softmax, Dropout, fused linear layers and the MSE and
+//! with-logits losses have symbolic rules built from other differentiable
+//! ops; piecewise derivatives are masked with [`OpType::Step`]. Every other op gets a single [`OpType::Gradient`] node
+//! that evaluates its eager backward once, and one
+//! [`OpType::SelectGradient`] per input needing a gradient: first
+//! derivatives through it are exact, but differentiating it again fails.
+
+use crate::backend::{GELU_COEFF, GELU_CUBIC};
+use crate::{GPError, GPResult, NodeId};
+use super::{Architecture, Node, OpType};
+
+/// Appends nodes computing the gradient of `output` w.r.t. each node in
+/// `wrt`, returning their ids in the same order.
+///
+/// `grad_output` is the gradient flowing into `output`, a node of the same
+/// shape; `None` uses ones (the gradient of the sum of `output`). Nodes
+/// `output` does not depend on get a zero-filled gradient.
+pub fn gradients(arch: &mut Architecture, output: NodeId, wrt: &[NodeId], grad_output: Option<NodeId>) -> GPResult<Vec<NodeId>> {
+    let n = arch.node_count();
+    if let Some(id) = wrt.iter().chain(&grad_output).find(|id| id.0 >= n) {
+        return Err(GPError::InferenceError(format!(
+            "Node index {} out of bounds (graph has {} nodes)", id.0, n
+        )));
+    }
+    let order = arch.topological_sort(output)?;
+
+    // Only nodes on a path from `wrt` to `output` need a gradient
+    let mut reaches = vec![false; n];
+    for id in wrt {
+        reaches[id.0] = true;
+    }
+    for &id in &order {
+        if arch.nodes()[id.0].inputs().unwrap_or(&[]).iter().any(|input| reaches[input.0]) {
+            reaches[id.0] = true;
+        }
+    }
+
+    let mut grads: Vec<Option<NodeId>> = vec![None; n];
+    if reaches[output.0] {
+        grads[output.0] = Some(match grad_output {
+            Some(grad) => grad,
+            None => arch.op(OpType::FillLike { value: 1.0 }, vec![output]),
+        });
+    }
+    for &id in order.iter().rev() {
+        let (Some(grad), Node::Op { op, inputs }) = (grads[id.0], &arch.nodes()[id.0]) else {
+            continue;
+        };
+        let (op, inputs) = (op.clone(), inputs.clone());
+        let needed: Vec<bool> = inputs.iter().map(|input| reaches[input.0]).collect();
+        let input_grads = op_gradients(arch, &op, &inputs, id, grad, &needed)?;
+
+        // Inputs used several times sum their gradients
+        for (input, grad) in inputs.iter().zip(input_grads) {
+            if let Some(grad) = grad.filter(|_| reaches[input.0]) {
+                grads[input.0] = Some(match grads[input.0] {
+                    Some(total) => arch.op(OpType::Add, vec![total, grad]),
+                    None => grad,
+                });
+            }
+        }
+    }
+
+    let mut result = Vec::with_capacity(wrt.len());
+    for &id in wrt {
+        result.push(match grads[id.0] {
+            Some(grad) => grad,
+            None => arch.op(OpType::FillLike { value: 0.0 }, vec![id]),
+        });
+    }
+    Ok(result)
+}
+
+/// Emits the gradient of each input of `op` whose `needed` flag is set, given
+/// the node's `output` and its gradient `grad`. `None` means zero.
+fn op_gradients(arch: &mut Architecture, op: &OpType, inputs: &[NodeId], output: NodeId, grad: NodeId, needed: &[bool]) -> GPResult<Vec<Option<NodeId>>> {
+    let x = inputs[0];
+    let grads = match op {
+        OpType::MatMul => {
+            let w = inputs[1];
+            let gx = needed[0].then(|| {
+                let wt = arch.op(OpType::Transpose, vec![w]);
+                arch.op(OpType::MatMul, vec![grad, wt])
+            });
+            let gw = needed[1].then(|| {
+                let xt = arch.op(OpType::Transpose, vec![x]);
+                arch.op(OpType::MatMul, vec![xt, grad])
+            });
+            vec![gx, gw]
+        }
+        OpType::Add => vec![
+            needed[0].then(|| sum_to(arch, grad, x)),
+            needed[1].then(|| sum_to(arch, grad, inputs[1])),
+        ],
+        OpType::Sub => vec![
+            needed[0].then(|| sum_to(arch, grad, x)),
+            needed[1].then(|| {
+                let neg = arch.op(OpType::Neg, vec![grad]);
+                sum_to(arch, neg, inputs[1])
+            }),
+        ],
+        OpType::Mul => {
+            let y = inputs[1];
+            let gx = needed[0].then(|| {
+                let g = arch.op(OpType::Mul, vec![grad, y]);
+                sum_to(arch, g, x)
+            });
+            let gy = needed[1].then(|| {
+                let g = arch.op(OpType::Mul, vec![grad, x]);
+                sum_to(arch, g, y)
+            });
+            vec![gx, gy]
+        }
+        OpType::Div => {
+            let y = inputs[1];
+            let gx = needed[0].then(|| {
+                let g = arch.op(OpType::Div, vec![grad, y]);
+                sum_to(arch, g, x)
+            });
+            // d(x/y)/dy = -(x/y) / y
+            let gy = needed[1].then(|| {
+                let g = arch.op(OpType::Mul, vec![grad, output]);
+                let g = arch.op(OpType::Div, vec![g, y]);
+                let g = arch.op(OpType::Neg, vec![g]);
+                sum_to(arch, g, y)
+            });
+            vec![gx, gy]
+        }
+        OpType::Neg => vec![Some(arch.op(OpType::Neg, vec![grad]))],
+        OpType::Exp => vec![Some(arch.op(OpType::Mul, vec![grad, output]))],
+        OpType::Log => vec![Some(arch.op(OpType::Div, vec![grad, x]))],
+        OpType::Pow { exponent } => {
+            let p = arch.op(OpType::FillLike { value: *exponent }, vec![x]);
+            let power = arch.op(OpType::Pow { exponent: exponent - 1.0 }, vec![x]);
+            let dy = arch.op(OpType::Mul, vec![p, power]);
+            vec![Some(arch.op(OpType::Mul, vec![grad, dy]))]
+        }
+        OpType::Sqrt => {
+            let twice = arch.op(OpType::Add, vec![output, output]);
+            vec![Some(arch.op(OpType::Div, vec![grad, twice]))]
+        }
+        OpType::ReduceSum { axes, .. } => {
+            vec![Some(arch.op(OpType::BroadcastLike { axes: axes.clone() }, vec![grad, x]))]
+        }
+        OpType::ReduceMean { axes, .. } => {
+            // Each mean folds numel(x) / numel(output) elements
+            let (n_in, n_out) = (numel(arch, x), numel(arch, output));
+            let count = arch.op(OpType::Div, vec![n_in, n_out]);
+            let g = arch.op(OpType::BroadcastLike { axes: axes.clone() }, vec![grad, x]);
+            vec![Some(arch.op(OpType::Div, vec![g, count]))]
+        }
+        OpType::ReLU => {
+            let step = arch.op(OpType::Step, vec![x]);
+            vec![Some(arch.op(OpType::Mul, vec![grad, step]))]
+        }
+        OpType::AddReLU => {
+            let step = arch.op(OpType::Step, vec![output]);
+            let g = arch.op(OpType::Mul, vec![grad, step]);
+            vec![
+                needed[0].then(|| sum_to(arch, g, x)),
+                needed[1].then(|| sum_to(arch, g, inputs[1])),
+            ]
+        }
+        OpType::Tanh => {
+            // 1 - tanh(x)^2
+            let one = arch.op(OpType::FillLike { value: 1.0 }, vec![output]);
+            let sq = arch.op(OpType::Mul, vec![output, output]);
+            let dy = arch.op(OpType::Sub, vec![one, sq]);
+            vec![Some(arch.op(OpType::Mul, vec![grad, dy]))]
+        }
+        OpType::Sigmoid => {
+            // sigmoid(x) * (1 - sigmoid(x))
+            let one = arch.op(OpType::FillLike { value: 1.0 }, vec![output]);
+            let rest = arch.op(OpType::Sub, vec![one, output]);
+            let dy = arch.op(OpType::Mul, vec![output, rest]);
+            vec![Some(arch.op(OpType::Mul, vec![grad, dy]))]
+        }
+        OpType::Softplus => {
+            let dy = arch.op(OpType::Sigmoid, vec![x]);
+            vec![Some(arch.op(OpType::Mul, vec![grad, dy]))]
+        }
+        OpType::LeakyReLU { slope } => {
+            // slope + (1 - slope) * step(x)
+            let step = arch.op(OpType::Step, vec![x]);
+            let rise = arch.op(OpType::FillLike { value: 1.0 - slope }, vec![x]);
+            let floor = arch.op(OpType::FillLike { value: *slope }, vec![x]);
+            let dy = arch.op(OpType::Mul, vec![step, rise]);
+            let dy = arch.op(OpType::Add, vec![dy, floor]);
+            vec![Some(arch.op(OpType::Mul, vec![grad, dy]))]
+        }
+        OpType::ELU { alpha } => {
+            // 1 above zero, alpha * exp(x) = elu(x) + alpha below
+            let step = arch.op(OpType::Step, vec![x]);
+            let one = arch.op(OpType::FillLike { value: 1.0 }, vec![x]);
+            let below = arch.op(OpType::Sub, vec![one, step]);
+            let shift = arch.op(OpType::FillLike { value: *alpha }, vec![x]);
+            let tail = arch.op(OpType::Add, vec![output, shift]);
+            let tail = arch.op(OpType::Mul, vec![tail, below]);
+            let dy = arch.op(OpType::Add, vec![step, tail]);
+            vec![Some(arch.op(OpType::Mul, vec![grad, dy]))]
+        }
+        OpType::GELU => {
+            // 0.5 (1 + t) + 0.5 x (1 - t^2) c (1 + 3k x^2), t = tanh(c (x + k x^3))
+            let sq = arch.op(OpType::Mul, vec![x, x]);
+            let cube = arch.op(OpType::Mul, vec![sq, x]);
+            let cube = scale(arch, cube, GELU_CUBIC);
+            let inner = arch.op(OpType::Add, vec![x, cube]);
+            let inner = scale(arch, inner, GELU_COEFF);
+            let t = arch.op(OpType::Tanh, vec![inner]);
+            let one = arch.op(OpType::FillLike { value: 1.0 }, vec![x]);
+            let left = arch.op(OpType::Add, vec![one, t]);
+            let t_sq = arch.op(OpType::Mul, vec![t, t]);
+            let sech_sq = arch.op(OpType::Sub, vec![one, t_sq]);
+            let d_inner = scale(arch, sq, 3.0 * GELU_CUBIC);
+            let d_inner = arch.op(OpType::Add, vec![one, d_inner]);
+            let d_inner = scale(arch, d_inner, GELU_COEFF);
+            let right = arch.op(OpType::Mul, vec![x, sech_sq]);
+            let right = arch.op(OpType::Mul, vec![right, d_inner]);
+            let dy = arch.op(OpType::Add, vec![left, right]);
+            let dy = scale(arch, dy, 0.5);
+            vec![Some(arch.op(OpType::Mul, vec![grad, dy]))]
+        }
+        OpType::SiLU => {
+            // s (1 + x (1 - s)), s = sigmoid(x)
+            let s = arch.op(OpType::Sigmoid, vec![x]);
+            let one = arch.op(OpType::FillLike { value: 1.0 }, vec![x]);
+            let rest = arch.op(OpType::Sub, vec![one, s]);
+            let rest = arch.op(OpType::Mul, vec![x, rest]);
+            let rest = arch.op(OpType::Add, vec![one, rest]);
+            let dy = arch.op(OpType::Mul, vec![s, rest]);
+            vec![Some(arch.op(OpType::Mul, vec![grad, dy]))]
+        }
+        OpType::HardTanh { min, max } | OpType::Clamp { min, max } => {
+            // 1 inside [min, max]: 1 - step(min - x) - step(x - max)
+            let lo = arch.op(OpType::FillLike { value: *min }, vec![x]);
+            let hi = arch.op(OpType::FillLike { value: *max }, vec![x]);
+            let under = arch.op(OpType::Sub, vec![lo, x]);
+            let under = arch.op(OpType::Step, vec![under]);
+            let over = arch.op(OpType::Sub, vec![x, hi]);
+            let over = arch.op(OpType::Step, vec![over]);
+            let one = arch.op(OpType::FillLike { value: 1.0 }, vec![x]);
+            let mask = arch.op(OpType::Sub, vec![one, under]);
+            let mask = arch.op(OpType::Sub, vec![mask, over]);
+            vec![Some(arch.op(OpType::Mul, vec![grad, mask]))]
+        }
+        OpType::Abs => {
+            // step(x) - step(-x), zero at zero
+            let neg = arch.op(OpType::Neg, vec![x]);
+            let pos = arch.op(OpType::Step, vec![x]);
+            let neg = arch.op(OpType::Step, vec![neg]);
+            let sign = arch.op(OpType::Sub, vec![pos, neg]);
+            vec![Some(arch.op(OpType::Mul, vec![grad, sign]))]
+        }
+        OpType::Softmax => {
+            // y * (g - sum(g * y)) along the class axis
+            let gy = arch.op(OpType::Mul, vec![grad, output]);
+            let dot = arch.op(OpType::ReduceSum { axes: vec![1], keep_dims: true }, vec![gy]);
+            let centered = arch.op(OpType::Sub, vec![grad, dot]);
+            vec![Some(arch.op(OpType::Mul, vec![output, centered]))]
+        }
+        OpType::MSELoss => {
+            // 2 * (p - t) / numel(p); the target is a constant, as in the eager backward
+            let diff = arch.op(OpType::Sub, vec![x, inputs[1]]);
+            let twice = arch.op(OpType::Add, vec![diff, diff]);
+            let count = numel(arch, x);
+            let dy = arch.op(OpType::Div, vec![twice, count]);
+            vec![Some(arch.op(OpType::Mul, vec![dy, grad])), None]
+        }
+        OpType::BCEWithLogitsLoss => {
+            // (sigmoid(x) - t) / numel(x)
+            let probs = arch.op(OpType::Sigmoid, vec![x]);
+            let diff = arch.op(OpType::Sub, vec![probs, inputs[1]]);
+            let count = numel(arch, x);
+            let dy = arch.op(OpType::Div, vec![diff, count]);
+            vec![Some(arch.op(OpType::Mul, vec![dy, grad])), None]
+        }
+        OpType::CrossEntropyWithLogitsLoss => {
+            // (softmax(x) - t) / rows
+            let probs = arch.op(OpType::Softmax, vec![x]);
+            let diff = arch.op(OpType::Sub, vec![probs, inputs[1]]);
+            let per_row = arch.op(OpType::ReduceSum { axes: vec![1], keep_dims: true }, vec![diff]);
+            let rows = numel(arch, per_row);
+            let dy = arch.op(OpType::Div, vec![diff, rows]);
+            vec![Some(arch.op(OpType::Mul, vec![dy, grad])), None]
+        }
+        OpType::Transpose => vec![Some(arch.op(OpType::Transpose, vec![grad]))],
+        OpType::Reshape { .. } => vec![Some(arch.op(OpType::ReshapeLike, vec![grad, x]))],
+        OpType::ReshapeLike => vec![Some(arch.op(OpType::ReshapeLike, vec![grad, x])), None],
+        OpType::Concat { axis } => {
+            let mut args = vec![grad];
+            args.extend_from_slice(inputs);
+            (0..inputs.len())
+                .map(|input| needed[input].then(|| arch.op(OpType::ConcatSlice { axis: *axis, input }, args.clone())))
+                .collect()
+        }
+        OpType::ConcatSlice { axis, input } => {
+            // The gradient in the slice's place, zeros for the other parts
+            let parts: Vec<NodeId> = inputs[1..].iter().enumerate()
+                .map(|(i, &part)| if i == *input { grad } else { arch.op(OpType::FillLike { value: 0.0 }, vec![part]) })
+                .collect();
+            let mut grads = vec![Some(arch.op(OpType::Concat { axis: *axis }, parts))];
+            grads.resize(inputs.len(), None);
+            grads
+        }
+        OpType::Dropout { rate } if *rate <= 0.0 || *rate >= 1.0 => vec![Some(grad)],
+        OpType::Dropout { rate } => {
+            // Kept elements are the nonzero outputs, scaled by 1 / (1 - rate)
+            let kept = arch.op(OpType::Abs, vec![output]);
+            let kept = arch.op(OpType::Step, vec![kept]);
+            let mask = scale(arch, kept, 1.0 / (1.0 - rate));
+            vec![Some(arch.op(OpType::Mul, vec![grad, mask]))]
+        }
+        OpType::FusedLinear { activation } => {
+            // Expanded to activation(x · w + b), rebuilding the pre-activation
+            let w = inputs[1];
+            let grad = match activation {
+                Some(act) => {
+                    let product = arch.op(OpType::MatMul, vec![x, w]);
+                    let pre = arch.op(OpType::Add, vec![product, inputs[2]]);
+                    let grads = op_gradients(arch, act, &[pre], output, grad, &[true])?;
+                    grads[0].ok_or_else(|| GPError::InferenceError(format!(
+                        "{} has no gradient", act.name()
+                    )))?
+                }
+                None => grad,
+            };
+            let gx = needed[0].then(|| {
+                let wt = arch.op(OpType::Transpose, vec![w]);
+                arch.op(OpType::MatMul, vec![grad, wt])
+            });
+            let gw = needed[1].then(|| {
+                let xt = arch.op(OpType::Transpose, vec![x]);
+                arch.op(OpType::MatMul, vec![xt, grad])
+            });
+            vec![gx, gw, needed[2].then(|| sum_to(arch, grad, inputs[2]))]
+        }
+        OpType::SumToLike { axes } => {
+            vec![Some(arch.op(OpType::BroadcastLike { axes: axes.clone() }, vec![grad, x])), None]
+        }
+        OpType::BroadcastLike { axes } => {
+            vec![Some(arch.op(OpType::SumToLike { axes: axes.clone() }, vec![grad, x])), None]
+        }
+        OpType::StopGradient | OpType::ArgMax { .. } | OpType::FillLike { .. } | OpType::Step => {
+            vec![None; inputs.len()]
+        }
+        OpType::Gradient { .. } | OpType::SelectGradient { .. } => {
+            let source = match (op, &arch.nodes()[x.0]) {
+                (OpType::Gradient { op: source }, _)
+                | (_, Node::Op { op: OpType::Gradient { op: source }, .. }) => source.name(),
+                _ => op.name(),
+            };
+            return Err(GPError::InferenceError(format!(
+                "The gradient of {} is not differentiable", source
+            )));
+        }
+        _ => {
+            // One eager backward for all inputs, cut apart per needed input
+            let mut args = inputs.to_vec();
+            args.extend([output, grad]);
+            let packed = arch.op(OpType::Gradient { op: Box::new(op.clone()) }, args);
+            let mut parts = vec![packed];
+            parts.extend_from_slice(inputs);
+            (0..inputs.len())
+                .map(|input| needed[input].then(|| arch.op(OpType::SelectGradient { input }, parts.clone())))
+                .collect()
+        }
+    };
+    Ok(grads)
+}
+
+/// Sums the broadcast gradient `grad` down to the shape of `like`.
+fn sum_to(arch: &mut Architecture, grad: NodeId, like: NodeId) -> NodeId {
+    arch.op(OpType::SumToLike { axes: Vec::new() }, vec![grad, like])
+}
+
+/// `id * factor`, element-wise.
+fn scale(arch: &mut Architecture, id: NodeId, factor: f32) -> NodeId {
+    let factor = arch.op(OpType::FillLike { value: factor }, vec![id]);
+    arch.op(OpType::Mul, vec![id, factor])
+}
+
+/// The element count of `id` as a `[1]` tensor.
+fn numel(arch: &mut Architecture, id: NodeId) -> NodeId {
+    let ones = arch.op(OpType::FillLike { value: 1.0 }, vec![id]);
+    arch.op(OpType::ReduceSum { axes: Vec::new(), keep_dims: false }, vec![ones])
+}
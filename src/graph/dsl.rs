@@ -1,23 +1,133 @@
+use std::collections::HashMap;
+
 use crate::graph::{Graph, OpType};
+use crate::params::{ParamStore, ParamId};
 use crate::{Tensor, NodeId};
 
 pub struct GraphBuilder<'a> {
     graph: &'a mut Graph,
+    /// Name scopes entered via [`scoped`](Self::scoped), outermost first.
+    scope: Vec<String>,
+    /// Parameter name prefixes in use, each mapped to the last `_n` suffix
+    /// handed out with it as the base (see [`layer_prefix`](Self::layer_prefix)).
+    prefixes: HashMap<String, usize>,
+    /// Number of the store's parameters whose keys are in `prefixes`.
+    indexed_params: usize,
 }
 
 impl<'a> GraphBuilder<'a> {
     pub fn new(graph: &'a mut Graph) -> Self {
-        Self { graph }
+        Self { graph, scope: Vec::new(), prefixes: HashMap::new(), indexed_params: 0 }
     }
 
     pub fn val(&mut self, tensor: Tensor) -> NodeId {
         self.graph.input(tensor)
     }
 
+    /// Adds an input whose first dimension is the symbolic batch size.
+    pub fn batched_val(&mut self, tensor: Tensor) -> NodeId {
+        self.graph.batched_input(tensor)
+    }
+
+    /// Adds a zero-filled input of the declared `shape`, set later via
+    /// [`Graph::feed`].
+    pub fn placeholder(&mut self, shape: &[usize]) -> NodeId {
+        self.graph.placeholder(shape)
+    }
+
+    /// Adds a placeholder whose first dimension is the symbolic batch size.
+    pub fn batched_placeholder(&mut self, shape: &[usize]) -> NodeId {
+        self.graph.batched_placeholder(shape)
+    }
+
     pub fn param(&mut self, tensor: Tensor) -> NodeId {
         self.graph.param(tensor)
     }
 
+    /// Registers a parameter under `name`, which becomes its state-dict key.
+    pub fn named_param(&mut self, tensor: Tensor, name: &str) -> NodeId {
+        self.graph.named_param(tensor, name)
+    }
+
+    /// Registers a non-trainable buffer (e.g. a running mean).
+    pub fn buffer(&mut self, tensor: Tensor) -> NodeId {
+        self.graph.buffer(tensor)
+    }
+
+    /// Registers a named non-trainable buffer.
+    pub fn named_buffer(&mut self, tensor: Tensor, name: &str) -> NodeId {
+        self.graph.named_buffer(tensor, name)
+    }
+
+    // ── Parameter Names ────────────────────────────────────────────────────
+
+    /// Runs `f` inside the name scope `name`: layers called in `f` name
+    /// their parameters `{name}.{param}` (nested scopes join with `.`).
+    ///
+    /// Scoping each layer by a stable name keeps state-dict keys stable
+    /// when layers are inserted or reordered.
+    pub fn scoped<R>(&mut self, name: &str, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.scope.push(name.to_string());
+        let result = f(self);
+        self.scope.pop();
+        result
+    }
+
+    /// A parameter name prefix for a new layer: the current scope, or `kind`
+    /// (e.g. `"linear"`) outside any scope, suffixed `_1`, `_2`, … when
+    /// parameters under it already exist.
+    pub fn layer_prefix(&mut self, kind: &str) -> String {
+        let base = if self.scope.is_empty() { kind.to_string() } else { self.scope.join(".") };
+        self.index_prefixes();
+        let mut n = self.prefixes.get(&base).copied().unwrap_or(0);
+        let mut prefix = if n == 0 { base.clone() } else { format!("{}_{}", base, n) };
+        while self.prefixes.contains_key(&prefix) {
+            n += 1;
+            prefix = format!("{}_{}", base, n);
+        }
+        self.prefixes.insert(base, n);
+        self.prefixes.entry(prefix.clone()).or_insert(0);
+        prefix
+    }
+
+    /// Adds every dotted prefix of the keys registered since the last call
+    /// to `prefixes`, so each key is scanned once per builder.
+    fn index_prefixes(&mut self) {
+        let params = self.graph.params();
+        for i in self.indexed_params..params.len() {
+            let key = params.key(ParamId(i));
+            for (end, _) in key.match_indices('.') {
+                self.prefixes.entry(key[..end].to_string()).or_insert(0);
+            }
+        }
+        self.indexed_params = params.len();
+    }
+
+    /// Registers a layer's trainable `tensors` as `{prefix}.{name}` under a
+    /// fresh [`layer_prefix`](Self::layer_prefix) for `kind`.
+    pub fn layer_params(&mut self, kind: &str, tensors: &[(&str, &Tensor)]) -> Vec<NodeId> {
+        let prefix = self.layer_prefix(kind);
+        tensors.iter()
+            .map(|&(name, tensor)| self.named_param(tensor.clone(), &format!("{}.{}", prefix, name)))
+            .collect()
+    }
+
+    /// Adds a node for an already registered parameter, sharing its tensor
+    /// and gradient with every other node that references `id`.
+    pub fn param_ref(&mut self, id: ParamId) -> NodeId {
+        self.graph.param_ref(id)
+    }
+
+    /// Returns the `ParamId` behind a Param node.
+    pub fn param_id(&self, node: NodeId) -> Option<ParamId> {
+        self.graph.nodes().get(node.0).and_then(|n| n.param_id())
+    }
+
+    /// The parameter store of the graph being built.
+    pub fn params(&self) -> &ParamStore {
+        self.graph.params()
+    }
+
     pub fn matmul(&mut self, a: NodeId, b: NodeId) -> NodeId {
         self.graph.op(OpType::MatMul, vec![a, b])
     }
@@ -29,6 +139,83 @@ impl<'a> GraphBuilder<'a> {
     pub fn mul(&mut self, a: NodeId, b: NodeId) -> NodeId {
         self.graph.op(OpType::Mul, vec![a, b])
     }
+
+    pub fn sub(&mut self, a: NodeId, b: NodeId) -> NodeId {
+        self.graph.op(OpType::Sub, vec![a, b])
+    }
+
+    pub fn div(&mut self, a: NodeId, b: NodeId) -> NodeId {
+        self.graph.op(OpType::Div, vec![a, b])
+    }
+
+    pub fn neg(&mut self, x: NodeId) -> NodeId {
+        self.graph.op(OpType::Neg, vec![x])
+    }
+
+    pub fn exp(&mut self, x: NodeId) -> NodeId {
+        self.graph.op(OpType::Exp, vec![x])
+    }
+
+    pub fn log(&mut self, x: NodeId) -> NodeId {
+        self.graph.op(OpType::Log, vec![x])
+    }
+
+    pub fn pow(&mut self, x: NodeId, exponent: f32) -> NodeId {
+        self.graph.op(OpType::Pow { exponent }, vec![x])
+    }
+
+    pub fn sqrt(&mut self, x: NodeId) -> NodeId {
+        self.graph.op(OpType::Sqrt, vec![x])
+    }
+
+    pub fn abs(&mut self, x: NodeId) -> NodeId {
+        self.graph.op(OpType::Abs, vec![x])
+    }
+
+    pub fn clamp(&mut self, x: NodeId, min: f32, max: f32) -> NodeId {
+        self.graph.op(OpType::Clamp { min, max }, vec![x])
+    }
+
+    /// Sums over `axes`; an empty slice reduces over every axis.
+    pub fn reduce_sum(&mut self, x: NodeId, axes: &[usize], keep_dims: bool) -> NodeId {
+        self.graph.op(OpType::ReduceSum { axes: axes.to_vec(), keep_dims }, vec![x])
+    }
+
+    /// Averages over `axes`; an empty slice reduces over every axis.
+    pub fn reduce_mean(&mut self, x: NodeId, axes: &[usize], keep_dims: bool) -> NodeId {
+        self.graph.op(OpType::ReduceMean { axes: axes.to_vec(), keep_dims }, vec![x])
+    }
+
+    /// Max over `axes`; an empty slice reduces over every axis.
+    pub fn reduce_max(&mut self, x: NodeId, axes: &[usize], keep_dims: bool) -> NodeId {
+        self.graph.op(OpType::ReduceMax { axes: axes.to_vec(), keep_dims }, vec![x])
+    }
+
+    pub fn argmax(&mut self, x: NodeId, axis: usize, keep_dims: bool) -> NodeId {
+        self.graph.op(OpType::ArgMax { axis, keep_dims }, vec![x])
+    }
+
+    // ── Losses (scalar `[1]` outputs) ──────────────────────────────────────
+
+    pub fn mse_loss(&mut self, predicted: NodeId, target: NodeId) -> NodeId {
+        self.graph.op(OpType::MSELoss, vec![predicted, target])
+    }
+
+    pub fn bce_loss(&mut self, predicted: NodeId, target: NodeId) -> NodeId {
+        self.graph.op(OpType::BinaryCrossEntropyLoss, vec![predicted, target])
+    }
+
+    pub fn bce_with_logits_loss(&mut self, logits: NodeId, target: NodeId) -> NodeId {
+        self.graph.op(OpType::BCEWithLogitsLoss, vec![logits, target])
+    }
+
+    pub fn categorical_cross_entropy_loss(&mut self, predicted: NodeId, target: NodeId) -> NodeId {
+        self.graph.op(OpType::CategoricalCrossEntropyLoss, vec![predicted, target])
+    }
+
+    pub fn cross_entropy_with_logits_loss(&mut self, logits: NodeId, target: NodeId) -> NodeId {
+        self.graph.op(OpType::CrossEntropyWithLogitsLoss, vec![logits, target])
+    }
     
     pub fn node(&mut self, op: OpType, inputs: Vec<NodeId>) -> NodeId {
         self.graph.op(op, inputs)
@@ -56,10 +243,39 @@ impl<'a> GraphBuilder<'a> {
         self.graph.op(OpType::Softmax, vec![input])
     }
 
+    pub fn leaky_relu(&mut self, x: NodeId, slope: f32) -> NodeId {
+        self.graph.op(OpType::LeakyReLU { slope }, vec![x])
+    }
+
+    pub fn elu(&mut self, x: NodeId, alpha: f32) -> NodeId {
+        self.graph.op(OpType::ELU { alpha }, vec![x])
+    }
+
+    pub fn gelu(&mut self, x: NodeId) -> NodeId {
+        self.graph.op(OpType::GELU, vec![x])
+    }
+
+    pub fn silu(&mut self, x: NodeId) -> NodeId {
+        self.graph.op(OpType::SiLU, vec![x])
+    }
+
+    pub fn softplus(&mut self, x: NodeId) -> NodeId {
+        self.graph.op(OpType::Softplus, vec![x])
+    }
+
+    pub fn hardtanh(&mut self, x: NodeId, min: f32, max: f32) -> NodeId {
+        self.graph.op(OpType::HardTanh { min, max }, vec![x])
+    }
+
     pub fn dropout(&mut self, input: NodeId, rate: f32) -> NodeId {
         self.graph.op(OpType::Dropout { rate }, vec![input])
     }
 
+    /// Layer normalization over the last dimension: `gamma * x_norm + beta`.
+    pub fn layer_norm(&mut self, input: NodeId, gamma: NodeId, beta: NodeId, epsilon: f32) -> NodeId {
+        self.graph.op(OpType::LayerNorm { epsilon }, vec![input, gamma, beta])
+    }
+
     pub fn conv2d(&mut self, input: NodeId, weight: NodeId, stride: usize, padding: usize) -> NodeId {
         self.graph.op(OpType::Conv2D { stride, padding }, vec![input, weight])
     }
@@ -68,11 +284,29 @@ impl<'a> GraphBuilder<'a> {
         self.graph.op(OpType::MaxPool2D { kernel_size, stride }, vec![input])
     }
 
-    pub fn reshape(&mut self, input: NodeId, target_shape: Vec<usize>) -> NodeId {
+    /// Passes `input` through unchanged but stops gradients flowing back into it.
+    pub fn stop_gradient(&mut self, input: NodeId) -> NodeId {
+        self.graph.op(OpType::StopGradient, vec![input])
+    }
+
+    /// Swaps the two axes of a matrix.
+    pub fn transpose(&mut self, input: NodeId) -> NodeId {
+        self.graph.op(OpType::Transpose, vec![input])
+    }
+
+    /// Concatenates `inputs` along `axis` (e.g. `1` for the feature axis of
+    /// `[batch, features]` tensors).
+    pub fn concat(&mut self, inputs: Vec<NodeId>, axis: usize) -> NodeId {
+        self.graph.op(OpType::Concat { axis }, inputs)
+    }
+
+    /// Reshapes `input`; one dimension may be `-1` and is then inferred
+    /// from the element count.
+    pub fn reshape(&mut self, input: NodeId, target_shape: Vec<isize>) -> NodeId {
         self.graph.op(OpType::Reshape { target_shape }, vec![input])
     }
 
-    /// Flattens a tensor to 2D: `[batch, features]`.
+    /// Flattens a tensor to 2D: `[batch, features]`, for any batch size.
     ///
     /// Requires knowing the total feature count (product of all dims except batch).
     /// Use this after Conv2D/MaxPool2D layers to transition to Linear layers.
@@ -83,6 +317,6 @@ impl<'a> GraphBuilder<'a> {
     /// let flat = gb.flatten(conv_output, channels * height * width);
     /// ```
     pub fn flatten(&mut self, input: NodeId, feature_count: usize) -> NodeId {
-        self.reshape(input, vec![1, feature_count])
+        self.reshape(input, vec![-1, feature_count as isize])
     }
 }
@@ -11,13 +11,19 @@
 //! |--------------------|---------------------------------------------|
 //! | `Architecture`     | DAG topology, node management, sort          |
 //! | `ParamStore`       | Trainable parameter tensors + gradients      |
-//! | `ExecutionEngine`  | Backend, value cache, forward/backward pass  |
+//! | `ExecutionEngine`  | Backend, plan and value caches, fwd/backward |
 //! | `Graph`            | Composes all three into a convenient facade  |
 
 use crate::backend::Backend;
 use crate::{GPError, GPResult, Tensor, NodeId};
-use crate::params::ParamStore;
-use super::{Node, Architecture};
+use crate::params::{ParamStore, ParamId};
+use super::{Node, Architecture, OpType, MemoryPlan, ExecutionPlan};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Default for [`ExecutionEngine::set_parallel_threshold`]: levels reading
+/// fewer input elements than this run serially.
+pub const PARALLEL_MIN_ELEMENTS: usize = 1 << 16;
 
 /// Execution engine for computation graphs.
 ///
@@ -42,6 +48,33 @@ pub struct ExecutionEngine {
     training: bool,
     /// RNG seed counter for deterministic dropout masks.
     rng_counter: u64,
+    /// Buffer values (e.g. BatchNorm running stats) produced by the latest
+    /// training-mode forward pass, waiting to be written back to the
+    /// [`ParamStore`].
+    pending_buffers: Vec<(ParamId, Tensor)>,
+    /// Bumped whenever an input changes shape (e.g. a new batch size), so
+    /// cached outputs of the old shape are reallocated instead of reused.
+    shape_epoch: u64,
+    /// The `shape_epoch` each cached value was allocated in.
+    value_epochs: Vec<u64>,
+    /// Buffer reuse plan applied when executing its order (see [`MemoryPlan`]).
+    memory_plan: Option<MemoryPlan>,
+    /// Released buffers, one per plan slot, waiting to be computed into again.
+    pool: Vec<Option<Tensor>>,
+    /// Shape of each value at the time it was released, for shape-only backward.
+    released_shapes: Vec<Vec<usize>>,
+    /// Segment boundaries for activation checkpointing, sorted; empty when off.
+    activation_checkpoints: Vec<NodeId>,
+    /// The Dropout seed each op was last run with, so recomputation
+    /// reproduces the same mask.
+    node_seeds: Vec<u64>,
+    /// Compiled execution plans by target set, all built from `plans_version`.
+    plans: HashMap<Vec<NodeId>, Arc<ExecutionPlan>>,
+    plans_version: Option<u64>,
+    /// Whether independent nodes may run concurrently (`rayon` feature).
+    parallel: bool,
+    /// Input elements a level must read before it is spread over threads.
+    parallel_threshold: usize,
 }
 
 impl ExecutionEngine {
@@ -54,6 +87,18 @@ impl ExecutionEngine {
             node_gradients: Vec::new(),
             training: false,
             rng_counter: 0,
+            pending_buffers: Vec::new(),
+            shape_epoch: 0,
+            value_epochs: Vec::new(),
+            memory_plan: None,
+            pool: Vec::new(),
+            released_shapes: Vec::new(),
+            activation_checkpoints: Vec::new(),
+            node_seeds: Vec::new(),
+            plans: HashMap::new(),
+            plans_version: None,
+            parallel: true,
+            parallel_threshold: PARALLEL_MIN_ELEMENTS,
         }
     }
 
@@ -74,11 +119,79 @@ impl ExecutionEngine {
         self.training
     }
 
+    /// Enables or disables concurrent execution of independent nodes
+    /// (enabled by default). Only has an effect when built with the `rayon`
+    /// feature; results are identical either way.
+    pub fn set_parallel(&mut self, parallel: bool) {
+        self.parallel = parallel;
+    }
+
+    /// Returns whether independent nodes may run concurrently.
+    pub fn is_parallel(&self) -> bool {
+        self.parallel
+    }
+
+    /// Sets how many input elements the ops of one level must read, in
+    /// total, before they are run concurrently (default
+    /// [`PARALLEL_MIN_ELEMENTS`]). Smaller levels run on the calling thread,
+    /// where they finish faster than a fork/join would take; `0` fans out
+    /// every level with more than one op.
+    pub fn set_parallel_threshold(&mut self, min_elements: usize) {
+        self.parallel_threshold = min_elements;
+    }
+
+    /// Returns the per-level work threshold for concurrent execution.
+    pub fn parallel_threshold(&self) -> usize {
+        self.parallel_threshold
+    }
+
+    /// Whether `plan` is executed level by level on the rayon thread pool.
+    fn runs_parallel(&self, plan: &ExecutionPlan) -> bool {
+        cfg!(feature = "rayon") && self.parallel && plan.width() > 1
+    }
+
+    /// Whether a level of `tasks` ops reading `work` input elements is worth
+    /// handing to the thread pool.
+    #[cfg(feature = "rayon")]
+    fn fans_out(&self, tasks: usize, work: usize) -> bool {
+        tasks > 1 && work >= self.parallel_threshold
+    }
+
+    /// Returns the Dropout RNG counter (the seed for the next stochastic op).
+    pub fn rng_counter(&self) -> u64 {
+        self.rng_counter
+    }
+
+    /// Sets the Dropout RNG counter, e.g. when resuming from a checkpoint.
+    pub fn set_rng_counter(&mut self, counter: u64) {
+        self.rng_counter = counter;
+    }
+
     /// Returns the cached values from the last forward pass.
     pub fn values(&self) -> &[Option<Tensor>] {
         &self.values
     }
 
+    // ── Execution Plans ────────────────────────────────────────────────────
+
+    /// Returns the [`ExecutionPlan`] for `targets`, building it on first use.
+    ///
+    /// Plans are cached per target set and dropped as soon as `arch` reports
+    /// a new [`version`](Architecture::version), so topology changes never
+    /// run a stale order.
+    pub fn plan(&mut self, arch: &Architecture, targets: &[NodeId]) -> GPResult<Arc<ExecutionPlan>> {
+        if self.plans_version != Some(arch.version()) {
+            self.plans.clear();
+            self.plans_version = Some(arch.version());
+        }
+        if let Some(plan) = self.plans.get(targets) {
+            return Ok(Arc::clone(plan));
+        }
+        let plan = Arc::new(ExecutionPlan::new(arch, targets)?);
+        self.plans.insert(targets.to_vec(), Arc::clone(&plan));
+        Ok(plan)
+    }
+
     /// Ensures internal caches are sized to match the architecture.
     fn ensure_cache_size(&mut self, node_count: usize) {
         if self.values.len() < node_count {
@@ -87,6 +200,75 @@ impl ExecutionEngine {
         if self.node_gradients.len() < node_count {
             self.node_gradients.resize(node_count, None);
         }
+        if self.value_epochs.len() < node_count {
+            self.value_epochs.resize(node_count, 0);
+        }
+        if self.released_shapes.len() < node_count {
+            self.released_shapes.resize(node_count, Vec::new());
+        }
+        if self.node_seeds.len() < node_count {
+            self.node_seeds.resize(node_count, 0);
+        }
+    }
+
+    /// Records the shape of a value and drops it.
+    fn release_value(&mut self, id: NodeId) -> Option<Tensor> {
+        let value = self.values[id.0].take()?;
+        let shape = &mut self.released_shapes[id.0];
+        shape.clear();
+        shape.extend_from_slice(value.shape());
+        Some(value)
+    }
+
+    // ── Memory Planning ────────────────────────────────────────────────────
+
+    /// Installs a memory plan, or removes it with `None`.
+    ///
+    /// Forward passes over the plan's order then release each planned value
+    /// after its last use and compute later nodes into the freed buffers, so
+    /// [`values`](Self::values) only holds what the plan retains. Cached
+    /// values are cleared.
+    pub fn set_memory_plan(&mut self, plan: Option<MemoryPlan>) {
+        self.clear_values();
+        self.pool = vec![None; plan.as_ref().map_or(0, |p| p.buffer_count())];
+        self.memory_plan = plan;
+    }
+
+    /// Returns the installed memory plan.
+    pub fn memory_plan(&self) -> Option<&MemoryPlan> {
+        self.memory_plan.as_ref()
+    }
+
+    /// Enables activation checkpointing with `boundaries` as the segment
+    /// boundaries, or disables it with an empty slice.
+    ///
+    /// Training-mode forward passes then keep only the boundaries, the
+    /// target and the leaves, releasing every other op output after its
+    /// last use. [`backward`](Self::backward) recomputes released values
+    /// from the nearest retained ancestors when it needs them, one segment
+    /// at a time, trading compute for memory. Dropout masks are reproduced
+    /// exactly; buffer updates are not applied again.
+    pub fn set_activation_checkpoints(&mut self, boundaries: &[NodeId]) {
+        self.activation_checkpoints = boundaries.to_vec();
+        self.activation_checkpoints.sort_by_key(|id| id.0);
+        self.activation_checkpoints.dedup();
+    }
+
+    /// Returns the activation checkpoint boundaries (empty when disabled).
+    pub fn activation_checkpoints(&self) -> &[NodeId] {
+        &self.activation_checkpoints
+    }
+
+    fn checkpointing(&self) -> bool {
+        self.training && !self.activation_checkpoints.is_empty()
+    }
+
+    /// Whether checkpointing may release `id`: ops that are neither a
+    /// boundary nor the value being computed.
+    fn releasable(&self, arch: &Architecture, id: NodeId, target: NodeId) -> bool {
+        id != target
+            && matches!(arch.nodes()[id.0], Node::Op { .. })
+            && self.activation_checkpoints.binary_search_by_key(&id.0, |b| b.0).is_err()
     }
 
     // ── Parameter Sync ─────────────────────────────────────────────────────
@@ -113,10 +295,14 @@ impl ExecutionEngine {
     /// Accumulates per-node gradients for parameter nodes into the [`ParamStore`].
     ///
     /// Uses `accumulate_gradient` (not `set_gradient`) so that multiple backward
-    /// calls within a batch correctly sum their gradients.
+    /// calls within a batch correctly sum their gradients. Buffers are skipped.
     fn sync_param_gradients(&mut self, arch: &Architecture, params: &mut ParamStore) {
         for (node_idx, node) in arch.nodes().iter().enumerate() {
             if let Node::Param(param_id) = node {
+                if params.is_buffer(*param_id) {
+                    self.node_gradients[node_idx] = None;
+                    continue;
+                }
                 if let Some(grad) = self.node_gradients[node_idx].take() {
                     // Accumulate, not replace — critical for multi-sample batches
                     let _ = params.accumulate_gradient(*param_id, grad);
@@ -125,25 +311,46 @@ impl ExecutionEngine {
         }
     }
 
+    /// Writes buffer updates from training-mode forward passes (e.g. BatchNorm
+    /// running statistics) into the [`ParamStore`].
+    ///
+    /// [`Graph`](crate::graph::Graph) calls this after every execution; call it
+    /// yourself when driving the engine directly.
+    pub fn apply_buffer_updates(&mut self, params: &mut ParamStore) -> GPResult<()> {
+        for (id, value) in self.pending_buffers.drain(..) {
+            params.get_mut(id)
+                .ok_or_else(|| GPError::InferenceError(format!("Buffer {:?} not found", id)))?
+                .copy_from(&value)?;
+        }
+        Ok(())
+    }
+
     // ── Forward Pass ───────────────────────────────────────────────────────
 
     /// Executes the forward pass for the subgraph rooted at `target`.
     ///
-    /// Automatically computes topological order and syncs parameters.
+    /// Uses the cached [`ExecutionPlan`] for `target` (see [`plan`](Self::plan))
+    /// and syncs parameters.
+    ///
+    /// In training mode, buffer updates (e.g. BatchNorm running statistics)
+    /// are queued rather than written to `params`; apply them with
+    /// [`apply_buffer_updates`](Self::apply_buffer_updates) before the next
+    /// pass. Every forward pass discards updates left over from the previous
+    /// one, so the queue only ever holds the latest pass.
     pub fn forward(
         &mut self,
         arch: &Architecture,
         params: &ParamStore,
         target: NodeId,
     ) -> GPResult<Tensor> {
-        let order = arch.topological_sort(target)?;
-        self.forward_with_order(arch, params, &order, target)
+        let plan = self.plan(arch, &[target])?;
+        self.forward_plan(arch, params, &plan, target)
     }
 
     /// Executes the forward pass using a pre-computed topological order.
     ///
-    /// This is the hot-path for inference: called every frame per agent.
-    /// Reuses cached buffers where possible to minimize allocations.
+    /// Prefer [`forward_plan`](Self::forward_plan) on hot paths: this
+    /// validates `order` on every call.
     pub fn forward_with_order(
         &mut self,
         arch: &Architecture,
@@ -151,71 +358,277 @@ impl ExecutionEngine {
         order: &[NodeId],
         target: NodeId,
     ) -> GPResult<Tensor> {
+        let plan = ExecutionPlan::from_order(arch, order)?;
+        self.forward_plan(arch, params, &plan, target)
+    }
+
+    /// Executes the forward pass of a compiled [`ExecutionPlan`].
+    ///
+    /// This is the hot-path for inference: called every frame per agent.
+    /// Reuses cached buffers where possible to minimize allocations. If the
+    /// installed memory plan was built for the same order and keeps
+    /// `target`, it is applied. Fails if the architecture changed since the
+    /// plan was built.
+    pub fn forward_plan(
+        &mut self,
+        arch: &Architecture,
+        params: &ParamStore,
+        plan: &ExecutionPlan,
+        target: NodeId,
+    ) -> GPResult<Tensor> {
+        if !plan.is_current(arch) {
+            return Err(GPError::InferenceError(
+                "Execution plan is out of date: the architecture changed since it was built".to_string()
+            ));
+        }
+        let memory = self.memory_plan.take();
+        let result = match &memory {
+            Some(m) if m.order() == plan.order() && m.outputs().contains(&target) => {
+                self.forward_steps(arch, params, plan, Some(m), target)
+            }
+            _ => self.forward_steps(arch, params, plan, None, target),
+        };
+        self.memory_plan = memory;
+        result
+    }
+
+    fn forward_steps(
+        &mut self,
+        arch: &Architecture,
+        params: &ParamStore,
+        plan: &ExecutionPlan,
+        memory: Option<&MemoryPlan>,
+        target: NodeId,
+    ) -> GPResult<Tensor> {
+        self.pending_buffers.clear();
         self.sync_params(arch, params)?;
         self.ensure_cache_size(arch.node_count());
-        let backend = self.backend.as_ref();
+        let checkpoint_releases = if self.checkpointing() {
+            self.checkpoint_releases(arch, plan, target)
+        } else {
+            Vec::new()
+        };
 
-        for &node_id in order {
-            if node_id.0 >= arch.node_count() || node_id.0 >= self.values.len() {
-                return Err(GPError::InferenceError(format!(
-                    "Node index {} out of bounds", node_id.0
-                )));
-            }
+        // Pooled buffers are assigned for serial order, so memory plans run serially
+        if memory.is_none() && self.runs_parallel(plan) {
+            self.forward_levels(arch, plan, &checkpoint_releases)?;
+        } else {
+            self.forward_serial(arch, plan, memory, &checkpoint_releases)?;
+        }
 
+        self.values[target.0]
+            .as_ref()
+            .cloned()
+            .ok_or_else(|| GPError::InferenceError(format!(
+                "Target node {:?} not computed", target
+            )))
+    }
+
+    fn forward_serial(
+        &mut self,
+        arch: &Architecture,
+        plan: &ExecutionPlan,
+        memory: Option<&MemoryPlan>,
+        checkpoint_releases: &[Vec<NodeId>],
+    ) -> GPResult<()> {
+        for (step, &node_id) in plan.order().iter().enumerate() {
             match &arch.nodes()[node_id.0] {
-                Node::Input(t) => {
-                    if let Some(Some(cached)) = self.values.get_mut(node_id.0) {
-                        if cached.shape() == t.shape() {
-                            cached.copy_from(t)?;
-                        } else {
-                            *cached = t.clone();
-                        }
-                    } else {
-                        self.values[node_id.0] = Some(t.clone());
-                    }
-                }
+                Node::Input(t) => self.load_input(node_id, t)?,
                 Node::Param(_) => {
                     // Already synced via sync_params()
                 }
-                Node::Op { op, inputs } => {
+                Node::Op { op, .. } => {
+                    // The plan guarantees every input id is lower than the node's
+                    let inputs = plan.inputs(step);
                     let (left, right) = self.values.split_at_mut(node_id.0);
                     let out_opt = &mut right[0];
 
-                    let mut input_refs = Vec::with_capacity(inputs.len());
-                    for &input_id in inputs {
-                        if input_id.0 >= node_id.0 || input_id.0 >= left.len() {
-                            return Err(GPError::InferenceError(format!(
-                                "Input node {:?} is invalid or not before node {:?}",
-                                input_id, node_id
-                            )));
-                        }
-                        input_refs.push(left[input_id.0].as_ref().ok_or_else(|| {
-                            GPError::InferenceError(format!(
-                                "Input value not found for node {:?}", input_id
-                            ))
-                        })?);
-                    }
+                    let input_refs = input_values(left, inputs)?;
 
                     // Advance RNG seed per-node for unique Dropout masks
+                    let backend = self.backend.as_ref();
                     let seed = self.rng_counter;
                     self.rng_counter = self.rng_counter.wrapping_add(1);
+                    self.node_seeds[node_id.0] = seed;
 
-                    if let Some(out) = out_opt {
-                        op.forward_inplace(&input_refs, out, backend, self.training, seed)?;
-                    } else {
-                        let val = op.forward(&input_refs, backend, self.training, seed)?;
-                        *out_opt = Some(val);
+                    if self.training {
+                        collect_buffer_updates(arch, op, inputs, &input_refs, &mut self.pending_buffers)?;
+                    }
+
+                    // A planned node computes into its pool buffer, which
+                    // was released by a node of the same shape
+                    if let Some(slot) = memory.and_then(|m| m.slot(node_id)) {
+                        if out_opt.is_none() {
+                            *out_opt = take_pooled(&mut self.pool[slot], &self.released_shapes[node_id.0]);
+                        }
+                    }
+
+                    // Reuse the cached buffer unless an input changed shape since it was allocated
+                    match out_opt {
+                        Some(out) if self.value_epochs[node_id.0] == self.shape_epoch => {
+                            op.forward_inplace(&input_refs, out, backend, self.training, seed)?;
+                        }
+                        _ => {
+                            let val = op.forward(&input_refs, backend, self.training, seed)?;
+                            *out_opt = Some(val);
+                            self.value_epochs[node_id.0] = self.shape_epoch;
+                        }
                     }
                 }
             };
+
+            if let Some(memory) = memory {
+                for &released in memory.releases_after(step) {
+                    if let Some(slot) = memory.slot(released) {
+                        self.pool[slot] = self.release_value(released).or(self.pool[slot].take());
+                    }
+                }
+            }
+            if let Some(released) = checkpoint_releases.get(step) {
+                for &id in released {
+                    self.release_value(id);
+                }
+            }
         }
+        Ok(())
+    }
 
-        self.values[target.0]
-            .as_ref()
-            .cloned()
-            .ok_or_else(|| GPError::InferenceError(format!(
-                "Target node {:?} not computed", target
-            )))
+    /// Copies an input node's tensor into the value cache.
+    fn load_input(&mut self, node_id: NodeId, t: &Tensor) -> GPResult<()> {
+        if let Some(Some(cached)) = self.values.get_mut(node_id.0) {
+            if cached.shape() == t.shape() {
+                cached.copy_from(t)?;
+            } else {
+                *cached = t.clone();
+                self.shape_epoch += 1;
+            }
+        } else {
+            self.values[node_id.0] = Some(t.clone());
+        }
+        Ok(())
+    }
+
+    /// Parallel forward: the ops of each level run concurrently, each
+    /// computing into its own cached buffer while reading earlier levels.
+    ///
+    /// Seeds are handed out in plan order, exactly as the serial pass does,
+    /// so Dropout masks and results do not depend on scheduling.
+    #[cfg(feature = "rayon")]
+    fn forward_levels(&mut self, arch: &Architecture, plan: &ExecutionPlan, checkpoint_releases: &[Vec<NodeId>]) -> GPResult<()> {
+        use rayon::prelude::*;
+
+        let mut seeds = vec![0; plan.order().len()];
+        for (step, &node_id) in plan.order().iter().enumerate() {
+            if matches!(arch.nodes()[node_id.0], Node::Op { .. }) {
+                seeds[step] = self.rng_counter;
+                self.rng_counter = self.rng_counter.wrapping_add(1);
+                self.node_seeds[node_id.0] = seeds[step];
+            }
+        }
+
+        for level in plan.levels() {
+            let mut tasks = Vec::new();
+            for &step in level {
+                let node_id = plan.order()[step];
+                match &arch.nodes()[node_id.0] {
+                    Node::Input(t) => self.load_input(node_id, t)?,
+                    Node::Param(_) => {}
+                    Node::Op { op, .. } => {
+                        if self.training {
+                            let inputs = plan.inputs(step);
+                            let input_refs = input_values(&self.values, inputs)?;
+                            collect_buffer_updates(arch, op, inputs, &input_refs, &mut self.pending_buffers)?;
+                        }
+                        // Reuse the cached buffer unless an input changed shape since it was allocated
+                        let out = self.values[node_id.0].take()
+                            .filter(|_| self.value_epochs[node_id.0] == self.shape_epoch);
+                        tasks.push((step, op, out));
+                    }
+                }
+            }
+
+            let work = tasks.iter()
+                .flat_map(|&(step, ..)| plan.inputs(step))
+                .filter_map(|id| self.values[id.0].as_ref().map(Tensor::len))
+                .sum();
+            let parallel = self.fans_out(tasks.len(), work);
+
+            let (values, backend, training) = (&self.values, self.backend.as_ref(), self.training);
+            let run = |(step, op, out): (usize, &OpType, Option<Tensor>)| {
+                let result = input_values(values, plan.inputs(step)).and_then(|input_refs| match out {
+                    Some(mut out) => {
+                        op.forward_inplace(&input_refs, &mut out, backend, training, seeds[step])?;
+                        Ok((out, false))
+                    }
+                    None => Ok((op.forward(&input_refs, backend, training, seeds[step])?, true)),
+                });
+                (step, result)
+            };
+            let results: Vec<(usize, GPResult<(Tensor, bool)>)> = if parallel {
+                tasks.into_par_iter().map(run).collect()
+            } else {
+                tasks.into_iter().map(run).collect()
+            };
+
+            for (step, result) in results {
+                let (value, fresh) = result?;
+                let id = plan.order()[step].0;
+                self.values[id] = Some(value);
+                if fresh {
+                    self.value_epochs[id] = self.shape_epoch;
+                }
+            }
+            for released in level.iter().filter_map(|&step| checkpoint_releases.get(step)) {
+                for &id in released {
+                    self.release_value(id);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn forward_levels(&mut self, arch: &Architecture, plan: &ExecutionPlan, checkpoint_releases: &[Vec<NodeId>]) -> GPResult<()> {
+        self.forward_serial(arch, plan, None, checkpoint_releases)
+    }
+
+    /// Values that activation checkpointing releases after each step of
+    /// `plan`: every releasable op, once its last consumer has run.
+    fn checkpoint_releases(&self, arch: &Architecture, plan: &ExecutionPlan, target: NodeId) -> Vec<Vec<NodeId>> {
+        let mut releases = vec![Vec::new(); plan.order().len()];
+        for (step, &id) in plan.order().iter().enumerate() {
+            if self.releasable(arch, id, target) {
+                releases[plan.last_use(step)].push(id);
+            }
+        }
+        releases
+    }
+
+    /// Recomputes the released values `ids` depend on, then `ids` themselves,
+    /// in node order (which is topological), from the retained values.
+    fn recompute(&mut self, arch: &Architecture, ids: &[NodeId]) -> GPResult<()> {
+        let mut missing = Vec::new();
+        let mut stack: Vec<NodeId> = ids.iter().copied().filter(|id| self.values[id.0].is_none()).collect();
+        while let Some(id) = stack.pop() {
+            if missing.contains(&id) {
+                continue;
+            }
+            let Node::Op { inputs, .. } = &arch.nodes()[id.0] else {
+                return Err(GPError::InferenceError(format!("Value not found for node {:?}", id)));
+            };
+            missing.push(id);
+            stack.extend(inputs.iter().filter(|i| self.values[i.0].is_none()));
+        }
+        missing.sort_by_key(|id| id.0);
+
+        let backend = self.backend.as_ref();
+        for id in missing {
+            let Node::Op { op, inputs } = &arch.nodes()[id.0] else { continue };
+            let input_refs = input_values(&self.values, inputs)?;
+            let value = op.forward(&input_refs, backend, self.training, self.node_seeds[id.0])?;
+            self.values[id.0] = Some(value);
+        }
+        Ok(())
     }
 
     /// Executes a single node. Used by the WASM bridge for per-node execution
@@ -227,20 +640,9 @@ impl ExecutionEngine {
         node_id: NodeId,
     ) -> GPResult<()> {
         self.ensure_cache_size(arch.node_count());
-        let backend = self.backend.as_ref();
 
         match &arch.nodes()[node_id.0] {
-            Node::Input(t) => {
-                if let Some(Some(cached)) = self.values.get_mut(node_id.0) {
-                    if cached.shape() == t.shape() {
-                        cached.copy_from(t)?;
-                    } else {
-                        *cached = t.clone();
-                    }
-                } else {
-                    self.values[node_id.0] = Some(t.clone());
-                }
-            }
+            Node::Input(t) => self.load_input(node_id, t)?,
             Node::Param(param_id) => {
                 let t = params.tensor(*param_id);
                 if self.values[node_id.0].is_none() {
@@ -260,14 +662,24 @@ impl ExecutionEngine {
                     })?);
                 }
 
+                let backend = self.backend.as_ref();
                 let seed = self.rng_counter;
                 self.rng_counter = self.rng_counter.wrapping_add(1);
+                self.node_seeds[node_id.0] = seed;
 
-                if let Some(out) = out_opt {
-                    op.forward_inplace(&input_refs, out, backend, self.training, seed)?;
-                } else {
-                    let val = op.forward(&input_refs, backend, self.training, seed)?;
-                    *out_opt = Some(val);
+                if self.training {
+                    collect_buffer_updates(arch, op, inputs, &input_refs, &mut self.pending_buffers)?;
+                }
+
+                match out_opt {
+                    Some(out) if self.value_epochs[node_id.0] == self.shape_epoch => {
+                        op.forward_inplace(&input_refs, out, backend, self.training, seed)?;
+                    }
+                    _ => {
+                        let val = op.forward(&input_refs, backend, self.training, seed)?;
+                        *out_opt = Some(val);
+                        self.value_epochs[node_id.0] = self.shape_epoch;
+                    }
                 }
             }
         };
@@ -287,9 +699,8 @@ impl ExecutionEngine {
         target: NodeId,
         grad_output: Tensor,
     ) -> GPResult<()> {
-        let order = arch.topological_sort(target)?;
+        let plan = self.plan(arch, &[target])?;
         self.ensure_cache_size(arch.node_count());
-        let backend = self.backend.as_ref();
 
         // Initialize/Accumulate target gradient
         if let Some(existing) = &self.node_gradients[target.0] {
@@ -298,8 +709,21 @@ impl ExecutionEngine {
             self.node_gradients[target.0] = Some(grad_output);
         }
 
-        // Process in reverse topological order
-        for &node_id in order.iter().rev() {
+        if self.runs_parallel(&plan) {
+            self.backward_levels(arch, &plan, target)?;
+        } else {
+            self.backward_steps(arch, &plan, target)?;
+        }
+
+        // Forward parameter gradients to ParamStore
+        self.sync_param_gradients(arch, params);
+
+        Ok(())
+    }
+
+    /// Serial backward: processes the plan in reverse order.
+    fn backward_steps(&mut self, arch: &Architecture, plan: &ExecutionPlan, target: NodeId) -> GPResult<()> {
+        for (step, &node_id) in plan.order().iter().enumerate().rev() {
             let grad = match self.node_gradients[node_id.0].take() {
                 Some(g) => g,
                 None => continue,
@@ -308,23 +732,14 @@ impl ExecutionEngine {
             // Keep the gradient for param gradient forwarding
             self.node_gradients[node_id.0] = Some(grad.clone());
 
-            let (op, inputs) = match &arch.nodes()[node_id.0] {
-                Node::Op { op, inputs } => (op, inputs),
+            let op = match &arch.nodes()[node_id.0] {
+                Node::Op { op, .. } => op,
                 _ => continue, // Leaf nodes don't propagate
             };
+            let inputs = plan.inputs(step);
+            self.restore_for_backward(arch, op, inputs, node_id)?;
 
-            let mut input_refs = Vec::with_capacity(inputs.len());
-            for &id in inputs {
-                input_refs.push(self.values[id.0].as_ref().ok_or_else(|| {
-                    GPError::InferenceError(format!(
-                        "Value not found for node {:?}", id
-                    ))
-                })?);
-            }
-
-            // Pass the cached output of this node to backward (needed by Dropout)
-            let node_output = self.values[node_id.0].as_ref();
-            let input_grads = op.backward(&input_refs, node_output, &grad, backend)?;
+            let input_grads = self.node_backward(op, inputs, node_id, &grad)?;
             for (i, &input_id) in inputs.iter().enumerate() {
                 if let Some(existing) = &self.node_gradients[input_id.0] {
                     self.node_gradients[input_id.0] = Some(existing + &input_grads[i]);
@@ -332,14 +747,162 @@ impl ExecutionEngine {
                     self.node_gradients[input_id.0] = Some(input_grads[i].clone());
                 }
             }
+
+            // Every consumer of this node is done: drop recomputed values again
+            if self.checkpointing() && self.releasable(arch, node_id, target) {
+                self.release_value(node_id);
+            }
         }
+        Ok(())
+    }
 
-        // Forward parameter gradients to ParamStore
-        self.sync_param_gradients(arch, params);
+    /// Checkpointing: brings back the released values an op's backward reads.
+    fn restore_for_backward(&mut self, arch: &Architecture, op: &OpType, inputs: &[NodeId], node_id: NodeId) -> GPResult<()> {
+        if !self.checkpointing() {
+            return Ok(());
+        }
+        let mut needed: Vec<NodeId> = Vec::new();
+        if op.backward_needs_input_values() {
+            needed.extend(inputs.iter().filter(|id| self.values[id.0].is_none()));
+        }
+        if op.backward_needs_output() && self.values[node_id.0].is_none() {
+            needed.push(node_id);
+        }
+        if needed.is_empty() {
+            return Ok(());
+        }
+        self.recompute(arch, &needed)
+    }
 
+    /// Gradients of one op's inputs, from the cached values.
+    fn node_backward(&self, op: &OpType, inputs: &[NodeId], node_id: NodeId, grad: &Tensor) -> GPResult<Vec<Tensor>> {
+        let backend = self.backend.as_ref();
+        // Pass the cached output of this node to backward (needed by Dropout)
+        let node_output = self.values[node_id.0].as_ref();
+        match inputs.iter().find(|id| self.values[id.0].is_none()) {
+            None => {
+                let input_refs: Vec<&Tensor> = inputs.iter()
+                    .filter_map(|id| self.values[id.0].as_ref())
+                    .collect();
+                op.backward_in_mode(&input_refs, node_output, grad, backend, self.training)
+            }
+            Some(missing) if op.backward_needs_input_values() => {
+                Err(GPError::InferenceError(format!(
+                    "Value not found for node {:?}", missing
+                )))
+            }
+            // Released by a memory plan: ops that only need their input
+            // shapes can still run
+            Some(_) => {
+                let shapes: Vec<&[usize]> = inputs.iter()
+                    .map(|id| match &self.values[id.0] {
+                        Some(v) => v.shape(),
+                        None => self.released_shapes[id.0].as_slice(),
+                    })
+                    .collect();
+                op.backward_from_shapes(&shapes, node_output, grad, backend)
+            }
+        }
+    }
+
+    /// Parallel backward: levels run in reverse, the ops of a level compute
+    /// their input gradients concurrently.
+    ///
+    /// Contributions to each node's gradient are summed in the same order as
+    /// the serial pass (descending step, then input index), so results are
+    /// bit-identical to it.
+    #[cfg(feature = "rayon")]
+    fn backward_levels(&mut self, arch: &Architecture, plan: &ExecutionPlan, target: NodeId) -> GPResult<()> {
+        use rayon::prelude::*;
+
+        let mut contributions: Vec<Vec<(usize, Tensor)>> = vec![Vec::new(); arch.node_count()];
+        for level in plan.levels().iter().rev() {
+            // Every consumer of this level ran in a later level: sum their contributions
+            for &step in level {
+                let id = plan.order()[step].0;
+                let mut parts = std::mem::take(&mut contributions[id]);
+                parts.sort_by_key(|part| std::cmp::Reverse(part.0));
+                for (_, part) in parts {
+                    self.node_gradients[id] = Some(match self.node_gradients[id].take() {
+                        Some(existing) => &existing + &part,
+                        None => part,
+                    });
+                }
+            }
+
+            let mut tasks = Vec::new();
+            for &step in level {
+                let node_id = plan.order()[step];
+                if let (Node::Op { op, .. }, Some(_)) = (&arch.nodes()[node_id.0], &self.node_gradients[node_id.0]) {
+                    self.restore_for_backward(arch, op, plan.inputs(step), node_id)?;
+                    tasks.push((step, op));
+                }
+            }
+
+            let work = tasks.iter()
+                .flat_map(|&(step, _)| plan.inputs(step))
+                .filter_map(|id| self.values[id.0].as_ref().map(Tensor::len))
+                .sum();
+            let parallel = self.fans_out(tasks.len(), work);
+
+            let this = &*self;
+            let run = |&(step, op): &(usize, &OpType)| {
+                let node_id = plan.order()[step];
+                let grad = this.node_gradients[node_id.0].as_ref().expect("filtered above");
+                this.node_backward(op, plan.inputs(step), node_id, grad)
+            };
+            let results: Vec<GPResult<Vec<Tensor>>> = if parallel {
+                tasks.par_iter().map(run).collect()
+            } else {
+                tasks.iter().map(run).collect()
+            };
+
+            for (&(step, _), grads) in tasks.iter().zip(results) {
+                for (&input, grad) in plan.inputs(step).iter().zip(grads?) {
+                    contributions[input.0].push((step, grad));
+                }
+                let node_id = plan.order()[step];
+                if self.checkpointing() && self.releasable(arch, node_id, target) {
+                    self.release_value(node_id);
+                }
+            }
+        }
         Ok(())
     }
 
+    #[cfg(not(feature = "rayon"))]
+    fn backward_levels(&mut self, arch: &Architecture, plan: &ExecutionPlan, target: NodeId) -> GPResult<()> {
+        self.backward_steps(arch, plan, target)
+    }
+
+    /// Backward pass from a single-element node (typically a loss op),
+    /// seeded with an implicit gradient of 1.
+    ///
+    /// The node must have been evaluated by a preceding forward pass.
+    pub fn backward_scalar(
+        &mut self,
+        arch: &Architecture,
+        params: &mut ParamStore,
+        target: NodeId,
+    ) -> GPResult<()> {
+        let shape = self.values.get(target.0)
+            .and_then(|v| v.as_ref())
+            .map(|v| v.shape().to_vec())
+            .ok_or_else(|| GPError::InferenceError(format!(
+                "Value not found for node {:?}; run forward before backward_scalar", target
+            )))?;
+        let len: usize = shape.iter().product();
+        if len != 1 {
+            return Err(GPError::IncompatibleShapes {
+                expected: vec![1],
+                found: shape,
+                exp_len: 1,
+                found_len: len,
+            });
+        }
+        self.backward(arch, params, target, Tensor::new_ones(&shape))
+    }
+
     // ── Cache Management ───────────────────────────────────────────────────
 
     /// Clears all cached activation values, forcing recomputation on next forward.
@@ -365,6 +928,44 @@ impl ExecutionEngine {
     }
 }
 
+/// Looks up the cached values of `inputs`.
+fn input_values<'a>(values: &'a [Option<Tensor>], inputs: &[NodeId]) -> GPResult<Vec<&'a Tensor>> {
+    inputs.iter()
+        .map(|id| values[id.0].as_ref().ok_or_else(|| GPError::InferenceError(format!(
+            "Input value not found for node {:?}", id
+        ))))
+        .collect()
+}
+
+/// Queues the buffer updates an op produces in training mode, mapping op input
+/// indices to the [`ParamId`]s of the buffer nodes feeding them.
+/// Takes the buffer pooled in `slot` if it has `shape`; a buffer of another
+/// shape stays in the pool for the slot's next user.
+fn take_pooled(slot: &mut Option<Tensor>, shape: &[usize]) -> Option<Tensor> {
+    match slot.take() {
+        Some(buf) if buf.shape() == shape => Some(buf),
+        other => {
+            *slot = other;
+            None
+        }
+    }
+}
+
+fn collect_buffer_updates(
+    arch: &Architecture,
+    op: &OpType,
+    inputs: &[NodeId],
+    input_refs: &[&Tensor],
+    pending: &mut Vec<(ParamId, Tensor)>,
+) -> GPResult<()> {
+    for (idx, value) in op.buffer_updates(input_refs)? {
+        if let Node::Param(param_id) = &arch.nodes()[inputs[idx].0] {
+            pending.push((*param_id, value));
+        }
+    }
+    Ok(())
+}
+
 // ── Tests ──────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -405,11 +1006,7 @@ mod tests {
         let mut engine = ExecutionEngine::new(Box::new(CPUBackend));
 
         // Set input data
-        if let Some(Node::Input(t)) = arch.nodes_mut().get_mut(0) {
-            let slice = t.as_slice_mut().unwrap();
-            slice[0] = 1.0;
-            slice[1] = 2.0;
-        }
+        arch.feed(NodeId(0), &Tensor::from_shape_vec(&[1, 2], vec![1.0, 2.0]).unwrap()).unwrap();
 
         let result = engine.forward(&arch, &params, NodeId(4)).unwrap();
         assert_eq!(result.shape(), &[1, 3]);
@@ -420,11 +1017,7 @@ mod tests {
         let (mut arch, params) = build_linear_arch();
         let mut engine = ExecutionEngine::new(Box::new(CPUBackend));
 
-        if let Some(Node::Input(t)) = arch.nodes_mut().get_mut(0) {
-            let slice = t.as_slice_mut().unwrap();
-            slice[0] = 1.0;
-            slice[1] = 2.0;
-        }
+        arch.feed(NodeId(0), &Tensor::from_shape_vec(&[1, 2], vec![1.0, 2.0]).unwrap()).unwrap();
 
         let order = arch.topological_sort(NodeId(4)).unwrap();
         let r1 = engine.forward_with_order(&arch, &params, &order, NodeId(4)).unwrap();
@@ -439,11 +1032,7 @@ mod tests {
         let (mut arch, mut params) = build_linear_arch();
         let mut engine = ExecutionEngine::new(Box::new(CPUBackend));
 
-        if let Some(Node::Input(t)) = arch.nodes_mut().get_mut(0) {
-            let slice = t.as_slice_mut().unwrap();
-            slice[0] = 1.0;
-            slice[1] = -1.0;
-        }
+        arch.feed(NodeId(0), &Tensor::from_shape_vec(&[1, 2], vec![1.0, -1.0]).unwrap()).unwrap();
 
         // Forward
         let result = engine.forward(&arch, &params, NodeId(4)).unwrap();
@@ -468,11 +1057,7 @@ mod tests {
         let (mut arch, params) = build_linear_arch();
         let mut engine = ExecutionEngine::new(Box::new(CPUBackend));
 
-        if let Some(Node::Input(t)) = arch.nodes_mut().get_mut(0) {
-            let slice = t.as_slice_mut().unwrap();
-            slice[0] = 1.0;
-            slice[1] = 2.0;
-        }
+        arch.feed(NodeId(0), &Tensor::from_shape_vec(&[1, 2], vec![1.0, 2.0]).unwrap()).unwrap();
 
         // Sync params first
         engine.sync_params(&arch, &params).unwrap();
@@ -485,6 +1070,31 @@ mod tests {
 
         // Check output exists
         assert!(engine.values()[4].is_some());
+
+        // Seeds are recorded as in a full forward pass, for recomputation
+        let mut full = ExecutionEngine::new(Box::new(CPUBackend));
+        full.forward(&arch, &params, NodeId(4)).unwrap();
+        assert_eq!(engine.node_seeds, full.node_seeds);
+    }
+
+    #[test]
+    fn test_engine_plan_cache() {
+        let (mut arch, params) = build_linear_arch();
+        let mut engine = ExecutionEngine::new(Box::new(CPUBackend));
+
+        let plan = engine.plan(&arch, &[NodeId(4)]).unwrap();
+        assert!(Arc::ptr_eq(&plan, &engine.plan(&arch, &[NodeId(4)]).unwrap()));
+
+        // Feeding keeps the plan; adding a node invalidates it
+        arch.feed(NodeId(0), &Tensor::from_shape_vec(&[1, 2], vec![1.0, 2.0]).unwrap()).unwrap();
+        assert!(Arc::ptr_eq(&plan, &engine.plan(&arch, &[NodeId(4)]).unwrap()));
+        let relu = arch.op(OpType::ReLU, vec![NodeId(4)]);
+        assert!(engine.forward_plan(&arch, &params, &plan, NodeId(4)).is_err());
+
+        let fresh = engine.plan(&arch, &[NodeId(4)]).unwrap();
+        assert!(!Arc::ptr_eq(&plan, &fresh));
+        let out = engine.forward(&arch, &params, relu).unwrap();
+        assert!(out.as_slice().unwrap().iter().all(|&v| v >= 0.0));
     }
 
     #[test]
@@ -498,4 +1108,39 @@ mod tests {
         engine.clear_values();
         assert!(engine.values().iter().all(|v| v.is_none()));
     }
+
+    #[test]
+    fn test_take_pooled_keeps_mismatched_buffers() {
+        let mut slot = Some(Tensor::new_zeros(&[2, 3]));
+        assert!(take_pooled(&mut slot, &[3, 2]).is_none());
+        assert_eq!(slot.as_ref().unwrap().shape(), &[2, 3]);
+
+        assert_eq!(take_pooled(&mut slot, &[2, 3]).unwrap().shape(), &[2, 3]);
+        assert!(slot.is_none());
+        assert!(take_pooled(&mut slot, &[2, 3]).is_none());
+    }
+
+    #[test]
+    fn test_engine_forward_keeps_only_latest_buffer_updates() {
+        let mut arch = Architecture::new();
+        let mut params = ParamStore::new();
+        let x = arch.input(Tensor::from_shape_vec(&[2, 1], vec![1.0, 3.0]).unwrap());
+        let mut inputs = vec![x];
+        for (value, name) in [(1.0, "gamma"), (0.0, "beta"), (0.0, "running_mean"), (1.0, "running_var")] {
+            let id = params.register(Tensor::from_shape_vec(&[1], vec![value]).unwrap(), name);
+            inputs.push(arch.param(id));
+        }
+        let out = arch.op(OpType::BatchNorm { epsilon: 1e-5, momentum: 0.5 }, inputs);
+
+        let mut engine = ExecutionEngine::new(Box::new(CPUBackend));
+        engine.set_training(true);
+        for _ in 0..3 {
+            engine.forward(&arch, &params, out).unwrap();
+        }
+        assert_eq!(engine.pending_buffers.len(), 2);
+
+        engine.apply_buffer_updates(&mut params).unwrap();
+        assert!(engine.pending_buffers.is_empty());
+        assert_eq!(params.tensor(ParamId(2)).as_slice().unwrap(), &[1.0]);
+    }
 }
@@ -0,0 +1,363 @@
+//! Static memory planning — liveness-based buffer reuse for op outputs.
+//!
+//! Without a plan, [`ExecutionEngine`](super::ExecutionEngine) keeps the value
+//! of every node it has evaluated, so a deep graph holds all intermediate
+//! activations even during pure inference. A [`MemoryPlan`] walks a fixed
+//! execution order, finds the last step that reads each op output, and
+//! assigns outputs whose lifetimes do not overlap to the same buffer.
+//!
+//! | Mode                     | Kept after the forward pass                   |
+//! |--------------------------|-----------------------------------------------|
+//! | [`PlanMode::Inference`]  | The planned outputs only                      |
+//! | [`PlanMode::Training`]   | Outputs and every value `backward` reads      |
+//!
+//! Input and parameter nodes are never planned: their tensors are owned by the
+//! [`Architecture`] and [`ParamStore`](crate::params::ParamStore) anyway.
+
+use crate::{GPError, GPResult, NodeId};
+use super::{Architecture, Node};
+use std::collections::HashMap;
+use std::fmt;
+
+/// What the planned forward passes are used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanMode {
+    /// Forward only: every intermediate is released after its last use.
+    Inference,
+    /// Forward followed by `backward`: values read by an op's backward
+    /// (see [`OpType::backward_needs_input_values`](super::OpType::backward_needs_input_values))
+    /// are retained, everything else is released.
+    Training,
+}
+
+/// Memory usage of a plan, in bytes of `f32` op outputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryReport {
+    pub mode: PlanMode,
+    /// Op nodes in the planned order.
+    pub ops: usize,
+    /// Bytes held when every op output is kept (the engine's default).
+    pub unplanned_bytes: usize,
+    /// Most bytes of op outputs alive at once during the forward pass.
+    pub peak_bytes: usize,
+    /// Reusable buffers in the pool.
+    pub buffers: usize,
+    /// Total size of the pooled buffers.
+    pub buffer_bytes: usize,
+    /// Bytes of op outputs kept after the forward pass.
+    pub retained_bytes: usize,
+}
+
+impl fmt::Display for MemoryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} plan: {} ops, peak {} B (unplanned {} B), {} buffers ({} B), {} B retained",
+            self.mode, self.ops, self.peak_bytes, self.unplanned_bytes,
+            self.buffers, self.buffer_bytes, self.retained_bytes
+        )
+    }
+}
+
+/// Buffer assignment for one execution order.
+///
+/// Built from a topological order (e.g. [`Architecture::topological_sort`]
+/// or [`Architecture::execution_order`]) and the static shapes from
+/// [`Verifier::verify`](super::verifier::Verifier::verify). Install it with
+/// [`ExecutionEngine::set_memory_plan`](super::ExecutionEngine::set_memory_plan)
+/// or [`Graph::plan_memory`](super::Graph::plan_memory).
+#[derive(Debug, Clone)]
+pub struct MemoryPlan {
+    order: Vec<NodeId>,
+    outputs: Vec<NodeId>,
+    /// Pool buffer of each node, indexed by node id; `None` is never released.
+    slots: Vec<Option<usize>>,
+    /// Nodes whose values are released after each step of `order`.
+    releases: Vec<Vec<NodeId>>,
+    report: MemoryReport,
+}
+
+impl MemoryPlan {
+    /// Plans buffers for running `order` to compute `outputs`.
+    ///
+    /// Op outputs share a buffer only when their shapes match exactly, so
+    /// they can be computed in place into it.
+    pub fn new(
+        arch: &Architecture,
+        order: &[NodeId],
+        outputs: &[NodeId],
+        shapes: &HashMap<NodeId, Vec<usize>>,
+        mode: PlanMode,
+    ) -> GPResult<Self> {
+        let nodes = arch.nodes();
+        let mut position = vec![None; nodes.len()];
+        for (step, &id) in order.iter().enumerate() {
+            let slot = position.get_mut(id.0).ok_or_else(|| GPError::InferenceError(format!(
+                "Node index {} out of bounds", id.0
+            )))?;
+            *slot = Some(step);
+        }
+        for id in outputs {
+            if position.get(id.0).copied().flatten().is_none() {
+                return Err(GPError::InferenceError(format!(
+                    "Output {:?} is not in the execution order", id
+                )));
+            }
+        }
+
+        // Liveness: the last step reading each value, and whether backward needs it
+        let mut last_use: Vec<usize> = (0..nodes.len()).map(|i| position[i].unwrap_or(0)).collect();
+        let mut retained = vec![false; nodes.len()];
+        for id in outputs {
+            retained[id.0] = true;
+        }
+        for (step, &id) in order.iter().enumerate() {
+            let Node::Op { op, inputs } = &nodes[id.0] else { continue };
+            for input in inputs {
+                if position.get(input.0).copied().flatten().is_none_or(|p| p >= step) {
+                    return Err(GPError::InferenceError(format!(
+                        "Input {:?} of node {:?} is not computed before it", input, id
+                    )));
+                }
+                last_use[input.0] = last_use[input.0].max(step);
+            }
+            if mode == PlanMode::Training {
+                if op.backward_needs_input_values() {
+                    for input in inputs {
+                        retained[input.0] = true;
+                    }
+                }
+                if op.backward_needs_output() {
+                    retained[id.0] = true;
+                }
+            }
+        }
+
+        let bytes_of = |id: &NodeId| -> GPResult<usize> {
+            let shape = shapes.get(id).ok_or_else(|| GPError::InferenceError(format!(
+                "No shape for node {:?}", id
+            )))?;
+            Ok(shape.iter().product::<usize>() * std::mem::size_of::<f32>())
+        };
+
+        let mut slots = vec![None; nodes.len()];
+        let mut releases = vec![Vec::new(); order.len()];
+        let mut slot_shapes: Vec<&[usize]> = Vec::new();
+        let mut free: Vec<usize> = Vec::new();
+        let (mut ops, mut unplanned, mut live, mut peak, mut retained_bytes) = (0, 0, 0, 0, 0);
+
+        for (step, &id) in order.iter().enumerate() {
+            if matches!(nodes[id.0], Node::Op { .. }) {
+                let bytes = bytes_of(&id)?;
+                ops += 1;
+                unplanned += bytes;
+                live += bytes;
+                peak = peak.max(live);
+
+                if retained[id.0] {
+                    retained_bytes += bytes;
+                } else {
+                    // Take a free buffer of the same shape, or grow the pool
+                    let shape = shapes[&id].as_slice();
+                    let slot = match free.iter().position(|&s| slot_shapes[s] == shape) {
+                        Some(i) => free.swap_remove(i),
+                        None => {
+                            slot_shapes.push(shape);
+                            slot_shapes.len() - 1
+                        }
+                    };
+                    slots[id.0] = Some(slot);
+                    releases[last_use[id.0]].push(id);
+                }
+            }
+
+            // Values last read by this step hand their buffers back to the pool
+            for released in &releases[step] {
+                live -= bytes_of(released)?;
+                free.extend(slots[released.0]);
+            }
+        }
+
+        let report = MemoryReport {
+            mode,
+            ops,
+            unplanned_bytes: unplanned,
+            peak_bytes: peak,
+            buffers: slot_shapes.len(),
+            buffer_bytes: slot_shapes.iter()
+                .map(|s| s.iter().product::<usize>() * std::mem::size_of::<f32>())
+                .sum(),
+            retained_bytes,
+        };
+        Ok(Self {
+            order: order.to_vec(),
+            outputs: outputs.to_vec(),
+            slots,
+            releases,
+            report,
+        })
+    }
+
+    /// The execution order the plan was built for.
+    pub fn order(&self) -> &[NodeId] {
+        &self.order
+    }
+
+    /// The nodes whose values are kept after the forward pass.
+    pub fn outputs(&self) -> &[NodeId] {
+        &self.outputs
+    }
+
+    /// The pool buffer assigned to `id`, or `None` if its value is never released.
+    pub fn slot(&self, id: NodeId) -> Option<usize> {
+        self.slots.get(id.0).copied().flatten()
+    }
+
+    /// Nodes whose values can be released once step `step` of the order has run.
+    pub fn releases_after(&self, step: usize) -> &[NodeId] {
+        &self.releases[step]
+    }
+
+    /// Number of buffers in the pool.
+    pub fn buffer_count(&self) -> usize {
+        self.report.buffers
+    }
+
+    pub fn report(&self) -> &MemoryReport {
+        &self.report
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::cpu::CPUBackend;
+    use crate::graph::dsl::GraphBuilder;
+    use crate::graph::Graph;
+    use crate::Tensor;
+
+    /// x → tanh → relu → sigmoid → tanh → relu → sigmoid, all `[4, 8]`.
+    fn chain() -> (Graph, NodeId, Vec<NodeId>) {
+        let mut graph = Graph::new(Box::new(CPUBackend));
+        let mut gb = GraphBuilder::new(&mut graph);
+        let x = gb.batched_placeholder(&[4, 8]);
+        let mut ops = Vec::new();
+        let mut h = x;
+        for _ in 0..2 {
+            h = gb.tanh(h);
+            ops.push(h);
+            h = gb.relu(h);
+            ops.push(h);
+            h = gb.sigmoid(h);
+            ops.push(h);
+        }
+        (graph, x, ops)
+    }
+
+    /// Two-layer MLP with an MSE loss: returns (graph, x, target, [mm1, pre1, hidden, mm2, out], loss).
+    fn mlp() -> (Graph, NodeId, NodeId, Vec<NodeId>, NodeId) {
+        let mut graph = Graph::new(Box::new(CPUBackend));
+        let mut gb = GraphBuilder::new(&mut graph);
+        let x = gb.placeholder(&[2, 3]);
+        let target = gb.placeholder(&[2, 1]);
+        let w1 = gb.param(Tensor::from_shape_vec(&[3, 4], (0..12).map(|i| (i as f32 - 6.0) * 0.1).collect()).unwrap());
+        let b1 = gb.param(Tensor::from_shape_vec(&[1, 4], vec![0.1, -0.2, 0.3, 0.0]).unwrap());
+        let w2 = gb.param(Tensor::from_shape_vec(&[4, 1], vec![0.5, -0.5, 0.25, 1.0]).unwrap());
+        let b2 = gb.param(Tensor::from_shape_vec(&[1, 1], vec![0.1]).unwrap());
+        let mm1 = gb.matmul(x, w1);
+        let pre1 = gb.add(mm1, b1);
+        let hidden = gb.relu(pre1);
+        let mm2 = gb.matmul(hidden, w2);
+        let out = gb.add(mm2, b2);
+        let loss = gb.mse_loss(out, target);
+        (graph, x, target, vec![mm1, pre1, hidden, mm2, out], loss)
+    }
+
+    fn param_grads(graph: &Graph) -> Vec<Vec<f32>> {
+        graph.params().trainable_param_ids().into_iter()
+            .map(|id| graph.params().gradient(id).unwrap().as_slice().unwrap().to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn test_inference_plan_reuses_buffers() {
+        let (mut graph, x, ops) = chain();
+        let out = *ops.last().unwrap();
+        let input = Tensor::from_shape_vec(&[4, 8], (0..32).map(|i| i as f32 * 0.1 - 1.5).collect()).unwrap();
+        let expected = graph.run(&[(x, &input)], &[out]).unwrap();
+
+        let report = graph.plan_memory(&[out], PlanMode::Inference).unwrap();
+        let bytes = 4 * 8 * 4;
+        assert_eq!(report.ops, 6);
+        assert_eq!(report.unplanned_bytes, 6 * bytes);
+        assert_eq!(report.peak_bytes, 2 * bytes);
+        assert_eq!(report.buffers, 2);
+        assert_eq!(report.retained_bytes, bytes);
+
+        // Twice: the second pass computes into the pooled buffers
+        for _ in 0..2 {
+            let result = graph.run(&[(x, &input)], &[out]).unwrap();
+            assert_eq!(result[0].as_slice().unwrap(), expected[0].as_slice().unwrap());
+            for &id in &ops[..5] {
+                assert!(graph.values()[id.0].is_none(), "{:?} should be released", id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_plan_handles_batch_change() {
+        let (mut graph, x, ops) = chain();
+        let (mut reference, _, _) = chain();
+        let out = *ops.last().unwrap();
+        graph.plan_memory(&[out], PlanMode::Inference).unwrap();
+
+        for batch in [4, 2, 2, 4] {
+            let input = Tensor::from_shape_vec(&[batch, 8], (0..batch * 8).map(|i| i as f32 * 0.05).collect()).unwrap();
+            let result = graph.run(&[(x, &input)], &[out]).unwrap();
+            let expected = reference.run(&[(x, &input)], &[out]).unwrap();
+            assert_eq!(result[0].shape(), &[batch, 8]);
+            assert_eq!(result[0].as_slice().unwrap(), expected[0].as_slice().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_training_plan_matches_unplanned_gradients() {
+        let (mut graph, x, target, nodes, loss) = mlp();
+        let input = Tensor::from_shape_vec(&[2, 3], vec![1.0, -0.5, 2.0, 0.3, 0.8, -1.2]).unwrap();
+        let labels = Tensor::from_shape_vec(&[2, 1], vec![1.0, -1.0]).unwrap();
+        graph.feed(x, &input).unwrap();
+        graph.feed(target, &labels).unwrap();
+
+        graph.execute(loss).unwrap();
+        graph.backward_scalar(loss).unwrap();
+        let expected = param_grads(&graph);
+
+        let report = graph.plan_memory(&[loss], PlanMode::Training).unwrap();
+        assert!(report.peak_bytes < report.unplanned_bytes);
+        graph.clear_gradients();
+        graph.execute(loss).unwrap();
+
+        // MatMul outputs only feed Adds, whose backward needs just shapes
+        let [mm1, pre1, hidden, mm2, out] = nodes[..] else { unreachable!() };
+        assert!(graph.values()[mm1.0].is_none());
+        assert!(graph.values()[mm2.0].is_none());
+        for id in [pre1, hidden, out] {
+            assert!(graph.values()[id.0].is_some(), "{:?} is read by backward", id);
+        }
+
+        graph.backward_scalar(loss).unwrap();
+        assert_eq!(param_grads(&graph), expected);
+    }
+
+    #[test]
+    fn test_inference_plan_rejects_backward() {
+        let (mut graph, x, target, _, loss) = mlp();
+        graph.feed(x, &Tensor::new_ones(&[2, 3])).unwrap();
+        graph.feed(target, &Tensor::new_zeros(&[2, 1])).unwrap();
+        graph.plan_memory(&[loss], PlanMode::Inference).unwrap();
+        graph.execute(loss).unwrap();
+        assert!(graph.backward_scalar(loss).is_err());
+    }
+}
@@ -3,15 +3,23 @@ pub mod engine;
 pub mod ops;
 pub mod dsl;
 pub mod verifier;
+pub mod passes;
+pub mod memory;
+pub mod plan;
+pub mod autodiff;
 
 pub use architecture::Architecture;
 pub use engine::ExecutionEngine;
 pub use ops::{OpType, Operation};
+pub use passes::{PassManager, NodeMap};
+pub use memory::{MemoryPlan, MemoryReport, PlanMode};
+pub use plan::ExecutionPlan;
 
 use crate::backend::Backend;
 use crate::{GPError, GPResult, Tensor, NodeId};
 use crate::params::{ParamStore, ParamId};
 use serde::{Serialize, Deserialize};
+use std::sync::Arc;
 
 
 /// A node in the computation graph.
@@ -105,6 +113,22 @@ impl Graph {
         self.engine.as_ref().map_or(false, |e| e.is_training())
     }
 
+    /// Enables or disables concurrent execution of independent branches
+    /// (see [`ExecutionEngine::set_parallel`]).
+    pub fn set_parallel(&mut self, parallel: bool) {
+        if let Some(e) = &mut self.engine {
+            e.set_parallel(parallel);
+        }
+    }
+
+    /// Sets the per-level work below which independent branches still run
+    /// serially (see [`ExecutionEngine::set_parallel_threshold`]).
+    pub fn set_parallel_threshold(&mut self, min_elements: usize) {
+        if let Some(e) = &mut self.engine {
+            e.set_parallel_threshold(min_elements);
+        }
+    }
+
     // ── Component Access ───────────────────────────────────────────────────
 
     /// Returns a reference to the graph topology.
@@ -143,6 +167,23 @@ impl Graph {
         self.arch.input(tensor)
     }
 
+    /// Adds an input whose first dimension is the symbolic batch size
+    /// (see [`Architecture::batched_input`]).
+    pub fn batched_input(&mut self, tensor: Tensor) -> NodeId {
+        self.arch.batched_input(tensor)
+    }
+
+    /// Adds a zero-filled input of the declared `shape`, to be set via
+    /// [`feed`](Self::feed) or [`run`](Self::run).
+    pub fn placeholder(&mut self, shape: &[usize]) -> NodeId {
+        self.arch.placeholder(shape)
+    }
+
+    /// Adds a placeholder whose first dimension is the symbolic batch size.
+    pub fn batched_placeholder(&mut self, shape: &[usize]) -> NodeId {
+        self.arch.batched_placeholder(shape)
+    }
+
     /// Registers a parameter tensor in the [`ParamStore`] and adds a
     /// `Node::Param` to the architecture.
     pub fn param(&mut self, tensor: Tensor) -> NodeId {
@@ -156,31 +197,117 @@ impl Graph {
         self.arch.param(param_id)
     }
 
+    /// Adds a `Node::Param` for an existing [`ParamId`] (weight sharing).
+    ///
+    /// Every node referencing the same id reads the same tensor, and their
+    /// gradients are summed into it during backward. The id must come from
+    /// this graph's [`ParamStore`].
+    pub fn param_ref(&mut self, id: ParamId) -> NodeId {
+        self.arch.param(id)
+    }
+
+    /// Registers a non-trainable buffer (e.g. BatchNorm running statistics)
+    /// in the [`ParamStore`] and adds a `Node::Param` for it.
+    pub fn buffer(&mut self, tensor: Tensor) -> NodeId {
+        let param_id = self.param_store.register_buffer(tensor, "");
+        self.arch.param(param_id)
+    }
+
+    /// Registers a named buffer and adds a node for it.
+    pub fn named_buffer(&mut self, tensor: Tensor, name: &str) -> NodeId {
+        let param_id = self.param_store.register_buffer(tensor, name);
+        self.arch.param(param_id)
+    }
+
     pub fn op(&mut self, op: OpType, inputs: Vec<NodeId>) -> NodeId {
         self.arch.op(op, inputs)
     }
 
+    // ── Input Feeding (delegates to Architecture) ─────────────────────────
+
+    /// Sets the value of an input node, checking its device and shape
+    /// (see [`Architecture::feed`]).
+    pub fn feed(&mut self, node: NodeId, tensor: &Tensor) -> GPResult<()> {
+        self.arch.feed(node, tensor)
+    }
+
+    /// Returns the current value of an input node.
+    pub fn input_value(&self, node: NodeId) -> Option<&Tensor> {
+        self.arch.input_value(node)
+    }
+
     // ── Execution (delegates to ExecutionEngine) ───────────────────────────
     //
     // Note: We access `self.engine` via direct field destructuring to
     // satisfy the borrow checker (split borrows across struct fields).
 
     /// Forward pass: computes and caches values using iterative execution.
+    ///
+    /// In training mode, buffer updates (BatchNorm running statistics) are
+    /// written back to the [`ParamStore`] afterwards.
     pub fn execute(&mut self, target: NodeId) -> GPResult<Tensor> {
         let engine = self.engine.as_mut().ok_or(GPError::BackendNotInitialized)?;
-        engine.forward(&self.arch, &self.param_store, target)
+        let out = engine.forward(&self.arch, &self.param_store, target)?;
+        engine.apply_buffer_updates(&mut self.param_store)?;
+        Ok(out)
+    }
+
+    /// Returns the compiled [`ExecutionPlan`] for `targets`, cached by the
+    /// engine until the topology changes.
+    pub fn plan(&mut self, targets: &[NodeId]) -> GPResult<Arc<ExecutionPlan>> {
+        let engine = self.engine.as_mut().ok_or(GPError::BackendNotInitialized)?;
+        engine.plan(&self.arch, targets)
+    }
+
+    /// Forward pass over a compiled plan (see [`plan`](Self::plan)).
+    pub fn execute_plan(&mut self, plan: &ExecutionPlan, target: NodeId) -> GPResult<Tensor> {
+        let engine = self.engine.as_mut().ok_or(GPError::BackendNotInitialized)?;
+        let out = engine.forward_plan(&self.arch, &self.param_store, plan, target)?;
+        engine.apply_buffer_updates(&mut self.param_store)?;
+        Ok(out)
     }
 
     /// Forward pass using a pre-computed topological order.
     pub fn execute_with_order(&mut self, order: &[NodeId], target: NodeId) -> GPResult<Tensor> {
         let engine = self.engine.as_mut().ok_or(GPError::BackendNotInitialized)?;
-        engine.forward_with_order(&self.arch, &self.param_store, order, target)
+        let out = engine.forward_with_order(&self.arch, &self.param_store, order, target)?;
+        engine.apply_buffer_updates(&mut self.param_store)?;
+        Ok(out)
+    }
+
+    /// Feeds the inputs in `feeds`, runs one forward pass covering every
+    /// node in `fetches`, and returns their values in the same order.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let x = graph.placeholder(&[1, 2]);
+    /// // ... build the graph ...
+    /// let values = graph.run(&[(x, &input)], &[output, hidden])?;
+    /// let (y, h) = (&values[0], &values[1]);
+    /// ```
+    pub fn run(&mut self, feeds: &[(NodeId, &Tensor)], fetches: &[NodeId]) -> GPResult<Vec<Tensor>> {
+        for &(node, tensor) in feeds {
+            self.feed(node, tensor)?;
+        }
+        let Some(&first) = fetches.first() else {
+            return Ok(Vec::new());
+        };
+
+        let plan = self.plan(fetches)?;
+        self.execute_plan(&plan, first)?;
+        fetches.iter()
+            .map(|id| self.values().get(id.0).cloned().flatten().ok_or_else(|| {
+                GPError::InferenceError(format!("Fetched node {:?} not computed", id))
+            }))
+            .collect()
     }
 
     /// Executes a single node (used by WASM bridge for per-node execution).
     pub fn execute_single_node(&mut self, node_id: NodeId) -> GPResult<()> {
         let engine = self.engine.as_mut().ok_or(GPError::BackendNotInitialized)?;
-        engine.execute_single_node(&self.arch, &self.param_store, node_id)
+        engine.execute_single_node(&self.arch, &self.param_store, node_id)?;
+        engine.apply_buffer_updates(&mut self.param_store)
     }
 
     /// Backward pass: computes gradients via reverse-mode autodiff.
@@ -189,6 +316,40 @@ impl Graph {
         engine.backward(&self.arch, &mut self.param_store, target, grad_output)
     }
 
+    /// Backward pass from a scalar loss node with an implicit unit gradient.
+    pub fn backward_scalar(&mut self, target: NodeId) -> GPResult<()> {
+        let engine = self.engine.as_mut().ok_or(GPError::BackendNotInitialized)?;
+        engine.backward_scalar(&self.arch, &mut self.param_store, target)
+    }
+
+    // ── Symbolic Differentiation ───────────────────────────────────────────
+
+    /// Appends nodes computing the gradient of `output` w.r.t. each node in
+    /// `wrt`, with ones flowing into `output`, and returns their ids.
+    ///
+    /// Unlike [`backward`](Self::backward), the gradients are graph nodes:
+    /// evaluate them like any other node, or differentiate them again for
+    /// second derivatives (see [`autodiff`]).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Hessian-vector product of a scalar loss
+    /// let grad_w = graph.gradients(loss, &[w])?[0];
+    /// let dot = graph.op(OpType::Mul, vec![grad_w, v]);
+    /// let hvp = graph.gradients(dot, &[w])?[0];
+    /// let result = graph.run(&[], &[hvp])?;
+    /// ```
+    pub fn gradients(&mut self, output: NodeId, wrt: &[NodeId]) -> GPResult<Vec<NodeId>> {
+        autodiff::gradients(&mut self.arch, output, wrt, None)
+    }
+
+    /// Like [`gradients`](Self::gradients), with the node `grad_output`
+    /// flowing into `output` (a vector-Jacobian product).
+    pub fn gradients_with(&mut self, output: NodeId, wrt: &[NodeId], grad_output: NodeId) -> GPResult<Vec<NodeId>> {
+        autodiff::gradients(&mut self.arch, output, wrt, Some(grad_output))
+    }
+
     // ── Cache Access (delegates to ExecutionEngine) ────────────────────────
 
     /// Returns cached activation values from the last forward pass.
@@ -243,6 +404,8 @@ impl Graph {
     }
 
     /// Returns a mutable reference to all nodes.
+    ///
+    /// To set input values, prefer [`feed`](Self::feed).
     pub fn nodes_mut(&mut self) -> &mut [Node] {
         self.arch.nodes_mut()
     }
@@ -252,6 +415,87 @@ impl Graph {
         self.arch.topological_sort(target)
     }
 
+    /// Computes an execution order covering every node `targets` depend on.
+    pub fn execution_order(&self, targets: &[NodeId]) -> GPResult<Vec<NodeId>> {
+        self.arch.execution_order(targets)
+    }
+
+    // ── Optimization ───────────────────────────────────────────────────────
+
+    /// Optimizes the graph for computing `outputs`, with the inputs in
+    /// `feeds` fed between runs. Placeholders and batched inputs are always
+    /// treated as fed; every other input is treated as a constant.
+    ///
+    /// Runs the default [`PassManager`] pipeline (constant folding, CSE,
+    /// fusion, dead-node elimination). Node ids change: translate ids taken
+    /// before the call through the returned [`NodeMap`]. Cached values and
+    /// gradients are cleared, and any memory plan or activation checkpoints
+    /// are removed.
+    pub fn optimize(&mut self, outputs: &[NodeId], feeds: &[NodeId]) -> GPResult<NodeMap> {
+        self.optimize_with(&PassManager::default(), outputs, feeds)
+    }
+
+    /// Like [`optimize`](Self::optimize), with a custom pass pipeline.
+    pub fn optimize_with(&mut self, passes: &PassManager, outputs: &[NodeId], feeds: &[NodeId]) -> GPResult<NodeMap> {
+        let engine = self.engine.as_mut().ok_or(GPError::BackendNotInitialized)?;
+        let map = passes.run(&mut self.arch, &self.param_store, outputs, feeds, engine.backend())?;
+        engine.set_memory_plan(None);
+        engine.set_activation_checkpoints(&[]);
+        engine.clear_node_gradients();
+        Ok(map)
+    }
+
+    // ── Memory Planning ────────────────────────────────────────────────────
+
+    /// Plans buffer reuse for forward passes computing `outputs` and
+    /// installs the plan in the engine, returning its memory report.
+    ///
+    /// The plan applies to [`run`](Self::run) with these fetches, and to
+    /// [`execute`](Self::execute) when `outputs` is a single node. Afterwards
+    /// [`values`](Self::values) holds only `outputs` and, in
+    /// [`PlanMode::Training`], the values `backward` reads; an inference plan
+    /// does not support `backward`. Plan again after changing the topology.
+    pub fn plan_memory(&mut self, outputs: &[NodeId], mode: PlanMode) -> GPResult<MemoryReport> {
+        let order = self.plan(outputs)?;
+        let shapes = verifier::Verifier::verify(self)?;
+        let plan = MemoryPlan::new(&self.arch, order.order(), outputs, &shapes, mode)?;
+        let report = plan.report().clone();
+        let engine = self.engine.as_mut().ok_or(GPError::BackendNotInitialized)?;
+        engine.set_memory_plan(Some(plan));
+        Ok(report)
+    }
+
+    /// Removes the memory plan, so every evaluated value is cached again.
+    pub fn clear_memory_plan(&mut self) {
+        if let Some(e) = &mut self.engine {
+            e.set_memory_plan(None);
+        }
+    }
+
+    /// Enables gradient checkpointing for training: only the `boundaries`
+    /// between segments (plus the executed target, inputs and parameters)
+    /// stay cached after a training-mode forward pass, and
+    /// [`backward`](Self::backward) recomputes the activations inside each
+    /// segment when it reaches it. Pass an empty slice to disable.
+    ///
+    /// Fetch only boundaries or the first output when using [`run`](Self::run)
+    /// in training mode, since other intermediates are released.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Keep one activation per block of a deep network
+    /// graph.set_activation_checkpoints(&[block1_out, block2_out, block3_out]);
+    /// graph.set_training(true);
+    /// graph.execute(loss)?;
+    /// graph.backward_scalar(loss)?;
+    /// ```
+    pub fn set_activation_checkpoints(&mut self, boundaries: &[NodeId]) {
+        if let Some(e) = &mut self.engine {
+            e.set_activation_checkpoints(boundaries);
+        }
+    }
+
     /// Updates parameters using a simple SGD step: param -= lr * grad.
     pub fn update_parameters(&mut self, learning_rate: f32) -> GPResult<()> {
         let engine = self.engine.as_ref().ok_or(GPError::BackendNotInitialized)?;
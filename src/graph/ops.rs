@@ -6,7 +6,11 @@
 //! [`Operation`] is a trait for user-defined custom operations.
 
 use serde::{Serialize, Deserialize};
-use crate::backend::Backend;
+use crate::backend::{
+    Backend, sigmoid_scalar, leaky_relu_scalar, elu_scalar, gelu_scalar, silu_scalar, softplus_scalar,
+};
+use crate::tensor::{broadcast_shapes, reduced_shape, reduction_axes};
+use crate::loss::{Loss, MSE, BinaryCrossEntropy, BCEWithLogits, CategoricalCrossEntropy, CrossEntropyWithLogits};
 use crate::{GPError, GPResult, Tensor};
 
 /// Enumeration of all built-in computation graph operations.
@@ -20,20 +24,127 @@ pub enum OpType {
     MaxPool2D { kernel_size: usize, stride: usize },
     Add,
     Mul,
+    /// Element-wise `a - b` (broadcasting).
+    Sub,
+    /// Element-wise `a / b` (broadcasting).
+    Div,
+    Neg,
+    Exp,
+    /// Natural logarithm.
+    Log,
+    /// Element-wise power with a constant exponent: `x^exponent`.
+    Pow { exponent: f32 },
+    Sqrt,
+    Abs,
+    /// Element-wise clamp into `[min, max]`. Gradient is zero outside the range.
+    Clamp { min: f32, max: f32 },
+    /// Sum over `axes` (empty = all axes). A full reduction without
+    /// `keep_dims` produces shape `[1]`.
+    ReduceSum { axes: Vec<usize>, keep_dims: bool },
+    /// Mean over `axes` (empty = all axes).
+    ReduceMean { axes: Vec<usize>, keep_dims: bool },
+    /// Max over `axes` (empty = all axes). Gradient flows to the first maximum.
+    ReduceMax { axes: Vec<usize>, keep_dims: bool },
+    /// Index of the first maximum along `axis`, as f32. Not differentiable:
+    /// the gradient w.r.t. its input is zero.
+    ArgMax { axis: usize, keep_dims: bool },
+    // Loss ops take `[prediction, target]` and produce a `[1]` scalar, so
+    // training can start from `Graph::backward_scalar(loss)`. Targets are
+    // treated as constants (their gradient is zero).
+    /// Mean squared error over all elements.
+    MSELoss,
+    /// Binary cross-entropy on probabilities in `[0, 1]`.
+    BinaryCrossEntropyLoss,
+    /// Binary cross-entropy on raw logits (numerically stable).
+    BCEWithLogitsLoss,
+    /// Categorical cross-entropy on softmax probabilities, mean over rows.
+    CategoricalCrossEntropyLoss,
+    /// Softmax + cross-entropy on raw logits, mean over rows.
+    CrossEntropyWithLogitsLoss,
     ReLU,
     Tanh,
     Sigmoid,
+    /// `x` for positive inputs, `slope * x` otherwise.
+    LeakyReLU { slope: f32 },
+    /// `x` for positive inputs, `alpha * (exp(x) - 1)` otherwise.
+    ELU { alpha: f32 },
+    /// Gaussian Error Linear Unit (tanh approximation).
+    GELU,
+    /// SiLU / Swish: `x * sigmoid(x)`.
+    SiLU,
+    /// Smooth ReLU: `ln(1 + exp(x))`.
+    Softplus,
+    /// Element-wise clamp into `[min, max]`, conventionally `[-1, 1]`.
+    HardTanh { min: f32, max: f32 },
     /// Row-wise softmax: `softmax(x_i) = exp(x_i) / sum(exp(x_j))` per row.
     /// Numerically stable via max subtraction.
     Softmax,
-    Reshape { target_shape: Vec<usize> },
+    /// Reshape to `target_shape`; a single `-1` entry is inferred from the
+    /// element count (e.g. `[-1, features]` keeps any batch size).
+    Reshape { target_shape: Vec<isize> },
+    /// Concatenates any number of inputs along `axis`; all other dimensions
+    /// must match.
+    Concat { axis: usize },
+    /// Identity in the forward pass; blocks gradient flow in the backward pass
+    /// (used for truncated backpropagation through time).
+    StopGradient,
+    /// Swaps the two axes of a matrix.
+    Transpose,
+    // The ops below take `[x, like]` and only read the shape of `like`, which
+    // gets no gradient. Symbolic differentiation (see
+    // [`autodiff`](super::autodiff)) emits them where the eager backward
+    // reads runtime shapes.
+    /// Sums `x` down to the shape of `like`: over `axes` when given,
+    /// otherwise over the axes `like` was broadcast along.
+    SumToLike { axes: Vec<usize> },
+    /// Broadcasts `x` up to the shape of `like`. With `axes`, the reduced
+    /// axes are first reinserted as size 1 (undoing a `ReduceSum` over them).
+    BroadcastLike { axes: Vec<usize> },
+    /// A tensor of the input's shape filled with `value`. Not differentiable.
+    FillLike { value: f32 },
+    /// Reshapes `x` to the shape of `like` (undoing a `Reshape`).
+    ReshapeLike,
+    /// The part of `x`, a concatenation along `axis`, that input number
+    /// `input` contributed, over inputs `[x, concat inputs..]`. Only reads
+    /// the shapes of the concat inputs.
+    ConcatSlice { axis: usize, input: usize },
+    /// `1` for positive inputs, `0` otherwise (the derivative of ReLU).
+    /// Not differentiable: its gradient is zero.
+    Step,
+    /// The eager backward of `op` over inputs `[op inputs.., op output,
+    /// grad_output]`: every input gradient, flattened and concatenated in
+    /// input order. Emitted by symbolic differentiation for ops without a
+    /// symbolic rule; it has no gradient itself.
+    Gradient { op: Box<OpType> },
+    /// The gradient w.r.t. input number `input` cut out of a [`Gradient`]
+    /// node, over inputs `[gradient, op inputs..]`. Only reads the shapes of
+    /// the op inputs.
+    ///
+    /// [`Gradient`]: OpType::Gradient
+    SelectGradient { input: usize },
     /// Fused Add + ReLU for reduced memory bandwidth.
     AddReLU,
+    /// Fused `activation(x · w + b)` over inputs `[x, w, b]`, produced by the
+    /// fusion pass. The activation must be element-wise (see
+    /// [`OpType::is_elementwise_activation`]).
+    FusedLinear { activation: Option<Box<OpType>> },
     /// Dropout: identity during inference, random zeroing during training.
     Dropout { rate: f32 },
     /// Batch Normalization: normalizes per-feature, then applies gamma*x_norm+beta.
+    ///
+    /// Takes 3 inputs `[input, gamma, beta]` (always batch statistics), or 5
+    /// inputs `[input, gamma, beta, running_mean, running_var]` where the last
+    /// two are buffers: training mode uses batch statistics and blends them
+    /// into the buffers by `momentum`; inference mode uses the buffers.
+    BatchNorm {
+        epsilon: f32,
+        #[serde(default = "default_batchnorm_momentum")]
+        momentum: f32,
+    },
+    /// Layer Normalization: normalizes each sample over its last dimension,
+    /// then applies gamma*x_norm+beta. Independent of batch size.
     /// Takes 3 inputs: [input, gamma, beta]. Epsilon stored in variant.
-    BatchNorm { epsilon: f32 },
+    LayerNorm { epsilon: f32 },
     /// User-defined operation via the [`Operation`] trait.
     Custom(Box<dyn Operation>),
 }
@@ -46,18 +157,66 @@ impl OpType {
             OpType::MaxPool2D { .. } => "MaxPool2D",
             OpType::Add => "Add",
             OpType::Mul => "Mul",
+            OpType::Sub => "Sub",
+            OpType::Div => "Div",
+            OpType::Neg => "Neg",
+            OpType::Exp => "Exp",
+            OpType::Log => "Log",
+            OpType::Pow { .. } => "Pow",
+            OpType::Sqrt => "Sqrt",
+            OpType::Abs => "Abs",
+            OpType::Clamp { .. } => "Clamp",
+            OpType::ReduceSum { .. } => "ReduceSum",
+            OpType::ReduceMean { .. } => "ReduceMean",
+            OpType::ReduceMax { .. } => "ReduceMax",
+            OpType::ArgMax { .. } => "ArgMax",
+            OpType::MSELoss => "MSELoss",
+            OpType::BinaryCrossEntropyLoss => "BinaryCrossEntropyLoss",
+            OpType::BCEWithLogitsLoss => "BCEWithLogitsLoss",
+            OpType::CategoricalCrossEntropyLoss => "CategoricalCrossEntropyLoss",
+            OpType::CrossEntropyWithLogitsLoss => "CrossEntropyWithLogitsLoss",
             OpType::ReLU => "ReLU",
             OpType::Tanh => "Tanh",
             OpType::Sigmoid => "Sigmoid",
             OpType::Softmax => "Softmax",
+            OpType::LeakyReLU { .. } => "LeakyReLU",
+            OpType::ELU { .. } => "ELU",
+            OpType::GELU => "GELU",
+            OpType::SiLU => "SiLU",
+            OpType::Softplus => "Softplus",
+            OpType::HardTanh { .. } => "HardTanh",
             OpType::Reshape { .. } => "Reshape",
+            OpType::Concat { .. } => "Concat",
+            OpType::StopGradient => "StopGradient",
+            OpType::Transpose => "Transpose",
+            OpType::SumToLike { .. } => "SumToLike",
+            OpType::BroadcastLike { .. } => "BroadcastLike",
+            OpType::FillLike { .. } => "FillLike",
+            OpType::ReshapeLike => "ReshapeLike",
+            OpType::ConcatSlice { .. } => "ConcatSlice",
+            OpType::Step => "Step",
+            OpType::Gradient { .. } => "Gradient",
+            OpType::SelectGradient { .. } => "SelectGradient",
             OpType::AddReLU => "AddReLU",
+            OpType::FusedLinear { .. } => "FusedLinear",
             OpType::Dropout { .. } => "Dropout",
             OpType::BatchNorm { .. } => "BatchNorm",
+            OpType::LayerNorm { .. } => "LayerNorm",
             OpType::Custom(op) => op.name(),
         }
     }
 
+    /// Returns true for activations applied independently to every element,
+    /// which can be fused into a preceding op.
+    pub fn is_elementwise_activation(&self) -> bool {
+        matches!(
+            self,
+            OpType::ReLU | OpType::Tanh | OpType::Sigmoid | OpType::LeakyReLU { .. }
+            | OpType::ELU { .. } | OpType::GELU | OpType::SiLU | OpType::Softplus
+            | OpType::HardTanh { .. }
+        )
+    }
+
     // ── Forward Pass ───────────────────────────────────────────────────────
 
     /// Executes the forward pass.
@@ -72,16 +231,99 @@ impl OpType {
             OpType::MaxPool2D { kernel_size, stride } => backend.max_pool2d(inputs[0], *kernel_size, *stride),
             OpType::Add => backend.add(inputs[0], inputs[1]),
             OpType::Mul => backend.mul(inputs[0], inputs[1]),
+            OpType::Sub => backend.sub(inputs[0], inputs[1]),
+            OpType::Div => backend.div(inputs[0], inputs[1]),
+            OpType::Neg => backend.neg(inputs[0]),
+            OpType::Exp => backend.exp(inputs[0]),
+            OpType::Log => backend.log(inputs[0]),
+            OpType::Pow { exponent } => backend.pow(inputs[0], *exponent),
+            OpType::Sqrt => backend.sqrt(inputs[0]),
+            OpType::Abs => backend.abs(inputs[0]),
+            OpType::Clamp { min, max } => backend.clamp(inputs[0], *min, *max),
+            OpType::ReduceSum { axes, keep_dims } => {
+                let axes = reduction_axes(inputs[0].shape(), axes)?;
+                let out = backend.reduce_sum(inputs[0], &axes, *keep_dims)?;
+                out.into_shape(&reduced_shape(inputs[0].shape(), &axes, *keep_dims))
+            }
+            OpType::ReduceMean { axes, keep_dims } => {
+                let axes = reduction_axes(inputs[0].shape(), axes)?;
+                let count = reduction_count(inputs[0].shape(), &axes);
+                let out = &backend.reduce_sum(inputs[0], &axes, *keep_dims)? * (1.0 / count);
+                out.into_shape(&reduced_shape(inputs[0].shape(), &axes, *keep_dims))
+            }
+            OpType::ReduceMax { axes, keep_dims } => backend.reduce_max(inputs[0], axes, *keep_dims),
+            OpType::ArgMax { axis, keep_dims } => backend.argmax(inputs[0], *axis, *keep_dims),
+            OpType::MSELoss => loss_forward(self, &MSE, inputs),
+            OpType::BinaryCrossEntropyLoss => loss_forward(self, &BinaryCrossEntropy, inputs),
+            OpType::BCEWithLogitsLoss => loss_forward(self, &BCEWithLogits, inputs),
+            OpType::CategoricalCrossEntropyLoss => loss_forward(self, &CategoricalCrossEntropy, inputs),
+            OpType::CrossEntropyWithLogitsLoss => loss_forward(self, &CrossEntropyWithLogits, inputs),
             OpType::ReLU => backend.relu(inputs[0]),
             OpType::Tanh => backend.tanh(inputs[0]),
             OpType::Sigmoid => backend.sigmoid(inputs[0]),
             OpType::Softmax => softmax_forward(inputs[0]),
+            OpType::LeakyReLU { slope } => backend.leaky_relu(inputs[0], *slope),
+            OpType::ELU { alpha } => backend.elu(inputs[0], *alpha),
+            OpType::GELU => backend.gelu(inputs[0]),
+            OpType::SiLU => backend.silu(inputs[0]),
+            OpType::Softplus => backend.softplus(inputs[0]),
+            OpType::HardTanh { min, max } => backend.clamp(inputs[0], *min, *max),
             OpType::Reshape { target_shape } => {
-                let t = inputs[0].clone();
-                Ok(t.into_shape(target_shape.as_slice())?.into_dyn())
+                let shape = reshape_target(inputs[0].shape(), target_shape)?;
+                Ok(inputs[0].clone().into_shape(&shape)?.into_dyn())
+            }
+            OpType::Concat { axis } => concat_forward(inputs, *axis),
+            OpType::StopGradient => Ok(inputs[0].clone()),
+            OpType::Transpose => transpose(inputs[0]),
+            OpType::SumToLike { axes } => sum_to_shape(inputs[0], inputs[1].shape(), axes, backend),
+            OpType::BroadcastLike { axes } => broadcast_to_shape(inputs[0], inputs[1].shape(), axes, backend),
+            OpType::FillLike { value } => Ok(Tensor::from_elem(inputs[0].shape(), *value)),
+            OpType::ReshapeLike => Ok(inputs[0].clone().into_shape(inputs[1].shape())?.into_dyn()),
+            OpType::ConcatSlice { axis, input } => {
+                let shapes = tensor_shapes(&inputs[1..]);
+                concat_backward(&shapes, inputs[0], *axis)?.into_iter().nth(*input).ok_or_else(|| {
+                    GPError::InferenceError(format!("ConcatSlice has no input {}", input))
+                })
+            }
+            OpType::Step => {
+                let mut out = Tensor::new_zeros(inputs[0].shape());
+                elementwise_inplace(inputs[0], &mut out, step_scalar)?;
+                Ok(out)
+            }
+            OpType::Gradient { op } => {
+                let n = inputs.len() - 2;
+                let grads = op.backward_in_mode(&inputs[..n], Some(inputs[n]), inputs[n + 1], backend, training)?;
+                let mut packed = Vec::with_capacity(grads.iter().map(Tensor::len).sum());
+                for grad in &grads {
+                    packed.extend_from_slice(grad.as_slice()?);
+                }
+                Tensor::from_shape_vec(&[packed.len()], packed)
+            }
+            OpType::SelectGradient { input } => {
+                let offset: usize = inputs[1..=*input].iter().map(|t| t.len()).sum();
+                let shape = inputs[input + 1].shape();
+                let len: usize = shape.iter().product();
+                let packed = inputs[0].as_slice()?;
+                let part = packed.get(offset..offset + len).ok_or_else(|| GPError::InferenceError(format!(
+                    "Gradient of {} elements has no input {} at offset {}", packed.len(), input, offset
+                )))?;
+                Tensor::from_shape_vec(shape, part.to_vec())
             }
             OpType::AddReLU => backend.add_relu(inputs[0], inputs[1]),
-            OpType::BatchNorm { epsilon } => batchnorm_forward(inputs[0], inputs[1], inputs[2], *epsilon),
+            OpType::FusedLinear { activation } => {
+                let mut out = backend.matmul_t(inputs[0], inputs[1], false, false)?;
+                bias_activation_inplace(&mut out, inputs[2], activation.as_deref(), backend)?;
+                Ok(out)
+            }
+            OpType::BatchNorm { epsilon, .. } => {
+                check_batchnorm_shapes(&tensor_shapes(inputs))?;
+                if inputs.len() == 5 && !training {
+                    batchnorm_inference(inputs[0], inputs[1], inputs[2], inputs[3], inputs[4], *epsilon)
+                } else {
+                    batchnorm_forward(inputs[0], inputs[1], inputs[2], *epsilon)
+                }
+            }
+            OpType::LayerNorm { epsilon } => layernorm_forward(inputs[0], inputs[1], inputs[2], *epsilon),
             OpType::Dropout { rate } => {
                 if !training || *rate <= 0.0 || *rate >= 1.0 {
                     return Ok(inputs[0].clone()); // Inference or invalid rate: identity
@@ -129,8 +371,28 @@ impl OpType {
             OpType::Mul => backend.mul_into(inputs[0], inputs[1], out),
             OpType::ReLU => elementwise_inplace(inputs[0], out, |x| if x < 0.0 { 0.0 } else { x }),
             OpType::Tanh => elementwise_inplace(inputs[0], out, |x| x.tanh()),
-            OpType::Sigmoid => elementwise_inplace(inputs[0], out, |x| 1.0 / (1.0 + (-x).exp())),
-            OpType::AddReLU => { backend.relu_inplace(out)?; Ok(()) }
+            OpType::Sigmoid => elementwise_inplace(inputs[0], out, sigmoid_scalar),
+            OpType::LeakyReLU { slope } => elementwise_inplace(inputs[0], out, |x| leaky_relu_scalar(x, *slope)),
+            OpType::ELU { alpha } => elementwise_inplace(inputs[0], out, |x| elu_scalar(x, *alpha)),
+            OpType::GELU => elementwise_inplace(inputs[0], out, gelu_scalar),
+            OpType::SiLU => elementwise_inplace(inputs[0], out, silu_scalar),
+            OpType::Softplus => elementwise_inplace(inputs[0], out, softplus_scalar),
+            OpType::HardTanh { min, max } => elementwise_inplace(inputs[0], out, |x| x.max(*min).min(*max)),
+            OpType::Neg => elementwise_inplace(inputs[0], out, |x| -x),
+            OpType::Exp => elementwise_inplace(inputs[0], out, |x| x.exp()),
+            OpType::Log => elementwise_inplace(inputs[0], out, |x| x.ln()),
+            OpType::Sqrt => elementwise_inplace(inputs[0], out, |x| x.sqrt()),
+            OpType::Abs => elementwise_inplace(inputs[0], out, |x| x.abs()),
+            OpType::StopGradient => out.copy_from(inputs[0]),
+            OpType::Step => elementwise_inplace(inputs[0], out, step_scalar),
+            OpType::AddReLU => {
+                backend.add_into(inputs[0], inputs[1], out)?;
+                backend.relu_inplace(out)
+            }
+            OpType::FusedLinear { activation } => {
+                backend.matmul_into(inputs[0], inputs[1], false, false, out)?;
+                bias_activation_inplace(out, inputs[2], activation.as_deref(), backend)
+            }
             OpType::Custom(op) => op.forward_inplace(inputs, out, backend, training, rng_seed),
             _ => {
                 let res = self.forward(inputs, backend, training, rng_seed)?;
@@ -141,7 +403,23 @@ impl OpType {
 
     // ── Backward Pass (Gradient) ───────────────────────────────────────────
 
-    /// Computes gradients for each input given the gradient of the output.
+    /// Like [`backward`](Self::backward), for a forward pass run with the
+    /// given `training` flag. Differs only for a BatchNorm with running
+    /// statistics in inference mode, whose forward normalizes by those
+    /// constants instead of the batch.
+    pub fn backward_in_mode(&self, inputs: &[&Tensor], output: Option<&Tensor>, grad_output: &Tensor, backend: &dyn Backend, training: bool) -> GPResult<Vec<Tensor>> {
+        match self {
+            OpType::BatchNorm { epsilon, .. } if inputs.len() == 5 && !training => {
+                check_batchnorm_shapes(&tensor_shapes(inputs))?;
+                batchnorm_inference_backward(inputs, grad_output, *epsilon)
+            }
+            _ => self.backward(inputs, output, grad_output, backend),
+        }
+    }
+
+    /// Computes gradients for each input given the gradient of the output,
+    /// assuming a training-mode forward pass (see
+    /// [`backward_in_mode`](Self::backward_in_mode)).
     ///
     /// * `inputs` — cached forward-pass values at each input node.
     /// * `output` — cached forward-pass output of THIS node (needed by Dropout
@@ -161,10 +439,13 @@ impl OpType {
             OpType::MaxPool2D { kernel_size, stride } => {
                 Ok(vec![backend.max_pool2d_backward(inputs[0], grad_output, *kernel_size, *stride)?])
             }
-            OpType::Add => Ok(vec![
-                resolve_grad(inputs[0].shape(), grad_output, backend)?,
-                resolve_grad(inputs[1].shape(), grad_output, backend)?,
-            ]),
+            OpType::Add | OpType::Sub | OpType::Neg | OpType::ReduceSum { .. } | OpType::ReduceMean { .. }
+            | OpType::ArgMax { .. } | OpType::Reshape { .. } | OpType::Concat { .. }
+            | OpType::StopGradient | OpType::Dropout { .. } | OpType::Transpose
+            | OpType::SumToLike { .. } | OpType::BroadcastLike { .. } | OpType::FillLike { .. }
+            | OpType::ReshapeLike | OpType::ConcatSlice { .. } | OpType::Step => {
+                self.backward_from_shapes(&tensor_shapes(inputs), output, grad_output, backend)
+            }
             OpType::Mul => {
                 let (ga, gb) = backend.mul_backward(inputs[0], inputs[1], grad_output)?;
                 Ok(vec![
@@ -172,6 +453,56 @@ impl OpType {
                     resolve_grad(inputs[1].shape(), &gb, backend)?,
                 ])
             }
+            OpType::Div => {
+                let (ga, gb) = backend.div_backward(inputs[0], inputs[1], grad_output)?;
+                Ok(vec![
+                    resolve_grad(inputs[0].shape(), &ga, backend)?,
+                    resolve_grad(inputs[1].shape(), &gb, backend)?,
+                ])
+            }
+            OpType::Exp => {
+                // d/dx exp(x) = exp(x); reuse the cached output when available
+                let y = match output {
+                    Some(y) => y.clone(),
+                    None => backend.exp(inputs[0])?,
+                };
+                Ok(vec![backend.mul(grad_output, &y)?])
+            }
+            OpType::Log => Ok(vec![backend.div(grad_output, inputs[0])?]),
+            OpType::Pow { exponent } => {
+                // d/dx x^p = p * x^(p-1)
+                let dy = &backend.pow(inputs[0], exponent - 1.0)? * *exponent;
+                Ok(vec![backend.mul(grad_output, &dy)?])
+            }
+            OpType::Sqrt => {
+                // d/dx sqrt(x) = 1 / (2 * sqrt(x))
+                let y = backend.sqrt(inputs[0])?;
+                Ok(vec![backend.div(&(grad_output * 0.5), &y)?])
+            }
+            OpType::Abs => Ok(vec![backend.abs_backward(inputs[0], grad_output)?]),
+            OpType::Clamp { min, max } => {
+                Ok(vec![backend.clamp_backward(inputs[0], grad_output, *min, *max)?])
+            }
+            OpType::ReduceMax { axes, .. } => {
+                let axes = reduction_axes(inputs[0].shape(), axes)?;
+                let kept = grad_output.clone().into_shape(&reduced_shape(inputs[0].shape(), &axes, true))?;
+                Ok(vec![backend.reduce_max_backward(inputs[0], &axes, &kept)?])
+            }
+            OpType::MSELoss => loss_backward(MSE.gradient(inputs[0], inputs[1])?, inputs, grad_output),
+            OpType::BinaryCrossEntropyLoss => {
+                loss_backward(bce_probability_gradient(inputs[0], inputs[1])?, inputs, grad_output)
+            }
+            OpType::BCEWithLogitsLoss => {
+                loss_backward(BCEWithLogits.gradient(inputs[0], inputs[1])?, inputs, grad_output)
+            }
+            OpType::CategoricalCrossEntropyLoss => {
+                check_loss_shapes(self, inputs[0].shape(), inputs[1].shape())?;
+                loss_backward(CategoricalCrossEntropy.gradient(inputs[0], inputs[1])?, inputs, grad_output)
+            }
+            OpType::CrossEntropyWithLogitsLoss => {
+                check_loss_shapes(self, inputs[0].shape(), inputs[1].shape())?;
+                loss_backward(CrossEntropyWithLogits.gradient(inputs[0], inputs[1])?, inputs, grad_output)
+            }
             OpType::ReLU => Ok(vec![backend.relu_backward(inputs[0], grad_output)?]),
             OpType::Tanh => {
                 let y = backend.tanh(inputs[0])?;
@@ -182,9 +513,13 @@ impl OpType {
                 Ok(vec![backend.sigmoid_backward(&y, grad_output)?])
             }
             OpType::Softmax => softmax_backward(inputs[0], grad_output),
-            OpType::Reshape { .. } => {
-                let grad = grad_output.clone().into_shape(inputs[0].shape())?.into_dyn();
-                Ok(vec![grad])
+            OpType::LeakyReLU { slope } => Ok(vec![backend.leaky_relu_backward(inputs[0], grad_output, *slope)?]),
+            OpType::ELU { alpha } => Ok(vec![backend.elu_backward(inputs[0], grad_output, *alpha)?]),
+            OpType::GELU => Ok(vec![backend.gelu_backward(inputs[0], grad_output)?]),
+            OpType::SiLU => Ok(vec![backend.silu_backward(inputs[0], grad_output)?]),
+            OpType::Softplus => Ok(vec![backend.softplus_backward(inputs[0], grad_output)?]),
+            OpType::HardTanh { min, max } => {
+                Ok(vec![backend.clamp_backward(inputs[0], grad_output, *min, *max)?])
             }
             OpType::AddReLU => {
                 let relu_grad = backend.relu_backward(&backend.add(inputs[0], inputs[1])?, grad_output)?;
@@ -193,7 +528,112 @@ impl OpType {
                     resolve_grad(inputs[1].shape(), &relu_grad, backend)?,
                 ])
             }
-            OpType::BatchNorm { epsilon } => batchnorm_backward(inputs[0], inputs[1], grad_output, *epsilon),
+            OpType::FusedLinear { activation } => {
+                let pre = backend.add(&backend.matmul_t(inputs[0], inputs[1], false, false)?, inputs[2])?;
+                let grad = match activation {
+                    Some(act) => act.backward(&[&pre], output, grad_output, backend)?.remove(0),
+                    None => grad_output.clone(),
+                };
+                Ok(vec![
+                    backend.matmul_t(&grad, inputs[1], false, true)?,
+                    backend.matmul_t(inputs[0], &grad, true, false)?,
+                    resolve_grad(inputs[2].shape(), &grad, backend)?,
+                ])
+            }
+            OpType::BatchNorm { epsilon, .. } => {
+                // Training-mode forward (batch statistics); inference mode
+                // goes through `backward_in_mode`.
+                // Running statistics are buffers and get no gradient.
+                check_batchnorm_shapes(&tensor_shapes(inputs))?;
+                let mut grads = batchnorm_backward(inputs[0], inputs[1], grad_output, *epsilon)?;
+                for buffer in inputs.iter().skip(3) {
+                    grads.push(Tensor::new_zeros(buffer.shape()));
+                }
+                Ok(grads)
+            }
+            OpType::LayerNorm { epsilon } => layernorm_backward(inputs[0], inputs[1], inputs[2], grad_output, *epsilon),
+            OpType::Gradient { op } => Err(GPError::InferenceError(format!(
+                "The gradient of {} is not differentiable", op.name()
+            ))),
+            OpType::SelectGradient { .. } => Err(GPError::InferenceError(
+                "A selected gradient is not differentiable".to_string()
+            )),
+            OpType::Custom(op) => op.backward(inputs, output, grad_output, backend),
+        }
+    }
+
+    /// Returns false for ops whose backward reads only the shapes of their
+    /// inputs, so a memory plan may release the input values after the
+    /// forward pass (see [`backward_from_shapes`](Self::backward_from_shapes)).
+    pub fn backward_needs_input_values(&self) -> bool {
+        !matches!(
+            self,
+            OpType::Add | OpType::Sub | OpType::Neg | OpType::ReduceSum { .. } | OpType::ReduceMean { .. }
+            | OpType::ArgMax { .. } | OpType::Reshape { .. } | OpType::Concat { .. }
+            | OpType::StopGradient | OpType::Dropout { .. } | OpType::Transpose
+            | OpType::SumToLike { .. } | OpType::BroadcastLike { .. } | OpType::FillLike { .. }
+            | OpType::ReshapeLike | OpType::ConcatSlice { .. } | OpType::Step
+        )
+    }
+
+    /// Returns true for ops whose backward reads their own forward output.
+    pub fn backward_needs_output(&self) -> bool {
+        matches!(self, OpType::Dropout { .. } | OpType::Custom(_))
+    }
+
+    /// Backward pass from the input shapes alone, for ops where
+    /// [`backward_needs_input_values`](Self::backward_needs_input_values)
+    /// is false.
+    pub fn backward_from_shapes(&self, input_shapes: &[&[usize]], output: Option<&Tensor>, grad_output: &Tensor, backend: &dyn Backend) -> GPResult<Vec<Tensor>> {
+        match self {
+            OpType::Add => Ok(vec![
+                resolve_grad(input_shapes[0], grad_output, backend)?,
+                resolve_grad(input_shapes[1], grad_output, backend)?,
+            ]),
+            OpType::Sub => Ok(vec![
+                resolve_grad(input_shapes[0], grad_output, backend)?,
+                resolve_grad(input_shapes[1], &backend.neg(grad_output)?, backend)?,
+            ]),
+            OpType::Neg => Ok(vec![backend.neg(grad_output)?]),
+            OpType::ReduceSum { axes, .. } => {
+                let grad = expand_reduced_grad(input_shapes[0], axes, grad_output, backend)?;
+                Ok(vec![grad])
+            }
+            OpType::ReduceMean { axes, .. } => {
+                let count = reduction_count(input_shapes[0], &reduction_axes(input_shapes[0], axes)?);
+                let grad = expand_reduced_grad(input_shapes[0], axes, grad_output, backend)?;
+                Ok(vec![&grad * (1.0 / count)])
+            }
+            OpType::ArgMax { .. } | OpType::StopGradient | OpType::FillLike { .. } | OpType::Step => {
+                Ok(vec![Tensor::new_zeros(input_shapes[0])])
+            }
+            OpType::Transpose => Ok(vec![transpose(grad_output)?]),
+            OpType::SumToLike { axes } => Ok(vec![
+                broadcast_to_shape(grad_output, input_shapes[0], axes, backend)?,
+                Tensor::new_zeros(input_shapes[1]),
+            ]),
+            OpType::BroadcastLike { axes } => Ok(vec![
+                sum_to_shape(grad_output, input_shapes[0], axes, backend)?,
+                Tensor::new_zeros(input_shapes[1]),
+            ]),
+            OpType::Reshape { .. } => {
+                let grad = grad_output.clone().into_shape(input_shapes[0])?.into_dyn();
+                Ok(vec![grad])
+            }
+            OpType::ReshapeLike => Ok(vec![
+                grad_output.clone().into_shape(input_shapes[0])?.into_dyn(),
+                Tensor::new_zeros(input_shapes[1]),
+            ]),
+            OpType::ConcatSlice { axis, input } => {
+                // The slice's gradient in place, zeros around it
+                let parts: Vec<Tensor> = input_shapes[1..].iter().enumerate()
+                    .map(|(i, shape)| if i == *input { grad_output.clone() } else { Tensor::new_zeros(shape) })
+                    .collect();
+                let mut grads = vec![concat_forward(&parts.iter().collect::<Vec<_>>(), *axis)?];
+                grads.extend(input_shapes[1..].iter().map(|shape| Tensor::new_zeros(shape)));
+                Ok(grads)
+            }
+            OpType::Concat { axis } => concat_backward(input_shapes, grad_output, *axis),
             OpType::Dropout { rate } => {
                 if *rate <= 0.0 || *rate >= 1.0 {
                     // No dropout applied → gradient passes through unchanged
@@ -218,7 +658,26 @@ impl OpType {
                     }
                 }
             }
-            OpType::Custom(op) => op.backward(inputs, output, grad_output, backend),
+            _ => Err(GPError::InferenceError(format!(
+                "{} needs its input values for backward", self.name()
+            ))),
+        }
+    }
+
+    // ── Buffer Updates ─────────────────────────────────────────────────────
+
+    /// New values for buffer inputs after a training-mode forward pass.
+    ///
+    /// Returns `(input_index, value)` pairs; the engine writes each value to
+    /// the buffer feeding that input. Only BatchNorm with running statistics
+    /// produces updates (and not at batch size 1, where variance is undefined).
+    pub fn buffer_updates(&self, inputs: &[&Tensor]) -> GPResult<Vec<(usize, Tensor)>> {
+        match self {
+            OpType::BatchNorm { momentum, .. } if inputs.len() == 5 => {
+                check_batchnorm_shapes(&tensor_shapes(inputs))?;
+                batchnorm_running_update(inputs[0], inputs[3], inputs[4], *momentum)
+            }
+            _ => Ok(Vec::new()),
         }
     }
 
@@ -237,6 +696,10 @@ impl OpType {
                 }
                 Ok(vec![input_shapes[0][0], input_shapes[1][1]])
             }
+            OpType::FusedLinear { .. } => {
+                let product = OpType::MatMul.output_shape(&input_shapes[..2])?;
+                broadcast_shapes(&product, &input_shapes[2])
+            }
             OpType::Conv2D { stride, padding } => {
                 let (n, _ci, h, w) = (input_shapes[0][0], input_shapes[0][1], input_shapes[0][2], input_shapes[0][3]);
                 let (co, _ci_w, kh, kw) = (input_shapes[1][0], input_shapes[1][1], input_shapes[1][2], input_shapes[1][3]);
@@ -246,24 +709,108 @@ impl OpType {
                 let (n, c, h, w) = (input_shapes[0][0], input_shapes[0][1], input_shapes[0][2], input_shapes[0][3]);
                 Ok(vec![n, c, (h - kernel_size) / stride + 1, (w - kernel_size) / stride + 1])
             }
-            OpType::Add | OpType::Mul | OpType::AddReLU => {
-                if input_shapes[0] != input_shapes[1] {
-                    let exp_total: usize = input_shapes[0].iter().product();
-                    let found_total: usize = input_shapes[1].iter().product();
+            OpType::Add | OpType::Mul | OpType::AddReLU | OpType::Sub | OpType::Div => {
+                broadcast_shapes(&input_shapes[0], &input_shapes[1])
+            }
+            OpType::ReLU | OpType::Sigmoid | OpType::Tanh | OpType::Softmax
+            | OpType::LeakyReLU { .. } | OpType::ELU { .. } | OpType::GELU
+            | OpType::SiLU | OpType::Softplus | OpType::HardTanh { .. }
+            | OpType::Dropout { .. } | OpType::StopGradient
+            | OpType::Neg | OpType::Exp | OpType::Log | OpType::Pow { .. }
+            | OpType::Sqrt | OpType::Abs | OpType::Clamp { .. } | OpType::FillLike { .. }
+            | OpType::Step => {
+                Ok(input_shapes[0].clone())
+            }
+            OpType::BatchNorm { .. } => {
+                let shapes: Vec<&[usize]> = input_shapes.iter().map(Vec::as_slice).collect();
+                check_batchnorm_shapes(&shapes)?;
+                Ok(input_shapes[0].clone())
+            }
+            OpType::Transpose => match input_shapes[0].as_slice() {
+                &[rows, cols] => Ok(vec![cols, rows]),
+                shape => Err(GPError::InferenceError(format!(
+                    "Transpose expects a matrix, got shape {:?}", shape
+                ))),
+            },
+            OpType::SumToLike { .. } | OpType::BroadcastLike { .. } => Ok(input_shapes[1].clone()),
+            OpType::ReshapeLike => {
+                let (len, like): (usize, usize) = (input_shapes[0].iter().product(), input_shapes[1].iter().product());
+                if len != like {
                     return Err(GPError::IncompatibleShapes {
-                        expected: input_shapes[0].clone(),
-                        found: input_shapes[1].clone(),
-                        exp_len: exp_total,
-                        found_len: found_total,
+                        expected: input_shapes[1].clone(),
+                        found: input_shapes[0].clone(),
+                        exp_len: like,
+                        found_len: len,
                     });
                 }
-                Ok(input_shapes[0].clone())
+                Ok(input_shapes[1].clone())
             }
-            OpType::ReLU | OpType::Sigmoid | OpType::Tanh | OpType::Softmax
-            | OpType::Dropout { .. } | OpType::BatchNorm { .. } => {
+            OpType::ConcatSlice { axis, input } => {
+                let Some(part) = input_shapes.get(input + 1) else {
+                    return Err(GPError::InferenceError(format!(
+                        "ConcatSlice of input {} needs the concatenation and its inputs", input
+                    )));
+                };
+                let whole = concat_shape(&input_shapes[1..], *axis)?;
+                if whole != input_shapes[0] {
+                    return Err(GPError::IncompatibleShapes {
+                        expected: whole.clone(),
+                        found: input_shapes[0].clone(),
+                        exp_len: whole.iter().product(),
+                        found_len: input_shapes[0].iter().product(),
+                    });
+                }
+                Ok(part.clone())
+            }
+            OpType::Gradient { .. } => {
+                if input_shapes.len() < 3 {
+                    return Err(GPError::InferenceError(
+                        "Gradient needs the op inputs, output and gradient".to_string()
+                    ));
+                }
+                let len = input_shapes[..input_shapes.len() - 2].iter()
+                    .map(|shape| shape.iter().product::<usize>())
+                    .sum();
+                Ok(vec![len])
+            }
+            OpType::SelectGradient { input } => match input_shapes.get(input + 1) {
+                Some(shape) => Ok(shape.clone()),
+                None => Err(GPError::InferenceError(format!(
+                    "SelectGradient of input {} needs the gradient and op inputs", input
+                ))),
+            },
+            OpType::LayerNorm { .. } => {
+                let features = input_shapes[0].last().copied().unwrap_or(1);
+                for affine in &input_shapes[1..3] {
+                    let len: usize = affine.iter().product();
+                    if len != features {
+                        return Err(GPError::IncompatibleShapes {
+                            expected: vec![1, features],
+                            found: affine.clone(),
+                            exp_len: features,
+                            found_len: len,
+                        });
+                    }
+                }
                 Ok(input_shapes[0].clone())
             }
-            OpType::Reshape { target_shape } => Ok(target_shape.clone()),
+            OpType::Reshape { target_shape } => reshape_target(&input_shapes[0], target_shape),
+            OpType::Concat { axis } => concat_shape(input_shapes, *axis),
+            OpType::ReduceSum { axes, keep_dims }
+            | OpType::ReduceMean { axes, keep_dims }
+            | OpType::ReduceMax { axes, keep_dims } => {
+                let axes = reduction_axes(&input_shapes[0], axes)?;
+                Ok(reduced_shape(&input_shapes[0], &axes, *keep_dims))
+            }
+            OpType::ArgMax { axis, keep_dims } => {
+                let axes = reduction_axes(&input_shapes[0], &[*axis])?;
+                Ok(reduced_shape(&input_shapes[0], &axes, *keep_dims))
+            }
+            OpType::MSELoss | OpType::BinaryCrossEntropyLoss | OpType::BCEWithLogitsLoss
+            | OpType::CategoricalCrossEntropyLoss | OpType::CrossEntropyWithLogitsLoss => {
+                check_loss_shapes(self, &input_shapes[0], &input_shapes[1])?;
+                Ok(vec![1])
+            }
             OpType::Custom(op) => op.output_shape(input_shapes),
         }
     }
@@ -271,8 +818,54 @@ impl OpType {
 
 // ── Helper Functions ───────────────────────────────────────────────────────
 
+/// Scalar form of an element-wise activation (identity for other ops).
+fn activation_scalar(op: &OpType, x: f32) -> f32 {
+    match op {
+        OpType::ReLU => if x < 0.0 { 0.0 } else { x },
+        OpType::Tanh => x.tanh(),
+        OpType::Sigmoid => sigmoid_scalar(x),
+        OpType::LeakyReLU { slope } => leaky_relu_scalar(x, *slope),
+        OpType::ELU { alpha } => elu_scalar(x, *alpha),
+        OpType::GELU => gelu_scalar(x),
+        OpType::SiLU => silu_scalar(x),
+        OpType::Softplus => softplus_scalar(x),
+        OpType::HardTanh { min, max } => x.max(*min).min(*max),
+        _ => x,
+    }
+}
+
+/// Derivative of ReLU, matching `relu_backward` (zero at the origin).
+fn step_scalar(x: f32) -> f32 {
+    if x > 0.0 { 1.0 } else { 0.0 }
+}
+
+/// `out = activation(out + bias)` in a single pass for row-vector biases
+/// (`[n]` or `[1, n]` against `[.., n]`); other broadcasts add first.
+fn bias_activation_inplace(out: &mut Tensor, bias: &Tensor, activation: Option<&OpType>, backend: &dyn Backend) -> GPResult<()> {
+    let cols = out.shape().last().copied().unwrap_or(1);
+    let row_bias = bias.len() == cols
+        && bias.ndim() <= out.ndim()
+        && bias.shape().iter().rev().skip(1).all(|&d| d == 1);
+    let act = |x: f32| activation.map_or(x, |op| activation_scalar(op, x));
+
+    if row_bias {
+        let b = bias.as_slice()?;
+        for row in out.as_slice_mut()?.chunks_mut(cols) {
+            for (v, &bv) in row.iter_mut().zip(b) {
+                *v = act(*v + bv);
+            }
+        }
+    } else {
+        *out = backend.add(out, bias)?;
+        for v in out.as_slice_mut()? {
+            *v = act(*v);
+        }
+    }
+    Ok(())
+}
+
 /// Element-wise in-place operation: `out[i] = f(in[i])`.
-fn elementwise_inplace(input: &Tensor, out: &mut Tensor, f: fn(f32) -> f32) -> GPResult<()> {
+fn elementwise_inplace(input: &Tensor, out: &mut Tensor, f: impl Fn(f32) -> f32) -> GPResult<()> {
     let in_len = input.len();
     let out_len = out.len();
     if in_len != out_len {
@@ -348,6 +941,138 @@ fn batchnorm_forward(x: &Tensor, gamma: &Tensor, beta: &Tensor, epsilon: f32) ->
     Tensor::from_shape_vec(shape, out)
 }
 
+/// Shapes of `inputs`, in order.
+fn tensor_shapes<'a>(inputs: &[&'a Tensor]) -> Vec<&'a [usize]> {
+    inputs.iter().map(|t| t.shape()).collect()
+}
+
+/// Checks BatchNorm input shapes: `x` must be `[batch, features]`, and gamma,
+/// beta and the running statistics must hold one value per feature.
+fn check_batchnorm_shapes(shapes: &[&[usize]]) -> GPResult<()> {
+    let x = shapes[0];
+    if x.len() != 2 {
+        let len = x.iter().product();
+        return Err(GPError::IncompatibleShapes {
+            expected: vec![x.first().copied().unwrap_or(1), x.iter().skip(1).product()],
+            found: x.to_vec(),
+            exp_len: len,
+            found_len: len,
+        });
+    }
+    let features = x[1];
+    for shape in &shapes[1..] {
+        let len: usize = shape.iter().product();
+        if len != features {
+            return Err(GPError::IncompatibleShapes {
+                expected: vec![1, features],
+                found: shape.to_vec(),
+                exp_len: features,
+                found_len: len,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Default momentum for BatchNorm running statistics (PyTorch's default).
+pub(crate) fn default_batchnorm_momentum() -> f32 {
+    0.1
+}
+
+/// Per-feature mean and unbiased variance over the batch dimension.
+fn batch_moments(x: &Tensor) -> GPResult<(Vec<f32>, Vec<f32>)> {
+    let shape = x.shape();
+    let (batch, features) = (shape[0], shape[1]);
+    let x_slice = x.as_slice()?;
+    let mut mean = vec![0.0f32; features];
+    let mut var = vec![0.0f32; features];
+    for row in x_slice.chunks(features) {
+        for (m, &v) in mean.iter_mut().zip(row) {
+            *m += v;
+        }
+    }
+    for m in &mut mean {
+        *m /= batch as f32;
+    }
+    for row in x_slice.chunks(features) {
+        for ((s, &v), &m) in var.iter_mut().zip(row).zip(&mean) {
+            *s += (v - m) * (v - m);
+        }
+    }
+    for s in &mut var {
+        *s /= (batch - 1) as f32;
+    }
+    Ok((mean, var))
+}
+
+/// Running statistics update: `running = (1 - momentum) * running + momentum * batch_stat`.
+///
+/// Uses the unbiased batch variance, matching PyTorch. Returns no updates for
+/// batch size 1, where the forward pass is affine-only.
+fn batchnorm_running_update(x: &Tensor, running_mean: &Tensor, running_var: &Tensor, momentum: f32) -> GPResult<Vec<(usize, Tensor)>> {
+    if x.shape()[0] <= 1 {
+        return Ok(Vec::new());
+    }
+    let (mean, var) = batch_moments(x)?;
+    let blend = |running: &Tensor, batch: &[f32]| -> GPResult<Tensor> {
+        let data = running.as_slice()?.iter().zip(batch)
+            .map(|(&r, &b)| (1.0 - momentum) * r + momentum * b)
+            .collect();
+        Tensor::from_shape_vec(running.shape(), data)
+    };
+    Ok(vec![
+        (3, blend(running_mean, &mean)?),
+        (4, blend(running_var, &var)?),
+    ])
+}
+
+/// BatchNorm inference: normalizes with running statistics instead of the batch,
+/// so each sample's output is independent of the rest of the batch.
+fn batchnorm_inference(x: &Tensor, gamma: &Tensor, beta: &Tensor, running_mean: &Tensor, running_var: &Tensor, epsilon: f32) -> GPResult<Tensor> {
+    let features = x.shape()[1];
+    let g_slice = gamma.as_slice()?;
+    let b_slice = beta.as_slice()?;
+    let m_slice = running_mean.as_slice()?;
+    let v_slice = running_var.as_slice()?;
+    let out = x.as_slice()?.iter().enumerate()
+        .map(|(i, &v)| {
+            let f = i % features;
+            g_slice[f] * (v - m_slice[f]) / (v_slice[f] + epsilon).sqrt() + b_slice[f]
+        })
+        .collect();
+    Tensor::from_shape_vec(x.shape(), out)
+}
+
+/// BatchNorm inference backward over `[x, gamma, beta, running_mean, running_var]`.
+///
+/// The running statistics are constants here, so
+/// `grad_x = grad * gamma / sqrt(running_var + eps)`; the buffers get zeros.
+fn batchnorm_inference_backward(inputs: &[&Tensor], grad_output: &Tensor, epsilon: f32) -> GPResult<Vec<Tensor>> {
+    let (x, gamma, beta, running_mean, running_var) = (inputs[0], inputs[1], inputs[2], inputs[3], inputs[4]);
+    let features = x.shape()[1];
+    let g_slice = gamma.as_slice()?;
+    let m_slice = running_mean.as_slice()?;
+    let inv_std: Vec<f32> = running_var.as_slice()?.iter().map(|&v| 1.0 / (v + epsilon).sqrt()).collect();
+
+    let mut grad_x = Vec::with_capacity(x.len());
+    let mut grad_gamma = vec![0.0f32; features];
+    let mut grad_beta = vec![0.0f32; features];
+    for (i, (&v, &go)) in x.as_slice()?.iter().zip(grad_output.as_slice()?).enumerate() {
+        let f = i % features;
+        grad_x.push(go * g_slice[f] * inv_std[f]);
+        grad_gamma[f] += go * (v - m_slice[f]) * inv_std[f];
+        grad_beta[f] += go;
+    }
+
+    Ok(vec![
+        Tensor::from_shape_vec(x.shape(), grad_x)?,
+        Tensor::from_shape_vec(gamma.shape(), grad_gamma)?,
+        Tensor::from_shape_vec(beta.shape(), grad_beta)?,
+        Tensor::new_zeros(running_mean.shape()),
+        Tensor::new_zeros(running_var.shape()),
+    ])
+}
+
 /// BatchNorm backward: computes gradients for x, gamma, and beta.
 ///
 /// Returns [grad_x, grad_gamma, grad_beta].
@@ -430,6 +1155,102 @@ fn batchnorm_backward(x: &Tensor, gamma: &Tensor, grad_output: &Tensor, epsilon:
     ])
 }
 
+// ── LayerNorm ──────────────────────────────────────────────────────────────
+
+/// Splits a shape into `(rows, features)` where `features` is the last dim.
+fn layernorm_dims(x: &Tensor, gamma: &Tensor, beta: &Tensor) -> GPResult<(usize, usize)> {
+    let features = x.shape().last().copied().unwrap_or(1);
+    for affine in [gamma, beta] {
+        if affine.len() != features {
+            return Err(GPError::IncompatibleShapes {
+                expected: vec![1, features],
+                found: affine.shape().to_vec(),
+                exp_len: features,
+                found_len: affine.len(),
+            });
+        }
+    }
+    let rows = x.len().checked_div(features).unwrap_or(0);
+    Ok((rows, features))
+}
+
+/// LayerNorm forward: `y = gamma * (x - mean) / sqrt(var + eps) + beta`.
+///
+/// Input x: [..., features], gamma: [1, features], beta: [1, features].
+/// Statistics computed per sample over the last dimension, so the result
+/// does not depend on the batch size (unlike BatchNorm).
+fn layernorm_forward(x: &Tensor, gamma: &Tensor, beta: &Tensor, epsilon: f32) -> GPResult<Tensor> {
+    let (rows, features) = layernorm_dims(x, gamma, beta)?;
+    let x_slice = x.as_slice()?;
+    let g_slice = gamma.as_slice()?;
+    let b_slice = beta.as_slice()?;
+    let n = features as f32;
+    let mut out = vec![0.0f32; rows * features];
+
+    for r in 0..rows {
+        let rs = r * features;
+        let row = &x_slice[rs..rs + features];
+        let mean = row.iter().sum::<f32>() / n;
+        // Biased variance, matching PyTorch's LayerNorm
+        let var = row.iter().map(|&v| (v - mean) * (v - mean)).sum::<f32>() / n;
+        let inv_std = 1.0 / (var + epsilon).sqrt();
+        for f in 0..features {
+            out[rs + f] = g_slice[f] * (row[f] - mean) * inv_std + b_slice[f];
+        }
+    }
+
+    Tensor::from_shape_vec(x.shape(), out)
+}
+
+/// LayerNorm backward: computes gradients for x, gamma, and beta.
+///
+/// Returns [grad_x, grad_gamma, grad_beta], with the affine gradients
+/// summed over all rows and shaped like gamma/beta.
+fn layernorm_backward(x: &Tensor, gamma: &Tensor, beta: &Tensor, grad_output: &Tensor, epsilon: f32) -> GPResult<Vec<Tensor>> {
+    let (rows, features) = layernorm_dims(x, gamma, beta)?;
+    let x_slice = x.as_slice()?;
+    let g_slice = gamma.as_slice()?;
+    let go_slice = grad_output.as_slice()?;
+    let n = features as f32;
+
+    let mut grad_x = vec![0.0f32; rows * features];
+    let mut grad_gamma = vec![0.0f32; features];
+    let mut grad_beta = vec![0.0f32; features];
+    let mut x_norm = vec![0.0f32; features];
+    let mut dx_norm = vec![0.0f32; features];
+
+    for r in 0..rows {
+        let rs = r * features;
+        let row = &x_slice[rs..rs + features];
+        let mean = row.iter().sum::<f32>() / n;
+        let var = row.iter().map(|&v| (v - mean) * (v - mean)).sum::<f32>() / n;
+        let inv_std = 1.0 / (var + epsilon).sqrt();
+
+        let mut sum_dxn = 0.0f32;
+        let mut sum_dxn_xn = 0.0f32;
+        for f in 0..features {
+            let go = go_slice[rs + f];
+            x_norm[f] = (row[f] - mean) * inv_std;
+            dx_norm[f] = go * g_slice[f];
+            grad_gamma[f] += go * x_norm[f];
+            grad_beta[f] += go;
+            sum_dxn += dx_norm[f];
+            sum_dxn_xn += dx_norm[f] * x_norm[f];
+        }
+
+        // dx = (1/N) * inv_std * (N * dx_norm - sum(dx_norm) - x_norm * sum(dx_norm * x_norm))
+        for f in 0..features {
+            grad_x[rs + f] = inv_std / n * (n * dx_norm[f] - sum_dxn - x_norm[f] * sum_dxn_xn);
+        }
+    }
+
+    Ok(vec![
+        Tensor::from_shape_vec(x.shape(), grad_x)?,
+        Tensor::from_shape_vec(gamma.shape(), grad_gamma)?,
+        Tensor::from_shape_vec(beta.shape(), grad_beta)?,
+    ])
+}
+
 /// Numerically stable row-wise softmax forward.
 fn softmax_forward(x: &Tensor) -> GPResult<Tensor> {
     let shape = x.shape();
@@ -494,6 +1315,209 @@ fn softmax_backward(x: &Tensor, grad_output: &Tensor) -> GPResult<Vec<Tensor>> {
     Ok(vec![Tensor::from_shape_vec(shape, grad)?])
 }
 
+// ── Reshape ────────────────────────────────────────────────────────────────
+
+/// Resolves a reshape target with at most one inferred (`-1`) dimension.
+pub(crate) fn reshape_target(input_shape: &[usize], target: &[isize]) -> GPResult<Vec<usize>> {
+    let numel: usize = input_shape.iter().product();
+    let mismatch = |found: Vec<usize>| GPError::IncompatibleShapes {
+        expected: input_shape.to_vec(),
+        found_len: found.iter().product(),
+        found,
+        exp_len: numel,
+    };
+
+    let mut inferred = None;
+    let mut known = 1usize;
+    for (axis, &d) in target.iter().enumerate() {
+        match d {
+            -1 if inferred.is_none() => inferred = Some(axis),
+            d if d >= 0 => known *= d as usize,
+            _ => return Err(GPError::InferenceError(format!(
+                "Reshape target {:?} may only contain sizes and a single -1", target
+            ))),
+        }
+    }
+
+    let mut shape: Vec<usize> = target.iter().map(|&d| d.max(0) as usize).collect();
+    if let Some(axis) = inferred {
+        if known == 0 || !numel.is_multiple_of(known) {
+            return Err(mismatch(shape));
+        }
+        shape[axis] = numel / known;
+    }
+    if shape.iter().product::<usize>() != numel {
+        return Err(mismatch(shape));
+    }
+    Ok(shape)
+}
+
+/// Swaps the axes of a matrix.
+fn transpose(x: &Tensor) -> GPResult<Tensor> {
+    if x.ndim() != 2 {
+        return Err(GPError::InferenceError(format!(
+            "Transpose expects a matrix, got shape {:?}", x.shape()
+        )));
+    }
+    let view = x.try_view()?.reversed_axes();
+    Ok(view.as_standard_layout().into_owned().into())
+}
+
+// ── Concatenation ──────────────────────────────────────────────────────────
+
+/// Output shape of concatenating `shapes` along `axis`.
+pub(crate) fn concat_shape(shapes: &[Vec<usize>], axis: usize) -> GPResult<Vec<usize>> {
+    let first = shapes.first().ok_or_else(|| {
+        GPError::InferenceError("Concat needs at least one input".to_string())
+    })?;
+    if axis >= first.len() {
+        return Err(GPError::InferenceError(format!(
+            "Concat axis {} out of range for rank {}", axis, first.len()
+        )));
+    }
+    let mut out = first.clone();
+    for shape in &shapes[1..] {
+        let compatible = shape.len() == first.len()
+            && shape.iter().zip(first).enumerate().all(|(d, (a, b))| d == axis || a == b);
+        if !compatible {
+            return Err(GPError::IncompatibleShapes {
+                expected: first.clone(),
+                found: shape.clone(),
+                exp_len: first.iter().product(),
+                found_len: shape.iter().product(),
+            });
+        }
+        out[axis] += shape[axis];
+    }
+    Ok(out)
+}
+
+/// Splits a row-major shape around `axis` into `(outer, chunk)`: the number
+/// of leading blocks, and the contiguous length of one block.
+fn concat_blocks(shape: &[usize], axis: usize) -> (usize, usize) {
+    (shape[..axis].iter().product(), shape[axis..].iter().product())
+}
+
+fn concat_forward(inputs: &[&Tensor], axis: usize) -> GPResult<Tensor> {
+    let shapes: Vec<Vec<usize>> = inputs.iter().map(|t| t.shape().to_vec()).collect();
+    let out_shape = concat_shape(&shapes, axis)?;
+    let (outer, _) = concat_blocks(&out_shape, axis);
+
+    let slices = inputs.iter().map(|t| t.as_slice()).collect::<GPResult<Vec<_>>>()?;
+    let chunks: Vec<usize> = shapes.iter().map(|s| concat_blocks(s, axis).1).collect();
+    let mut out = Vec::with_capacity(out_shape.iter().product());
+    for o in 0..outer {
+        for (slice, &chunk) in slices.iter().zip(&chunks) {
+            out.extend_from_slice(&slice[o * chunk..(o + 1) * chunk]);
+        }
+    }
+    Tensor::from_shape_vec(&out_shape, out)
+}
+
+/// Concat backward: each input receives its slice of the output gradient.
+fn concat_backward(input_shapes: &[&[usize]], grad_output: &Tensor, axis: usize) -> GPResult<Vec<Tensor>> {
+    let (outer, _) = concat_blocks(grad_output.shape(), axis);
+    let go = grad_output.as_slice()?;
+    let chunks: Vec<usize> = input_shapes.iter().map(|s| concat_blocks(s, axis).1).collect();
+    let row: usize = chunks.iter().sum();
+
+    let mut grads: Vec<Vec<f32>> = input_shapes.iter().map(|s| Vec::with_capacity(s.iter().product())).collect();
+    for o in 0..outer {
+        let mut offset = o * row;
+        for (grad, &chunk) in grads.iter_mut().zip(&chunks) {
+            grad.extend_from_slice(&go[offset..offset + chunk]);
+            offset += chunk;
+        }
+    }
+    input_shapes.iter().zip(grads)
+        .map(|(s, g)| Tensor::from_shape_vec(s, g))
+        .collect()
+}
+
+// ── Reductions ─────────────────────────────────────────────────────────────
+
+/// Number of elements folded into each output of a reduction over `axes`.
+fn reduction_count(shape: &[usize], axes: &[usize]) -> f32 {
+    axes.iter().map(|&a| shape[a]).product::<usize>() as f32
+}
+
+/// Sums `x` down to `shape`: over `axes` when given, otherwise over the
+/// axes `shape` was broadcast along.
+fn sum_to_shape(x: &Tensor, shape: &[usize], axes: &[usize], backend: &dyn Backend) -> GPResult<Tensor> {
+    if axes.is_empty() {
+        return resolve_grad(shape, x, backend);
+    }
+    let axes = reduction_axes(x.shape(), axes)?;
+    backend.reduce_sum(x, &axes, true)?.into_shape(shape)
+}
+
+/// Broadcasts `x` up to `shape`, the inverse of [`sum_to_shape`].
+fn broadcast_to_shape(x: &Tensor, shape: &[usize], axes: &[usize], backend: &dyn Backend) -> GPResult<Tensor> {
+    if axes.is_empty() {
+        return backend.broadcast_to(x, shape);
+    }
+    expand_reduced_grad(shape, axes, x, backend)
+}
+
+/// Broadcasts the gradient of a sum-like reduction back to the input shape.
+fn expand_reduced_grad(input_shape: &[usize], axes: &[usize], grad_output: &Tensor, backend: &dyn Backend) -> GPResult<Tensor> {
+    let axes = reduction_axes(input_shape, axes)?;
+    let kept = grad_output.clone().into_shape(&reduced_shape(input_shape, &axes, true))?;
+    backend.broadcast_to(&kept, input_shape)
+}
+
+// ── Losses ─────────────────────────────────────────────────────────────────
+
+/// Checks that predictions and targets of loss `op` have the same shape, and
+/// that the categorical losses, which index the class axis, get
+/// `[batch, classes]` inputs.
+fn check_loss_shapes(op: &OpType, predicted: &[usize], target: &[usize]) -> GPResult<()> {
+    if predicted != target {
+        return Err(GPError::IncompatibleShapes {
+            expected: predicted.to_vec(),
+            found: target.to_vec(),
+            exp_len: predicted.iter().product(),
+            found_len: target.iter().product(),
+        });
+    }
+    let categorical = matches!(op, OpType::CategoricalCrossEntropyLoss | OpType::CrossEntropyWithLogitsLoss);
+    if categorical && predicted.len() != 2 {
+        return Err(GPError::InferenceError(format!(
+            "{} expects [batch, classes] inputs, got shape {:?}", op.name(), predicted
+        )));
+    }
+    Ok(())
+}
+
+fn loss_forward(op: &OpType, loss: &dyn Loss, inputs: &[&Tensor]) -> GPResult<Tensor> {
+    check_loss_shapes(op, inputs[0].shape(), inputs[1].shape())?;
+    Ok(Tensor::from_elem(&[1], loss.calculate(inputs[0], inputs[1])?))
+}
+
+/// Scales the loss gradient by the incoming scalar gradient. Targets get zeros.
+fn loss_backward(grad: Tensor, inputs: &[&Tensor], grad_output: &Tensor) -> GPResult<Vec<Tensor>> {
+    let scale = grad_output.get_flat(0)?;
+    Ok(vec![&grad * scale, Tensor::new_zeros(inputs[1].shape())])
+}
+
+/// True derivative of [`BinaryCrossEntropy`] w.r.t. the probabilities:
+/// `(p - t) / (p * (1 - p)) / N`.
+///
+/// `BinaryCrossEntropy::gradient` returns `(p - t) / N`, the gradient w.r.t.
+/// the pre-sigmoid logits, which would double-count the sigmoid derivative
+/// once autograd backpropagates through a `Sigmoid` node.
+fn bce_probability_gradient(predicted: &Tensor, target: &Tensor) -> GPResult<Tensor> {
+    let eps = 1e-7f32;
+    let n = predicted.len() as f32;
+    let grad: Vec<f32> = predicted.as_slice()?.iter().zip(target.as_slice()?)
+        .map(|(&p, &t)| {
+            let p = p.clamp(eps, 1.0 - eps);
+            (p - t) / (p * (1.0 - p)) / n
+        })
+        .collect();
+    Tensor::from_shape_vec(predicted.shape(), grad)
+}
+
 /// Resolves gradient shape mismatches via reduction (for broadcasting).
 pub(crate) fn resolve_grad(target_shape: &[usize], grad: &Tensor, backend: &dyn Backend) -> GPResult<Tensor> {
     if target_shape == grad.shape() {
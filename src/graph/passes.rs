@@ -0,0 +1,673 @@
+//! Graph optimization passes.
+//!
+//! A [`PassManager`] runs a sequence of [`Pass`]es, each rewriting the
+//! [`Architecture`] in place. The default pipeline:
+//!
+//! | Pass                              | Rewrite                                        |
+//! |-----------------------------------|------------------------------------------------|
+//! | [`DeadNodeElimination`]           | drops nodes the requested outputs don't need   |
+//! | [`ConstantFolding`]               | evaluates ops whose inputs are all constants   |
+//! | [`CommonSubexpressionElimination`]| merges identical ops and shared parameters     |
+//! | [`Fusion`]                        | MatMul+Add(+activation) → `FusedLinear`, Add+ReLU → `AddReLU` |
+//! | [`DeadNodeElimination`]           | drops the nodes made unreachable above         |
+//!
+//! Passes are told which nodes are requested outputs (never removed) and
+//! which inputs are fed between runs; every other `Node::Input` is a
+//! constant. Removing nodes renumbers the rest, so optimizing returns a
+//! [`NodeMap`] from old to new ids.
+//!
+//! # Example
+//! ```ignore
+//! let map = graph.optimize(&[prediction, loss], &[x, target])?;
+//! let prediction = map.get(prediction).unwrap();
+//! graph.feed(map.get(x).unwrap(), &batch)?;
+//! ```
+
+use std::collections::HashMap;
+use std::mem::Discriminant;
+use crate::backend::Backend;
+use crate::params::ParamId;
+use crate::{GPResult, NodeId, ParamStore, Tensor};
+use super::{Architecture, Node, OpType};
+
+/// Translates node ids from before an optimization to after it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeMap(Vec<Option<NodeId>>);
+
+impl NodeMap {
+    fn identity(node_count: usize) -> Self {
+        Self((0..node_count).map(|i| Some(NodeId(i))).collect())
+    }
+
+    /// The new id of `old`, or `None` if the node was removed (e.g. folded
+    /// into a constant or fused into a consumer).
+    pub fn get(&self, old: NodeId) -> Option<NodeId> {
+        self.0.get(old.0).copied().flatten()
+    }
+
+    /// Applies a further renumbering on top of this one.
+    fn then(&mut self, next: &[Option<NodeId>]) {
+        for id in &mut self.0 {
+            *id = id.and_then(|current| next.get(current.0).copied().flatten());
+        }
+    }
+}
+
+/// What passes may assume about the graph they rewrite.
+pub struct PassContext<'a> {
+    outputs: Vec<NodeId>,
+    feeds: Vec<NodeId>,
+    params: &'a ParamStore,
+    backend: &'a dyn Backend,
+    map: NodeMap,
+}
+
+impl<'a> PassContext<'a> {
+    pub fn new(node_count: usize, outputs: &[NodeId], feeds: &[NodeId], params: &'a ParamStore, backend: &'a dyn Backend) -> Self {
+        Self {
+            outputs: outputs.to_vec(),
+            feeds: feeds.to_vec(),
+            params,
+            backend,
+            map: NodeMap::identity(node_count),
+        }
+    }
+
+    /// Nodes whose values are requested. Passes must keep them, with the
+    /// same value.
+    pub fn outputs(&self) -> &[NodeId] {
+        &self.outputs
+    }
+
+    pub fn is_output(&self, id: NodeId) -> bool {
+        self.outputs.contains(&id)
+    }
+
+    /// Whether input `id` may be fed between runs. Placeholders and batched
+    /// inputs always are; other inputs not listed as feeds hold constants.
+    pub fn is_fed(&self, arch: &Architecture, id: NodeId) -> bool {
+        self.feeds.contains(&id) || arch.is_placeholder(id)
+    }
+
+    /// Backend used to evaluate constant subgraphs.
+    pub fn backend(&self) -> &dyn Backend {
+        self.backend
+    }
+
+    /// Current shape of every node of `arch`, or `None` where shape inference
+    /// fails. Parameter values change with training, but their shapes don't.
+    pub fn shapes(&self, arch: &Architecture) -> Vec<Option<Vec<usize>>> {
+        let mut shapes: Vec<Option<Vec<usize>>> = Vec::with_capacity(arch.node_count());
+        for node in arch.nodes() {
+            let shape = match node {
+                Node::Input(tensor) => Some(tensor.shape().to_vec()),
+                Node::Param(id) => self.params.get(*id).map(|t| t.shape().to_vec()),
+                Node::Op { op, inputs } => inputs.iter()
+                    .map(|id| shapes[id.0].clone())
+                    .collect::<Option<Vec<_>>>()
+                    .and_then(|input_shapes| op.output_shape(&input_shapes).ok()),
+            };
+            shapes.push(shape);
+        }
+        shapes
+    }
+
+    /// Records that the nodes were renumbered (see [`Architecture::retain_nodes`]).
+    pub fn renumber(&mut self, map: &[Option<NodeId>]) {
+        let remap = |ids: &[NodeId]| ids.iter().filter_map(|id| map.get(id.0).copied().flatten()).collect();
+        self.outputs = remap(&self.outputs);
+        self.feeds = remap(&self.feeds);
+        self.map.then(map);
+    }
+}
+
+/// A rewrite of the graph topology.
+pub trait Pass {
+    fn name(&self) -> &str;
+
+    /// Rewrites `arch`, returning whether anything changed.
+    fn run(&self, arch: &mut Architecture, ctx: &mut PassContext) -> GPResult<bool>;
+}
+
+/// Runs a sequence of passes over an [`Architecture`].
+pub struct PassManager {
+    passes: Vec<Box<dyn Pass>>,
+}
+
+impl PassManager {
+    /// Creates a manager with no passes.
+    pub fn new() -> Self {
+        Self { passes: Vec::new() }
+    }
+
+    /// Appends `pass` to the pipeline.
+    pub fn with_pass(mut self, pass: impl Pass + 'static) -> Self {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    /// Names of the passes, in the order they run.
+    pub fn pass_names(&self) -> Vec<&str> {
+        self.passes.iter().map(|p| p.name()).collect()
+    }
+
+    /// Runs every pass once, in order, keeping `outputs` computable from
+    /// `feeds`. Returns the translation from old to new node ids.
+    pub fn run(
+        &self,
+        arch: &mut Architecture,
+        params: &ParamStore,
+        outputs: &[NodeId],
+        feeds: &[NodeId],
+        backend: &dyn Backend,
+    ) -> GPResult<NodeMap> {
+        let mut ctx = PassContext::new(arch.node_count(), outputs, feeds, params, backend);
+        for pass in &self.passes {
+            pass.run(arch, &mut ctx)?;
+        }
+        Ok(ctx.map)
+    }
+}
+
+impl Default for PassManager {
+    /// The standard pipeline (see the [module docs](self)).
+    fn default() -> Self {
+        Self::new()
+            .with_pass(DeadNodeElimination)
+            .with_pass(ConstantFolding)
+            .with_pass(CommonSubexpressionElimination)
+            .with_pass(Fusion)
+            .with_pass(DeadNodeElimination)
+    }
+}
+
+// ── Passes ─────────────────────────────────────────────────────────────────
+
+/// Removes every node the requested outputs don't depend on.
+pub struct DeadNodeElimination;
+
+impl Pass for DeadNodeElimination {
+    fn name(&self) -> &str {
+        "DeadNodeElimination"
+    }
+
+    fn run(&self, arch: &mut Architecture, ctx: &mut PassContext) -> GPResult<bool> {
+        let mut keep = vec![false; arch.node_count()];
+        for id in arch.execution_order(ctx.outputs())? {
+            keep[id.0] = true;
+        }
+        if keep.iter().all(|&k| k) {
+            return Ok(false);
+        }
+        let map = arch.retain_nodes(&keep)?;
+        ctx.renumber(&map);
+        Ok(true)
+    }
+}
+
+/// Replaces ops whose inputs are all constants with their value, as a
+/// constant input. Parameters are never constant; Dropout, BatchNorm and
+/// custom ops are never folded.
+pub struct ConstantFolding;
+
+impl Pass for ConstantFolding {
+    fn name(&self) -> &str {
+        "ConstantFolding"
+    }
+
+    fn run(&self, arch: &mut Architecture, ctx: &mut PassContext) -> GPResult<bool> {
+        let mut constant = vec![false; arch.node_count()];
+        let mut changed = false;
+
+        for i in 0..arch.node_count() {
+            let id = NodeId(i);
+            let value = match &arch.nodes()[i] {
+                Node::Input(_) => {
+                    constant[i] = !ctx.is_fed(arch, id);
+                    continue;
+                }
+                Node::Op { op, inputs }
+                    if is_deterministic(op) && !inputs.is_empty() && inputs.iter().all(|x| constant[x.0]) =>
+                {
+                    // Folded nodes are inputs by now, so every operand is one
+                    let operands: Vec<&Tensor> = inputs.iter().filter_map(|&x| arch.input_value(x)).collect();
+                    op.forward(&operands, ctx.backend(), false, 0)?
+                }
+                _ => continue,
+            };
+            arch.nodes_mut()[i] = Node::Input(value);
+            constant[i] = true;
+            changed = true;
+        }
+        Ok(changed)
+    }
+}
+
+/// Merges ops that apply the same operation to the same inputs, and nodes
+/// that reference the same parameter. Consumers are redirected to the
+/// first occurrence; the duplicates become dead. Dropout, BatchNorm and
+/// custom ops are never merged.
+pub struct CommonSubexpressionElimination;
+
+impl Pass for CommonSubexpressionElimination {
+    fn name(&self) -> &str {
+        "CommonSubexpressionElimination"
+    }
+
+    fn run(&self, arch: &mut Architecture, _ctx: &mut PassContext) -> GPResult<bool> {
+        let mut canonical: Vec<NodeId> = (0..arch.node_count()).map(NodeId).collect();
+        let mut seen: HashMap<NodeKey, NodeId> = HashMap::new();
+        let mut changed = false;
+
+        for i in 0..arch.node_count() {
+            if let Node::Op { inputs, .. } = &mut arch.nodes_mut()[i] {
+                for input in inputs.iter_mut() {
+                    if canonical[input.0] != *input {
+                        *input = canonical[input.0];
+                        changed = true;
+                    }
+                }
+            }
+
+            let key = match &arch.nodes()[i] {
+                Node::Param(param_id) => NodeKey::Param(*param_id),
+                Node::Op { op, inputs } => {
+                    let Some(op_key) = OpKey::of(op) else { continue };
+                    let mut inputs = inputs.clone();
+                    if matches!(op, OpType::Add | OpType::Mul | OpType::AddReLU) {
+                        inputs.sort_by_key(|id| id.0);
+                    }
+                    NodeKey::Op(op_key, inputs)
+                }
+                Node::Input(_) => continue,
+            };
+            match seen.get(&key) {
+                Some(&first) => canonical[i] = first,
+                None => {
+                    seen.insert(key, NodeId(i));
+                }
+            }
+        }
+        Ok(changed)
+    }
+}
+
+/// Pattern-based op fusion:
+///
+/// - `Add(MatMul(x, w), b)` → `FusedLinear { activation: None }` over `[x, w, b]`,
+///   when `b` broadcasts into the product's shape without expanding it
+/// - `act(FusedLinear { activation: None })` → `FusedLinear { activation: Some(act) }`
+///   for element-wise activations
+/// - `ReLU(Add(a, b))` → `AddReLU(a, b)`
+///
+/// Only intermediates with a single consumer that are not requested
+/// outputs are fused away.
+pub struct Fusion;
+
+impl Pass for Fusion {
+    fn name(&self) -> &str {
+        "Fusion"
+    }
+
+    fn run(&self, arch: &mut Architecture, ctx: &mut PassContext) -> GPResult<bool> {
+        let mut consumers = vec![0usize; arch.node_count()];
+        for node in arch.nodes() {
+            for input in node.inputs().unwrap_or(&[]) {
+                consumers[input.0] += 1;
+            }
+        }
+        // Fused nodes keep the shape of the node they replace
+        let shapes = ctx.shapes(arch);
+        let mut changed = false;
+
+        for i in 0..arch.node_count() {
+            let nodes = arch.nodes();
+            let Node::Op { op, inputs } = &nodes[i] else { continue };
+            let fusible = |id: NodeId| consumers[id.0] == 1 && !ctx.is_output(id);
+            let producer = |id: NodeId| match &nodes[id.0] {
+                Node::Op { op, inputs } if fusible(id) => Some((op, inputs.as_slice())),
+                _ => None,
+            };
+
+            let rewrite = match op {
+                OpType::Add => {
+                    // FusedLinear's output has the product's shape, so a bias
+                    // that would grow it (e.g. `[2, 1] + [1, 3]`) can't be folded in
+                    let keeps_shape = |mm: NodeId, b: NodeId| match (&shapes[mm.0], &shapes[b.0]) {
+                        (Some(product), Some(bias)) => {
+                            OpType::Add.output_shape(&[product.clone(), bias.clone()]).ok().as_ref() == Some(product)
+                        }
+                        _ => false,
+                    };
+                    let matmul = |side: usize| match producer(inputs[side]) {
+                        Some((OpType::MatMul, mm)) if keeps_shape(inputs[side], inputs[1 - side]) => {
+                            Some((inputs[side], mm[0], mm[1], inputs[1 - side]))
+                        }
+                        _ => None,
+                    };
+                    matmul(0).or_else(|| matmul(1)).map(|(fused, x, w, b)| {
+                        (fused, OpType::FusedLinear { activation: None }, vec![x, w, b])
+                    })
+                }
+                act if act.is_elementwise_activation() => match producer(inputs[0]) {
+                    Some((OpType::FusedLinear { activation: None }, linear)) => Some((
+                        inputs[0],
+                        OpType::FusedLinear { activation: Some(Box::new(act.clone())) },
+                        linear.to_vec(),
+                    )),
+                    Some((OpType::Add, add)) if matches!(act, OpType::ReLU) => {
+                        Some((inputs[0], OpType::AddReLU, add.to_vec()))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            };
+
+            if let Some((fused, op, inputs)) = rewrite {
+                arch.nodes_mut()[i] = Node::Op { op, inputs };
+                consumers[fused.0] = 0;
+                changed = true;
+            }
+        }
+        Ok(changed)
+    }
+}
+
+/// Ops whose output depends only on their inputs, with no training-mode
+/// behaviour or side effects.
+fn is_deterministic(op: &OpType) -> bool {
+    !matches!(op, OpType::Dropout { .. } | OpType::BatchNorm { .. } | OpType::Custom(_))
+}
+
+/// What makes two nodes interchangeable for CSE.
+#[derive(PartialEq, Eq, Hash)]
+enum NodeKey {
+    Param(ParamId),
+    Op(OpKey, Vec<NodeId>),
+}
+
+/// Structural identity of an op: its variant and attributes. Floats compare
+/// by bit pattern, so `-0.0` differs from `0.0` and every NaN matches every
+/// other NaN.
+#[derive(PartialEq, Eq, Hash)]
+struct OpKey {
+    kind: Discriminant<OpType>,
+    attrs: Vec<u64>,
+    nested: Option<Box<OpKey>>,
+}
+
+impl OpKey {
+    /// Returns `None` for ops that must not be merged: stochastic ones
+    /// (Dropout), ones with side effects (BatchNorm's buffer updates) and
+    /// custom ops, whose attributes are opaque.
+    fn of(op: &OpType) -> Option<Self> {
+        let float = |x: f32| (if x.is_nan() { f32::NAN } else { x }).to_bits() as u64;
+        let axes = |axes: &[usize], keep_dims: bool| {
+            axes.iter().map(|&a| a as u64).chain([keep_dims as u64]).collect()
+        };
+        let mut nested = None;
+        let attrs = match op {
+            OpType::Dropout { .. } | OpType::BatchNorm { .. } | OpType::Custom(_) => return None,
+            OpType::Conv2D { stride, padding } => vec![*stride as u64, *padding as u64],
+            OpType::MaxPool2D { kernel_size, stride } => vec![*kernel_size as u64, *stride as u64],
+            OpType::Pow { exponent } => vec![float(*exponent)],
+            OpType::Clamp { min, max } | OpType::HardTanh { min, max } => vec![float(*min), float(*max)],
+            OpType::ReduceSum { axes: a, keep_dims }
+            | OpType::ReduceMean { axes: a, keep_dims }
+            | OpType::ReduceMax { axes: a, keep_dims } => axes(a, *keep_dims),
+            OpType::ArgMax { axis, keep_dims } => vec![*axis as u64, *keep_dims as u64],
+            OpType::LeakyReLU { slope } => vec![float(*slope)],
+            OpType::ELU { alpha } => vec![float(*alpha)],
+            OpType::Reshape { target_shape } => target_shape.iter().map(|&d| d as u64).collect(),
+            OpType::Concat { axis } => vec![*axis as u64],
+            OpType::SumToLike { axes: a } | OpType::BroadcastLike { axes: a } => {
+                a.iter().map(|&x| x as u64).collect()
+            }
+            OpType::FillLike { value } => vec![float(*value)],
+            OpType::Gradient { op } => {
+                nested = Some(Box::new(Self::of(op)?));
+                Vec::new()
+            }
+            OpType::SelectGradient { input } => vec![*input as u64],
+            OpType::ConcatSlice { axis, input } => vec![*axis as u64, *input as u64],
+            OpType::FusedLinear { activation } => {
+                if let Some(act) = activation {
+                    nested = Some(Box::new(Self::of(act)?));
+                }
+                Vec::new()
+            }
+            OpType::LayerNorm { epsilon } => vec![float(*epsilon)],
+            OpType::MatMul | OpType::Add | OpType::Mul | OpType::Sub | OpType::Div | OpType::Neg
+            | OpType::Exp | OpType::Log | OpType::Sqrt | OpType::Abs | OpType::MSELoss
+            | OpType::BinaryCrossEntropyLoss | OpType::BCEWithLogitsLoss
+            | OpType::CategoricalCrossEntropyLoss | OpType::CrossEntropyWithLogitsLoss
+            | OpType::ReLU | OpType::Tanh | OpType::Sigmoid | OpType::GELU | OpType::SiLU
+            | OpType::Softplus | OpType::Softmax | OpType::StopGradient | OpType::Transpose
+            | OpType::Step | OpType::AddReLU | OpType::ReshapeLike => Vec::new(),
+        };
+        Some(Self { kind: std::mem::discriminant(op), attrs, nested })
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::cpu::CPUBackend;
+    use crate::graph::Graph;
+    use crate::graph::dsl::GraphBuilder;
+
+    fn op_names(graph: &Graph) -> Vec<String> {
+        graph.nodes().iter().filter_map(|n| n.op()).map(|op| op.name().to_string()).collect()
+    }
+
+    fn t(shape: &[usize], data: Vec<f32>) -> Tensor {
+        Tensor::from_shape_vec(shape, data).unwrap()
+    }
+
+    /// `relu(relu(x·w1 + b1)·w2 + b2 + tanh(x·w3))`.
+    ///
+    /// Returns the graph with its input, the first pre-activation, the
+    /// residual sum and the output.
+    fn mlp() -> (Graph, NodeId, NodeId, NodeId, NodeId) {
+        let mut graph = Graph::new(Box::new(CPUBackend));
+        let mut gb = GraphBuilder::new(&mut graph);
+        let x = gb.placeholder(&[2, 3]);
+        let w1 = gb.param(t(&[3, 4], (0..12).map(|i| (i as f32 * 0.37).sin()).collect()));
+        let b1 = gb.param(t(&[1, 4], vec![0.1, -0.2, 0.3, -0.4]));
+        let w2 = gb.param(t(&[4, 2], (0..8).map(|i| (i as f32 * 0.91).cos()).collect()));
+        let b2 = gb.param(t(&[1, 2], vec![0.05, -0.05]));
+        let w3 = gb.param(t(&[3, 2], (0..6).map(|i| (i as f32 * 0.5).cos()).collect()));
+        let pre = gb.linear(x, w1, b1);
+        let h = gb.relu(pre);
+        let y = gb.linear(h, w2, b2);
+        let skip = gb.matmul(x, w3);
+        let skip = gb.tanh(skip);
+        let sum = gb.add(y, skip);
+        let out = gb.relu(sum);
+        (graph, x, pre, sum, out)
+    }
+
+    #[test]
+    fn test_fusion_matches_unfused_graph() {
+        let input = t(&[2, 3], vec![0.5, -1.0, 2.0, 1.5, 0.2, -0.7]);
+        let (mut reference, x, _, _, out) = mlp();
+        let expected = reference.run(&[(x, &input)], &[out]).unwrap().remove(0);
+        reference.backward(out, Tensor::new_ones(&[2, 2])).unwrap();
+
+        let (mut graph, x, _, _, out) = mlp();
+        let map = graph.optimize(&[out], &[x]).unwrap();
+        assert_eq!(op_names(&graph), vec!["FusedLinear", "FusedLinear", "MatMul", "Tanh", "AddReLU"]);
+
+        let (x, out) = (map.get(x).unwrap(), map.get(out).unwrap());
+        let got = graph.run(&[(x, &input)], &[out]).unwrap().remove(0);
+        for (a, b) in got.as_slice().unwrap().iter().zip(expected.as_slice().unwrap()) {
+            assert!((a - b).abs() < 1e-5);
+        }
+
+        // Parameter gradients are unchanged by the rewrite
+        graph.backward(out, Tensor::new_ones(&[2, 2])).unwrap();
+        for id in reference.params().trainable_param_ids() {
+            let (a, b) = (graph.params().gradient(id).unwrap(), reference.params().gradient(id).unwrap());
+            for (ga, gb) in a.as_slice().unwrap().iter().zip(b.as_slice().unwrap()) {
+                assert!((ga - gb).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fusion_keeps_requested_intermediates() {
+        // A requested pre-activation can't be absorbed by its ReLU
+        let (mut graph, x, pre, _, out) = mlp();
+        let map = graph.optimize(&[pre, out], &[x]).unwrap();
+        assert_eq!(op_names(&graph), vec!["FusedLinear", "ReLU", "FusedLinear", "MatMul", "Tanh", "AddReLU"]);
+        assert!(matches!(graph.nodes()[map.get(pre).unwrap().0].op(), Some(OpType::FusedLinear { activation: None })));
+
+        let (mut graph, x, _, sum, out) = mlp();
+        graph.optimize(&[sum, out], &[x]).unwrap();
+        assert_eq!(op_names(&graph), vec!["FusedLinear", "FusedLinear", "MatMul", "Tanh", "Add", "ReLU"]);
+    }
+
+    #[test]
+    fn test_fusion_skips_biases_that_expand_the_product() {
+        let build = |bias: Tensor| {
+            let mut graph = Graph::new(Box::new(CPUBackend));
+            let mut gb = GraphBuilder::new(&mut graph);
+            let x = gb.placeholder(&[2, 3]);
+            let w = gb.param(t(&[3, 1], vec![0.5, -1.0, 0.25]));
+            let b = gb.param(bias);
+            let out = gb.linear(x, w, b);
+            (graph, x, w, out)
+        };
+        let input = t(&[2, 3], vec![0.5, -1.0, 2.0, 1.5, 0.2, -0.7]);
+
+        // [2, 1] + [1, 4] is a [2, 4] outer sum, not a linear layer
+        let (mut reference, x, w, out) = build(t(&[1, 4], vec![0.1, 0.2, 0.3, 0.4]));
+        let expected = reference.run(&[(x, &input)], &[out]).unwrap().remove(0);
+        reference.backward(out, Tensor::new_ones(&[2, 4])).unwrap();
+
+        let (mut graph, x, _, out) = build(t(&[1, 4], vec![0.1, 0.2, 0.3, 0.4]));
+        let map = graph.optimize(&[out], &[x]).unwrap();
+        assert_eq!(op_names(&graph), vec!["MatMul", "Add"]);
+        let got = graph.run(&[(map.get(x).unwrap(), &input)], &[map.get(out).unwrap()]).unwrap().remove(0);
+        assert_eq!(got, expected);
+        graph.backward(map.get(out).unwrap(), Tensor::new_ones(&[2, 4])).unwrap();
+        let w_id = reference.nodes()[w.0].param_id().unwrap();
+        assert_eq!(graph.params().gradient(w_id).unwrap(), reference.params().gradient(w_id).unwrap());
+
+        // A bias that fits the product still fuses
+        let (mut graph, x, _, out) = build(t(&[1, 1], vec![0.1]));
+        graph.optimize(&[out], &[x]).unwrap();
+        assert_eq!(op_names(&graph), vec!["FusedLinear"]);
+    }
+
+    #[test]
+    fn test_constant_folding_and_dead_nodes() {
+        let mut graph = Graph::new(Box::new(CPUBackend));
+        let mut gb = GraphBuilder::new(&mut graph);
+        let x = gb.placeholder(&[1, 2]);
+        let scale = gb.val(t(&[1, 2], vec![2.0, 3.0]));
+        let offset = gb.val(t(&[1, 2], vec![1.0, 1.0]));
+        let shifted = gb.add(scale, offset); // constant: [3, 4]
+        let squared = gb.mul(shifted, shifted); // constant: [9, 16]
+        let y = gb.mul(x, squared);
+        let unused = gb.exp(x);
+
+        let map = graph.optimize(&[y], &[x]).unwrap();
+        assert_eq!(op_names(&graph), vec!["Mul"]);
+        assert_eq!(graph.nodes().len(), 3);
+        assert!(map.get(unused).is_none() && map.get(shifted).is_none());
+
+        let (x, y) = (map.get(x).unwrap(), map.get(y).unwrap());
+        let out = graph.run(&[(x, &t(&[1, 2], vec![1.0, -1.0]))], &[y]).unwrap();
+        assert_eq!(out[0].as_slice().unwrap(), &[9.0, -16.0]);
+    }
+
+    #[test]
+    fn test_fed_inputs_are_not_folded() {
+        let mut graph = Graph::new(Box::new(CPUBackend));
+        let mut gb = GraphBuilder::new(&mut graph);
+        let a = gb.val(t(&[1, 2], vec![1.0, 2.0]));
+        let b = gb.val(t(&[1, 2], vec![3.0, 4.0]));
+        let sum = gb.add(a, b);
+
+        let map = graph.optimize(&[sum], &[a]).unwrap();
+        assert_eq!(op_names(&graph), vec!["Add"]);
+        let a = map.get(a).unwrap();
+        let out = graph.run(&[(a, &t(&[1, 2], vec![10.0, 20.0]))], &[map.get(sum).unwrap()]).unwrap();
+        assert_eq!(out[0].as_slice().unwrap(), &[13.0, 24.0]);
+    }
+
+    #[test]
+    fn test_placeholders_are_fed_without_listing_them() {
+        let mut graph = Graph::new(Box::new(CPUBackend));
+        let mut gb = GraphBuilder::new(&mut graph);
+        let x = gb.placeholder(&[1, 2]);
+        let b = gb.val(t(&[1, 2], vec![3.0, 4.0]));
+        let sum = gb.add(x, b);
+
+        let map = graph.optimize(&[sum], &[]).unwrap();
+        assert_eq!(op_names(&graph), vec!["Add"]);
+        let x = map.get(x).unwrap();
+        let out = graph.run(&[(x, &t(&[1, 2], vec![10.0, 20.0]))], &[map.get(sum).unwrap()]).unwrap();
+        assert_eq!(out[0].as_slice().unwrap(), &[13.0, 24.0]);
+    }
+
+    #[test]
+    fn test_cse_merges_duplicates_and_shared_params() {
+        let mut graph = Graph::new(Box::new(CPUBackend));
+        let mut gb = GraphBuilder::new(&mut graph);
+        let x = gb.placeholder(&[1, 2]);
+        let w = gb.param(t(&[2, 2], vec![1.0, 2.0, 3.0, 4.0]));
+        let w_again = gb.param_ref(gb.param_id(w).unwrap());
+        let a = gb.matmul(x, w);
+        let b = gb.matmul(x, w_again);
+        let ta = gb.tanh(a);
+        let tb = gb.tanh(b);
+        let sum = gb.add(tb, ta);
+        let sum_swapped = gb.add(ta, tb);
+        let out = gb.mul(sum, sum_swapped);
+
+        let passes = PassManager::new()
+            .with_pass(CommonSubexpressionElimination)
+            .with_pass(DeadNodeElimination);
+        let backend = CPUBackend;
+        let params = graph.params().clone();
+        let map = passes.run(graph.arch_mut(), &params, &[out], &[x], &backend).unwrap();
+        assert_eq!(op_names(&graph), vec!["MatMul", "Tanh", "Add", "Mul"]);
+        assert_eq!(map.get(w_again), None);
+        assert_eq!(map.get(out), Some(NodeId(5)));
+        assert_eq!(passes.pass_names(), vec!["CommonSubexpressionElimination", "DeadNodeElimination"]);
+    }
+
+    #[test]
+    fn test_cse_compares_attributes_structurally() {
+        let mut graph = Graph::new(Box::new(CPUBackend));
+        let mut gb = GraphBuilder::new(&mut graph);
+        let x = gb.placeholder(&[1, 2]);
+        let clamp = gb.clamp(x, -1.0, 1.0);
+        let clamp_again = gb.clamp(x, -1.0, 1.0);
+        let nan = gb.pow(x, f32::NAN);
+        let nan_again = gb.pow(x, -f32::NAN);
+        let zero = gb.leaky_relu(x, 0.0);
+        let neg_zero = gb.leaky_relu(x, -0.0);
+        let drop = gb.dropout(x, 0.5);
+        let drop_again = gb.dropout(x, 0.5);
+        let outputs = [clamp, clamp_again, nan, nan_again, zero, neg_zero, drop, drop_again];
+        let mut acc = outputs[0];
+        for &node in &outputs[1..] {
+            acc = gb.add(acc, node);
+        }
+
+        let passes = PassManager::new()
+            .with_pass(CommonSubexpressionElimination)
+            .with_pass(DeadNodeElimination);
+        let params = graph.params().clone();
+        let map = passes.run(graph.arch_mut(), &params, &[acc], &[], &CPUBackend).unwrap();
+        assert_eq!(map.get(clamp_again), None);
+        assert_eq!(map.get(nan_again), None);
+        assert!(map.get(zero).is_some() && map.get(neg_zero).is_some());
+        assert!(map.get(drop).is_some() && map.get(drop_again).is_some());
+    }
+}
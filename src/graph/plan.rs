@@ -0,0 +1,176 @@
+//! Compiled execution plans — the topological order and input tables of a
+//! subgraph, built once and reused by every forward and backward pass.
+//!
+//! Sorting the graph costs a DFS over the whole subgraph, and validating each
+//! node's inputs costs another pass. An [`ExecutionPlan`] does both once for a
+//! set of targets and records the [`Architecture::version`] it was built from.
+//! [`ExecutionEngine`](super::ExecutionEngine) caches plans per target set and
+//! rebuilds them automatically after the architecture changes.
+
+use crate::{GPError, GPResult, NodeId};
+use super::Architecture;
+
+/// A validated execution order with flattened per-step tables.
+#[derive(Debug, Clone)]
+pub struct ExecutionPlan {
+    version: u64,
+    targets: Vec<NodeId>,
+    order: Vec<NodeId>,
+    /// `inputs[offsets[s]..offsets[s + 1]]` are the inputs of step `s`
+    /// (empty for inputs and parameters).
+    offsets: Vec<usize>,
+    inputs: Vec<NodeId>,
+    /// The last step that reads the value computed at each step.
+    last_use: Vec<usize>,
+    /// Steps grouped by dependency depth: no step reads a value computed in
+    /// its own level, so the steps of a level can run concurrently.
+    levels: Vec<Vec<usize>>,
+}
+
+impl ExecutionPlan {
+    /// Plans the subgraph every node in `targets` depends on.
+    ///
+    /// A single target uses [`Architecture::topological_sort`]; several use
+    /// [`Architecture::execution_order`].
+    pub fn new(arch: &Architecture, targets: &[NodeId]) -> GPResult<Self> {
+        let order = match targets {
+            [target] => arch.topological_sort(*target)?,
+            _ => arch.execution_order(targets)?,
+        };
+        let mut plan = Self::from_order(arch, &order)?;
+        plan.targets = targets.to_vec();
+        Ok(plan)
+    }
+
+    /// Plans a caller-supplied order.
+    ///
+    /// Inputs left out of the order (e.g. parameters, which the engine syncs
+    /// separately) keep their cached values, but every input must have a
+    /// lower id than the op reading it.
+    pub fn from_order(arch: &Architecture, order: &[NodeId]) -> GPResult<Self> {
+        let mut position = vec![None; arch.node_count()];
+        let mut offsets = Vec::with_capacity(order.len() + 1);
+        let mut inputs = Vec::new();
+        let mut last_use: Vec<usize> = (0..order.len()).collect();
+        let mut depth = vec![0; order.len()];
+        let mut levels: Vec<Vec<usize>> = Vec::new();
+        offsets.push(0);
+
+        for (step, &id) in order.iter().enumerate() {
+            let node = arch.get_node(id).ok_or_else(|| GPError::InferenceError(format!(
+                "Node index {} out of bounds", id.0
+            )))?;
+            for &input in node.inputs().unwrap_or(&[]) {
+                // The engine relies on lower input ids to borrow inputs and output together
+                if input.0 >= id.0 {
+                    return Err(GPError::InferenceError(format!(
+                        "Input node {:?} is invalid or not before node {:?}", input, id
+                    )));
+                }
+                if let Some(earlier) = position[input.0] {
+                    last_use[earlier] = step;
+                    depth[step] = depth[step].max(depth[earlier] + 1);
+                }
+                inputs.push(input);
+            }
+            offsets.push(inputs.len());
+            position[id.0] = Some(step);
+            if levels.len() <= depth[step] {
+                levels.resize(depth[step] + 1, Vec::new());
+            }
+            levels[depth[step]].push(step);
+        }
+
+        Ok(Self {
+            version: arch.version(),
+            targets: Vec::new(),
+            order: order.to_vec(),
+            offsets,
+            inputs,
+            last_use,
+            levels,
+        })
+    }
+
+    /// The [`Architecture::version`] the plan was built from.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns true if `arch` has not changed since the plan was built.
+    pub fn is_current(&self, arch: &Architecture) -> bool {
+        self.version == arch.version()
+    }
+
+    /// The targets the plan was built for (empty for [`from_order`](Self::from_order)).
+    pub fn targets(&self) -> &[NodeId] {
+        &self.targets
+    }
+
+    /// The execution order.
+    pub fn order(&self) -> &[NodeId] {
+        &self.order
+    }
+
+    /// The inputs of the node at `step` of the order.
+    pub fn inputs(&self, step: usize) -> &[NodeId] {
+        &self.inputs[self.offsets[step]..self.offsets[step + 1]]
+    }
+
+    /// The last step reading the value computed at `step` (`step` itself if
+    /// nothing in the plan reads it).
+    pub fn last_use(&self, step: usize) -> usize {
+        self.last_use[step]
+    }
+
+    /// Steps grouped into dependency levels, in execution order: every input
+    /// of a step is computed in an earlier level.
+    pub fn levels(&self) -> &[Vec<usize>] {
+        &self.levels
+    }
+
+    /// The most steps in a single level: how many nodes could run at once.
+    pub fn width(&self) -> usize {
+        self.levels.iter().map(Vec::len).max().unwrap_or(0)
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::OpType;
+    use crate::Tensor;
+
+    #[test]
+    fn test_plan_tables() {
+        let mut arch = Architecture::new();
+        let x = arch.input(Tensor::new_zeros(&[1, 2]));
+        let a = arch.op(OpType::ReLU, vec![x]);
+        let b = arch.op(OpType::Tanh, vec![x]);
+        let c = arch.op(OpType::Add, vec![a, b]);
+
+        let plan = ExecutionPlan::new(&arch, &[c]).unwrap();
+        assert_eq!(plan.order(), &[x, a, b, c]);
+        assert_eq!(plan.inputs(0), &[]);
+        assert_eq!(plan.inputs(3), &[a, b]);
+        assert_eq!((0..4).map(|s| plan.last_use(s)).collect::<Vec<_>>(), vec![2, 3, 3, 3]);
+        assert_eq!(plan.levels(), &[vec![0], vec![1, 2], vec![3]]);
+        assert_eq!(plan.width(), 2);
+        assert!(plan.is_current(&arch));
+
+        arch.op(OpType::Neg, vec![c]);
+        assert!(!plan.is_current(&arch));
+    }
+
+    #[test]
+    fn test_plan_rejects_forward_references() {
+        let mut arch = Architecture::new();
+        let x = arch.input(Tensor::new_zeros(&[1, 2]));
+        let a = arch.op(OpType::ReLU, vec![NodeId(2)]);
+        let b = arch.op(OpType::Tanh, vec![x]);
+        assert!(ExecutionPlan::from_order(&arch, &[x, b, a]).is_err());
+        assert!(ExecutionPlan::from_order(&arch, &[x, b]).is_ok());
+    }
+}
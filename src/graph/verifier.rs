@@ -1,6 +1,31 @@
 use crate::graph::{Graph, Node};
 use crate::{GPResult, GPError, NodeId};
 use std::collections::HashMap;
+use std::fmt;
+
+/// A dimension in a symbolic shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dim {
+    /// A size that does not depend on the batch size.
+    Fixed(usize),
+    /// A multiple of the symbolic batch size `N`: `Batch(1)` is `N` itself,
+    /// `Batch(4)` is `4N` (e.g. after reshaping the batch into another axis).
+    Batch(usize),
+}
+
+impl fmt::Display for Dim {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dim::Fixed(n) => write!(f, "{}", n),
+            Dim::Batch(1) => write!(f, "N"),
+            Dim::Batch(k) => write!(f, "{}N", k),
+        }
+    }
+}
+
+/// Batch sizes substituted for `N` when inferring symbolic shapes. Two
+/// coprime probes tell batch-dependent dimensions apart from fixed ones.
+const PROBE_BATCHES: (usize, usize) = (2, 3);
 
 /// Static shape verifier for computation graphs.
 ///
@@ -11,8 +36,59 @@ pub struct Verifier;
 impl Verifier {
     /// Validates the graph for shape consistency and connectivity.
     ///
-    /// Returns a map of `NodeId → predicted shape` for every node in the graph.
+    /// Returns a map of `NodeId → predicted shape` for every node in the graph,
+    /// using the current shapes of the input tensors.
     pub fn verify(graph: &Graph) -> GPResult<HashMap<NodeId, Vec<usize>>> {
+        Self::infer(graph, None)
+    }
+
+    /// Validates the graph for every batch size of its batched inputs
+    /// (see [`Graph::batched_input`]).
+    ///
+    /// Returns a map of `NodeId → symbolic shape`, where dimensions that
+    /// follow the batch size are [`Dim::Batch`]. Fails if some node only
+    /// type-checks for particular batch sizes (e.g. a batched input combined
+    /// with a fixed-size target), or if a dimension depends on the batch size
+    /// other than linearly.
+    ///
+    /// Shapes are not propagated symbolically: the graph is inferred
+    /// concretely at the batch sizes of `PROBE_BATCHES`, and each dimension
+    /// is classified by comparing the two results. Equal sizes are
+    /// [`Dim::Fixed`]; sizes `k * 2` and `k * 3` are `Dim::Batch(k)`; anything
+    /// else (e.g. `N * N` after flattening an `[N, N]` product) is rejected.
+    /// A dimension is therefore only checked to be linear at the probes,
+    /// which holds for every op whose output sizes are sums and products of
+    /// input sizes with at most one batch factor.
+    pub fn verify_symbolic(graph: &Graph) -> GPResult<HashMap<NodeId, Vec<Dim>>> {
+        let (a, b) = PROBE_BATCHES;
+        let with_a = Self::infer(graph, Some(a))?;
+        let with_b = Self::infer(graph, Some(b))?;
+
+        let mut symbolic = HashMap::with_capacity(with_a.len());
+        for (id, shape_a) in with_a {
+            let shape_b = &with_b[&id];
+            if shape_a.len() != shape_b.len() {
+                return Err(GPError::InferenceError(format!(
+                    "Node {} has a rank that depends on the batch size", id.0
+                )));
+            }
+            let dims = shape_a.iter().zip(shape_b)
+                .map(|(&da, &db)| match () {
+                    _ if da == db => Ok(Dim::Fixed(da)),
+                    _ if da % a == 0 && db % b == 0 && da / a == db / b => Ok(Dim::Batch(da / a)),
+                    _ => Err(GPError::InferenceError(format!(
+                        "Node {} has a dimension that does not scale linearly with the batch size", id.0
+                    ))),
+                })
+                .collect::<GPResult<Vec<Dim>>>()?;
+            symbolic.insert(id, dims);
+        }
+        Ok(symbolic)
+    }
+
+    /// Shape inference over the whole graph, optionally substituting `batch`
+    /// for the first dimension of every batched input.
+    fn infer(graph: &Graph, batch: Option<usize>) -> GPResult<HashMap<NodeId, Vec<usize>>> {
         let mut predicted_shapes = HashMap::new();
         let nodes = graph.nodes();
 
@@ -20,7 +96,13 @@ impl Verifier {
             let id = NodeId(i);
             match node {
                 Node::Input(tensor) => {
-                    predicted_shapes.insert(id, tensor.shape().to_vec());
+                    let mut shape = tensor.shape().to_vec();
+                    if let (Some(n), Some(first)) = (batch, shape.first_mut()) {
+                        if graph.arch().is_batched(id) {
+                            *first = n;
+                        }
+                    }
+                    predicted_shapes.insert(id, shape);
                 }
                 Node::Param(param_id) => {
                     let tensor = graph.params().tensor(*param_id);
@@ -49,3 +131,118 @@ impl Verifier {
         Ok(predicted_shapes)
     }
 }
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::cpu::CPUBackend;
+    use crate::graph::OpType;
+    use crate::graph::dsl::GraphBuilder;
+    use crate::layers::GRUCell;
+    use crate::{Layer, Tensor};
+
+    #[test]
+    fn test_verify_broadcast_bias_add() {
+        let mut graph = Graph::new(Box::new(CPUBackend));
+        let mut gb = GraphBuilder::new(&mut graph);
+        let x = gb.val(Tensor::new_zeros(&[4, 2]));
+        let w = gb.param(Tensor::new_zeros(&[2, 3]));
+        let b = gb.param(Tensor::new_zeros(&[1, 3]));
+        let mm = gb.matmul(x, w);
+        let y = gb.add(mm, b);
+
+        let shapes = Verifier::verify(&graph).unwrap();
+        assert_eq!(shapes[&y], vec![4, 3]);
+    }
+
+    #[test]
+    fn test_verify_scalar_mul_and_rank_extension() {
+        let mut graph = Graph::new(Box::new(CPUBackend));
+        let x = graph.input(Tensor::new_zeros(&[2, 1, 5]));
+        let s = graph.input(Tensor::from_elem(&[1, 1], -1.0));
+        let v = graph.input(Tensor::new_zeros(&[3, 1]));
+        let neg = graph.op(OpType::Mul, vec![s, x]);
+        let y = graph.op(OpType::AddReLU, vec![neg, v]);
+
+        let shapes = Verifier::verify(&graph).unwrap();
+        assert_eq!(shapes[&neg], vec![2, 1, 5]);
+        assert_eq!(shapes[&y], vec![2, 3, 5]);
+    }
+
+    #[test]
+    fn test_verify_gru_cell() {
+        let mut graph = Graph::new(Box::new(CPUBackend));
+        let mut gb = GraphBuilder::new(&mut graph);
+        let x = gb.val(Tensor::new_zeros(&[1, 3]));
+        let mut cell = GRUCell::new(3, 4);
+        let h = cell.forward(x, &mut gb);
+
+        let shapes = Verifier::verify(&graph).unwrap();
+        assert_eq!(shapes[&h], vec![1, 4]);
+    }
+
+    #[test]
+    fn test_verify_rejects_incompatible_broadcast() {
+        let mut graph = Graph::new(Box::new(CPUBackend));
+        let a = graph.input(Tensor::new_zeros(&[4, 3]));
+        let b = graph.input(Tensor::new_zeros(&[2, 3]));
+        graph.op(OpType::Add, vec![a, b]);
+
+        assert!(matches!(Verifier::verify(&graph), Err(GPError::InferenceError(_))));
+    }
+
+    #[test]
+    fn test_verify_symbolic_batch_through_flatten() {
+        let mut graph = Graph::new(Box::new(CPUBackend));
+        let mut gb = GraphBuilder::new(&mut graph);
+        let x = gb.batched_val(Tensor::new_zeros(&[1, 2, 4, 4]));
+        let k = gb.param(Tensor::new_zeros(&[3, 2, 3, 3]));
+        let conv = gb.conv2d(x, k, 1, 1);
+        let flat = gb.flatten(conv, 3 * 4 * 4);
+        let rows = gb.reshape(conv, vec![-1, 4]);
+        let w = gb.param(Tensor::new_zeros(&[48, 5]));
+        let y = gb.matmul(flat, w);
+
+        let shapes = Verifier::verify_symbolic(&graph).unwrap();
+        assert_eq!(shapes[&x], vec![Dim::Batch(1), Dim::Fixed(2), Dim::Fixed(4), Dim::Fixed(4)]);
+        assert_eq!(shapes[&flat], vec![Dim::Batch(1), Dim::Fixed(48)]);
+        assert_eq!(shapes[&rows], vec![Dim::Batch(12), Dim::Fixed(4)]);
+        assert_eq!(shapes[&y], vec![Dim::Batch(1), Dim::Fixed(5)]);
+        assert_eq!(format!("{} x {}", shapes[&rows][0], shapes[&rows][1]), "12N x 4");
+
+        // The concrete verifier still reports the shapes for the current batch.
+        assert_eq!(Verifier::verify(&graph).unwrap()[&y], vec![1, 5]);
+    }
+
+    #[test]
+    fn test_verify_symbolic_rejects_batch_specific_graph() {
+        let mut graph = Graph::new(Box::new(CPUBackend));
+        let x = graph.batched_input(Tensor::new_zeros(&[4, 3]));
+        let target = graph.input(Tensor::new_zeros(&[4, 3]));
+        graph.op(OpType::Sub, vec![x, target]);
+
+        // Fine for the batch it was built with, but not for any other.
+        assert!(Verifier::verify(&graph).is_ok());
+        assert!(matches!(Verifier::verify_symbolic(&graph), Err(GPError::InferenceError(_))));
+    }
+
+    #[test]
+    fn test_verify_symbolic_rejects_nonlinear_batch_dimension() {
+        let mut graph = Graph::new(Box::new(CPUBackend));
+        let mut gb = GraphBuilder::new(&mut graph);
+        let x = gb.batched_val(Tensor::new_zeros(&[4, 3]));
+        let xt = gb.transpose(x);
+        let gram = gb.matmul(x, xt);
+        let shapes = Verifier::verify_symbolic(&graph).unwrap();
+        assert_eq!(shapes[&gram], vec![Dim::Batch(1), Dim::Batch(1)]);
+
+        // Flattening [N, N] gives N * N elements: 4 and 9 at the probes.
+        let mut gb = GraphBuilder::new(&mut graph);
+        gb.reshape(gram, vec![-1]);
+        assert!(Verifier::verify(&graph).is_ok());
+        let err = Verifier::verify_symbolic(&graph).unwrap_err();
+        assert!(err.to_string().contains("does not scale linearly"), "{}", err);
+    }
+}
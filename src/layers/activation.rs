@@ -1,14 +1,88 @@
 use crate::{Layer, NodeId};
 use crate::graph::dsl::GraphBuilder;
-use serde::{Serialize, Deserialize};
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de::IntoDeserializer;
 
+/// Element-wise activation functions.
+///
+/// Parameterized variants serialize as `{"leakyrelu": {"slope": 0.01}}`;
+/// omitted fields fall back to the conventional defaults. A bare name such as
+/// `"leakyrelu"` is also accepted and uses the defaults for every field.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-#[serde(rename_all = "lowercase")]
+#[serde(remote = "Self", rename_all = "lowercase")]
 pub enum ActivationType {
     ReLU,
     Sigmoid,
     Tanh,
     Softmax,
+    LeakyReLU {
+        #[serde(default = "default_leaky_slope")]
+        slope: f32,
+    },
+    ELU {
+        #[serde(default = "default_elu_alpha")]
+        alpha: f32,
+    },
+    GELU,
+    #[serde(alias = "swish")]
+    SiLU,
+    Softplus,
+    HardTanh {
+        #[serde(default = "default_hardtanh_min")]
+        min: f32,
+        #[serde(default = "default_hardtanh_max")]
+        max: f32,
+    },
+}
+
+fn default_leaky_slope() -> f32 { 0.01 }
+fn default_elu_alpha() -> f32 { 1.0 }
+fn default_hardtanh_min() -> f32 { -1.0 }
+fn default_hardtanh_max() -> f32 { 1.0 }
+
+impl Serialize for ActivationType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ActivationType::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ActivationType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Name(String),
+            Tagged(#[serde(deserialize_with = "ActivationType::deserialize")] ActivationType),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            // Struct variants named without fields take their defaults
+            Repr::Name(name) => match name.as_str() {
+                "leakyrelu" => Ok(ActivationType::leaky_relu()),
+                "elu" => Ok(ActivationType::elu()),
+                "hardtanh" => Ok(ActivationType::hardtanh()),
+                _ => ActivationType::deserialize(name.into_deserializer()),
+            },
+            Repr::Tagged(activation) => Ok(activation),
+        }
+    }
+}
+
+impl ActivationType {
+    /// LeakyReLU with the conventional slope of 0.01.
+    pub fn leaky_relu() -> Self {
+        ActivationType::LeakyReLU { slope: default_leaky_slope() }
+    }
+
+    /// ELU with `alpha = 1.0`.
+    pub fn elu() -> Self {
+        ActivationType::ELU { alpha: default_elu_alpha() }
+    }
+
+    /// HardTanh clamping into `[-1, 1]`.
+    pub fn hardtanh() -> Self {
+        ActivationType::HardTanh { min: default_hardtanh_min(), max: default_hardtanh_max() }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -30,6 +104,12 @@ impl Layer for Activation {
             ActivationType::Sigmoid => graph.sigmoid(input),
             ActivationType::Tanh => graph.tanh(input),
             ActivationType::Softmax => graph.softmax(input),
+            ActivationType::LeakyReLU { slope } => graph.leaky_relu(input, slope),
+            ActivationType::ELU { alpha } => graph.elu(input, alpha),
+            ActivationType::GELU => graph.gelu(input),
+            ActivationType::SiLU => graph.silu(input),
+            ActivationType::Softplus => graph.softplus(input),
+            ActivationType::HardTanh { min, max } => graph.hardtanh(input, min, max),
         }
     }
 }
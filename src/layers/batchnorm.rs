@@ -3,26 +3,29 @@
 //! Normalizes inputs per-feature across the batch dimension, then applies
 //! a learnable affine transform: `output = gamma * normalized + beta`.
 //!
-//! Uses `OpType::BatchNorm` which computes batch statistics at execution time.
-//! Running statistics are NOT maintained across batches — each forward pass
-//! computes fresh statistics from the current batch.
+//! Uses `OpType::BatchNorm` with running statistics:
+//! - Training mode: normalizes with the current batch's statistics and blends
+//!   them into `running_mean` / `running_var` by `momentum`.
+//! - Inference mode: normalizes with the running statistics, so each sample's
+//!   output is independent of the rest of the batch (including batch_size=1).
 //!
-//! This is acceptable for:
-//! - Training (always uses batch stats)
-//! - Inference with batch_size > 1 (uses batch stats)
-//! - WASM/neuroevolution (small batches)
+//! The running statistics are registered as non-trainable buffers in the
+//! [`ParamStore`](crate::ParamStore), so they are persisted by `state_dict()`
+//! and checkpoints but never touched by optimizers.
 //!
-//! For inference with batch_size=1, falls back to affine transform (gamma*x+beta).
+//! At batch_size=1 in training mode, falls back to the affine transform
+//! (gamma*x+beta) and leaves the running statistics unchanged.
 
-use crate::{Tensor, Layer, NodeId};
+use crate::{GPResult, Tensor, Layer, NodeId, ParamStore};
 use crate::graph::dsl::GraphBuilder;
 use crate::graph::OpType;
+use crate::layers::ParamBinding;
 use serde::{Serialize, Deserialize};
 
 /// Batch Normalization: `y = gamma * (x - mean) / sqrt(var + eps) + beta`.
 ///
 /// - `gamma` (scale) and `beta` (shift) are learnable parameters.
-/// - Statistics computed per-feature across the batch dimension.
+/// - `running_mean` / `running_var` are buffers updated during training.
 ///
 /// # Shape
 ///
@@ -31,8 +34,14 @@ use serde::{Serialize, Deserialize};
 pub struct BatchNorm {
     pub num_features: usize,
     pub epsilon: f32,
+    pub momentum: f32,
     pub gamma: Tensor,
     pub beta: Tensor,
+    pub running_mean: Tensor,
+    pub running_var: Tensor,
+    /// Bound `[gamma, beta, running_mean, running_var]` ids.
+    #[serde(skip)]
+    pub binding: ParamBinding,
 }
 
 impl BatchNorm {
@@ -40,8 +49,12 @@ impl BatchNorm {
         Self {
             num_features,
             epsilon: 1e-5,
+            momentum: 0.1,
             gamma: Tensor::new_ones(&[1, num_features]),
             beta: Tensor::new_zeros(&[1, num_features]),
+            running_mean: Tensor::new_zeros(&[1, num_features]),
+            running_var: Tensor::new_ones(&[1, num_features]),
+            binding: ParamBinding::default(),
         }
     }
 }
@@ -49,11 +62,28 @@ impl BatchNorm {
 #[typetag::serde]
 impl Layer for BatchNorm {
     fn forward(&mut self, input: NodeId, graph: &mut GraphBuilder) -> NodeId {
-        let gamma_node = graph.param(self.gamma.clone());
-        let beta_node = graph.param(self.beta.clone());
-        graph.node(
-            OpType::BatchNorm { epsilon: self.epsilon },
-            vec![input, gamma_node, beta_node],
-        )
+        let params = self.binding.get_or_register(graph, |g| {
+            let prefix = g.layer_prefix("batchnorm");
+            vec![
+                g.named_param(self.gamma.clone(), &format!("{}.gamma", prefix)),
+                g.named_param(self.beta.clone(), &format!("{}.beta", prefix)),
+                g.named_buffer(self.running_mean.clone(), &format!("{}.running_mean", prefix)),
+                g.named_buffer(self.running_var.clone(), &format!("{}.running_var", prefix)),
+            ]
+        });
+        let mut inputs = vec![input];
+        inputs.extend(params);
+        graph.node(OpType::BatchNorm { epsilon: self.epsilon, momentum: self.momentum }, inputs)
+    }
+
+    /// Pulls `gamma`, `beta` and the running statistics.
+    fn pull_params(&mut self, params: &ParamStore) -> GPResult<()> {
+        self.binding.pull(params, &mut [
+            &mut self.gamma, &mut self.beta, &mut self.running_mean, &mut self.running_var,
+        ])
+    }
+
+    fn push_params(&self, params: &mut ParamStore) -> GPResult<()> {
+        self.binding.push(params, &[&self.gamma, &self.beta, &self.running_mean, &self.running_var])
     }
 }
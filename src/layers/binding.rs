@@ -0,0 +1,126 @@
+//! Parameter binding: lets a layer reuse its weights across calls.
+//!
+//! The first time a layer is called in a graph it registers its tensors in
+//! the [`ParamStore`] and records the resulting [`ParamId`]s here. Calling
+//! the same layer again in that graph references those ids instead of
+//! registering copies, so all uses share one set of weights and their
+//! gradients accumulate into it (siamese towers, repeated blocks).
+//!
+//! A binding is tied to one store: called on a different graph, the layer
+//! registers afresh. Copying a binding into another layer of the same shape
+//! ties the two layers together.
+//!
+//! Training updates the store, not the layer. [`Layer::pull_params`](crate::Layer::pull_params)
+//! copies the trained values back into the layer's fields (so typetag
+//! serialization saves them); [`Layer::push_params`](crate::Layer::push_params)
+//! goes the other way.
+
+use crate::graph::dsl::GraphBuilder;
+use crate::params::{ParamStore, ParamId};
+use crate::{GPError, GPResult, NodeId, Tensor};
+
+/// The [`ParamId`]s a layer is bound to, and the store that issued them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParamBinding {
+    store: Option<u64>,
+    ids: Vec<ParamId>,
+}
+
+impl ParamBinding {
+    /// Binds explicitly to existing parameters of `params` (e.g. to tie a
+    /// layer to weights created elsewhere). Ids must be in the layer's order.
+    pub fn new(params: &ParamStore, ids: Vec<ParamId>) -> Self {
+        Self { store: Some(params.uid()), ids }
+    }
+
+    /// The bound parameter ids, empty if unbound.
+    pub fn ids(&self) -> &[ParamId] {
+        &self.ids
+    }
+
+    /// Returns true if the ids were issued by `params`.
+    pub fn is_bound_to(&self, params: &ParamStore) -> bool {
+        self.store == Some(params.uid())
+    }
+
+    /// Forgets the bound ids; the next call registers fresh parameters.
+    pub fn clear(&mut self) {
+        self.store = None;
+        self.ids.clear();
+    }
+
+    /// Param nodes referencing the bound ids, or `None` if not bound to this graph.
+    pub fn reuse(&self, graph: &mut GraphBuilder) -> Option<Vec<NodeId>> {
+        if !self.is_bound_to(graph.params()) {
+            return None;
+        }
+        Some(self.ids.iter().map(|&id| graph.param_ref(id)).collect())
+    }
+
+    /// Records the ids behind freshly registered param (or buffer) nodes.
+    pub fn record(&mut self, graph: &GraphBuilder, nodes: &[NodeId]) {
+        self.ids = nodes.iter()
+            .map(|&n| graph.param_id(n).expect("ParamBinding::record expects Param nodes"))
+            .collect();
+        self.store = Some(graph.params().uid());
+    }
+
+    /// Copies the bound parameters' current values into `tensors`, which must
+    /// be listed in binding order.
+    pub fn pull(&self, params: &ParamStore, tensors: &mut [&mut Tensor]) -> GPResult<()> {
+        self.check(params, tensors.len())?;
+        for (&id, tensor) in self.ids.iter().zip(tensors.iter_mut()) {
+            let current = params.tensor(id);
+            check_shape(params, id, tensor, current)?;
+            **tensor = current.clone();
+        }
+        Ok(())
+    }
+
+    /// Writes `tensors` (in binding order) into the bound parameters.
+    pub fn push(&self, params: &mut ParamStore, tensors: &[&Tensor]) -> GPResult<()> {
+        self.check(params, tensors.len())?;
+        for (&id, &tensor) in self.ids.iter().zip(tensors) {
+            check_shape(params, id, tensor, params.tensor(id))?;
+            params.tensor_mut(id).copy_from(tensor)?;
+        }
+        Ok(())
+    }
+
+    fn check(&self, params: &ParamStore, expected: usize) -> GPResult<()> {
+        let valid = self.ids.iter().all(|id| params.get(*id).is_some());
+        if !self.is_bound_to(params) || self.ids.len() != expected || !valid {
+            return Err(GPError::UnboundParams { expected, bound: self.ids.len() });
+        }
+        Ok(())
+    }
+
+    /// Param nodes for the bound ids if bound to this graph; otherwise the
+    /// nodes returned by `register`, whose ids are recorded.
+    pub fn get_or_register(&mut self, graph: &mut GraphBuilder, register: impl FnOnce(&mut GraphBuilder) -> Vec<NodeId>) -> Vec<NodeId> {
+        if let Some(nodes) = self.reuse(graph) {
+            return nodes;
+        }
+        let nodes = register(graph);
+        self.record(graph, &nodes);
+        nodes
+    }
+
+    /// Param nodes for the named `tensors`: reused if bound to this graph,
+    /// otherwise registered as trainable parameters of a `kind` layer (see
+    /// [`GraphBuilder::layer_params`]) and recorded.
+    pub fn nodes(&mut self, graph: &mut GraphBuilder, kind: &str, tensors: &[(&str, &Tensor)]) -> Vec<NodeId> {
+        self.get_or_register(graph, |g| g.layer_params(kind, tensors))
+    }
+}
+
+fn check_shape(params: &ParamStore, id: ParamId, layer: &Tensor, stored: &Tensor) -> GPResult<()> {
+    if layer.shape() != stored.shape() {
+        return Err(GPError::ParamShapeMismatch {
+            name: params.key(id),
+            expected: layer.shape().to_vec(),
+            found: stored.shape().to_vec(),
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,68 @@
+//! 2D convolution layer.
+
+use crate::{GPResult, Tensor, Layer, NodeId, ParamStore};
+use crate::graph::dsl::GraphBuilder;
+use crate::layers::ParamBinding;
+use serde::{Serialize, Deserialize};
+
+/// 2D convolution with a per-channel bias: `output = conv2d(input, W) + b`.
+///
+/// # Shape
+///
+/// Input: `[batch, in_channels, H, W]` →
+/// Output: `[batch, out_channels, (H + 2p - k) / s + 1, (W + 2p - k) / s + 1]`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Conv2D {
+    pub in_channels: usize,
+    pub out_channels: usize,
+    pub kernel_size: usize,
+    pub stride: usize,
+    pub padding: usize,
+    /// `[out_channels, in_channels, kernel_size, kernel_size]`
+    pub weight: Tensor,
+    /// `[1, out_channels, 1, 1]`, broadcast over batch and spatial dims.
+    pub bias: Tensor,
+    #[serde(skip)]
+    pub binding: ParamBinding,
+}
+
+impl Conv2D {
+    /// Creates a convolution with He-uniform initialization.
+    ///
+    /// Weights are drawn from `Uniform(-limit, limit)` with
+    /// `limit = sqrt(6 / fan_in)` and `fan_in = in_channels * k * k`,
+    /// which suits the ReLU that usually follows a convolution.
+    pub fn new(in_channels: usize, out_channels: usize, kernel_size: usize, stride: usize, padding: usize) -> Self {
+        let mut weight = Tensor::new_random(&[out_channels, in_channels, kernel_size, kernel_size]);
+        let fan_in = (in_channels * kernel_size * kernel_size).max(1) as f32;
+        weight.scale_inplace((6.0 / fan_in).sqrt()).unwrap();
+
+        Self {
+            in_channels,
+            out_channels,
+            kernel_size,
+            stride,
+            padding,
+            weight,
+            bias: Tensor::new_zeros(&[1, out_channels, 1, 1]),
+            binding: ParamBinding::default(),
+        }
+    }
+}
+
+#[typetag::serde]
+impl Layer for Conv2D {
+    fn forward(&mut self, input: NodeId, graph: &mut GraphBuilder) -> NodeId {
+        let params = self.binding.nodes(graph, "conv2d", &[("weight", &self.weight), ("bias", &self.bias)]);
+        let conv = graph.conv2d(input, params[0], self.stride, self.padding);
+        graph.add(conv, params[1])
+    }
+
+    fn pull_params(&mut self, params: &ParamStore) -> GPResult<()> {
+        self.binding.pull(params, &mut [&mut self.weight, &mut self.bias])
+    }
+
+    fn push_params(&self, params: &mut ParamStore) -> GPResult<()> {
+        self.binding.push(params, &[&self.weight, &self.bias])
+    }
+}
@@ -1,7 +1,8 @@
-use crate::{Tensor, Layer, NodeId};
+use crate::{GPResult, Tensor, Layer, NodeId, ParamStore};
 use crate::graph::dsl::GraphBuilder;
 use serde::{Serialize, Deserialize};
 use crate::graph::OpType;
+use crate::layers::{ParamBinding, RecurrentCell};
 
 /// A Gated Recurrent Unit (GRU) Cell.
 /// Provides temporal memory while mitigating the vanishing gradient problem.
@@ -29,8 +30,19 @@ pub struct GRUCell {
     // Internal tracker for the state node during forward pass
     #[serde(skip)]
     pub(crate) state_node_id: Option<NodeId>,
+
+    /// Weights registered by `Layer::forward`; reused when called again in the same graph.
+    #[serde(skip)]
+    pub binding: ParamBinding,
 }
 
+/// Parameter names (the field names), in registration order.
+const PARAM_NAMES: [&str; 12] = [
+    "wz_ih", "bz_ih", "wz_hh", "bz_hh",
+    "wr_ih", "br_ih", "wr_hh", "br_hh",
+    "wn_ih", "bn_ih", "wn_hh", "bn_hh",
+];
+
 impl GRUCell {
     pub fn new(input_size: usize, hidden_size: usize) -> Self {
         let init_w = |i, h| Tensor::new_random(&[i, h]);
@@ -51,63 +63,97 @@ impl GRUCell {
             
             hidden_state: None,
             state_node_id: None,
+            binding: ParamBinding::default(),
         }
     }
 
     pub fn reset_memory(&mut self) {
         self.hidden_state = None;
     }
+
+    /// Weight tensors in parameter registration order (see [`PARAM_NAMES`]).
+    fn param_tensors(&self) -> [&Tensor; 12] {
+        [
+            &self.wz_ih, &self.bz_ih, &self.wz_hh, &self.bz_hh,
+            &self.wr_ih, &self.br_ih, &self.wr_hh, &self.br_hh,
+            &self.wn_ih, &self.bn_ih, &self.wn_hh, &self.bn_hh,
+        ]
+    }
+
+    fn param_tensors_mut(&mut self) -> [&mut Tensor; 12] {
+        [
+            &mut self.wz_ih, &mut self.bz_ih, &mut self.wz_hh, &mut self.bz_hh,
+            &mut self.wr_ih, &mut self.br_ih, &mut self.wr_hh, &mut self.br_hh,
+            &mut self.wn_ih, &mut self.bn_ih, &mut self.wn_hh, &mut self.bn_hh,
+        ]
+    }
 }
 
-#[typetag::serde]
-impl Layer for GRUCell {
-    fn forward(&mut self, input: NodeId, graph: &mut GraphBuilder) -> NodeId {
-        let h_prev_tensor = match &self.hidden_state {
-            Some(t) => t.clone(),
-            None => Tensor::new_zeros(&[1, self.hidden_size]),
-        };
-        let h_prev = graph.val(h_prev_tensor);
+impl RecurrentCell for GRUCell {
+    fn input_size(&self) -> usize {
+        self.input_size
+    }
+
+    fn hidden_size(&self) -> usize {
+        self.hidden_size
+    }
+
+    fn register_params(&self, graph: &mut GraphBuilder) -> Vec<NodeId> {
+        let named: Vec<(&str, &Tensor)> = PARAM_NAMES.into_iter().zip(self.param_tensors()).collect();
+        graph.layer_params("gru", &named)
+    }
+
+    fn binding_mut(&mut self) -> &mut ParamBinding {
+        &mut self.binding
+    }
+
+    fn step(&self, graph: &mut GraphBuilder, params: &[NodeId], input: NodeId, states: &[NodeId]) -> Vec<NodeId> {
+        let h_prev = states[0];
+        let [wz_ih, bz_ih, wz_hh, bz_hh, wr_ih, br_ih, wr_hh, br_hh, wn_ih, bn_ih, wn_hh, bn_hh] =
+            params[..12].try_into().expect("GRUCell expects 12 parameter nodes");
 
         // --- Update Gate (z_t) ---
-        let wz_ih = graph.param(self.wz_ih.clone()); let bz_ih = graph.param(self.bz_ih.clone());
         let z_ih_proj = graph.linear(input, wz_ih, bz_ih);
-        let wz_hh = graph.param(self.wz_hh.clone()); let bz_hh = graph.param(self.bz_hh.clone());
         let z_hh_proj = graph.linear(h_prev, wz_hh, bz_hh);
         let z_sum = graph.node(OpType::Add, vec![z_ih_proj, z_hh_proj]);
         let z_t = graph.node(OpType::Sigmoid, vec![z_sum]);
 
         // --- Reset Gate (r_t) ---
-        let wr_ih = graph.param(self.wr_ih.clone()); let br_ih = graph.param(self.br_ih.clone());
         let r_ih_proj = graph.linear(input, wr_ih, br_ih);
-        let wr_hh = graph.param(self.wr_hh.clone()); let br_hh = graph.param(self.br_hh.clone());
         let r_hh_proj = graph.linear(h_prev, wr_hh, br_hh);
         let r_sum = graph.node(OpType::Add, vec![r_ih_proj, r_hh_proj]);
         let r_t = graph.node(OpType::Sigmoid, vec![r_sum]);
 
         // --- New Memory (n_t / h_tilde) ---
-        let wn_ih = graph.param(self.wn_ih.clone()); let bn_ih = graph.param(self.bn_ih.clone());
         let n_ih_proj = graph.linear(input, wn_ih, bn_ih);
-        let wn_hh = graph.param(self.wn_hh.clone()); let bn_hh = graph.param(self.bn_hh.clone());
-        
         let r_times_h = graph.node(OpType::Mul, vec![r_t, h_prev]);
         let n_hh_proj = graph.linear(r_times_h, wn_hh, bn_hh);
-        
+
         let n_sum = graph.node(OpType::Add, vec![n_ih_proj, n_hh_proj]);
         let n_t = graph.node(OpType::Tanh, vec![n_sum]);
 
-        // graph doesn't have a 1-x op directly. We can multiply z_t by -1 and add 1
         let ones_tensor = Tensor::new_ones(&[1, self.hidden_size]);
         let ones = graph.val(ones_tensor);
+        let one_minus_z = graph.sub(ones, z_t);
 
-        let neg_one_tensor = Tensor::from_elem(&[1, 1], -1.0);
-        let neg_one = graph.val(neg_one_tensor);
-        let neg_z_t = graph.node(OpType::Mul, vec![z_t, neg_one]);
-        let one_minus_z = graph.node(OpType::Add, vec![ones, neg_z_t]);
-        
         let part1 = graph.node(OpType::Mul, vec![one_minus_z, n_t]);
         let part2 = graph.node(OpType::Mul, vec![z_t, h_prev]);
-        
-        let h_t = graph.node(OpType::Add, vec![part1, part2]);
+
+        vec![graph.node(OpType::Add, vec![part1, part2])]
+    }
+}
+
+#[typetag::serde]
+impl Layer for GRUCell {
+    fn forward(&mut self, input: NodeId, graph: &mut GraphBuilder) -> NodeId {
+        let h_prev_tensor = match &self.hidden_state {
+            Some(t) => t.clone(),
+            None => Tensor::new_zeros(&[1, self.hidden_size]),
+        };
+        let h_prev = graph.val(h_prev_tensor);
+
+        let params = self.bind_params(graph);
+        let h_t = self.step(graph, &params, input, &[h_prev])[0];
         self.state_node_id = Some(h_t);
         h_t
     }
@@ -123,4 +169,14 @@ impl Layer for GRUCell {
     fn reset_state(&mut self) {
         self.reset_memory();
     }
+
+    fn pull_params(&mut self, params: &ParamStore) -> GPResult<()> {
+        // The binding is cloned so the tensors can be borrowed mutably.
+        let binding = self.binding.clone();
+        binding.pull(params, &mut self.param_tensors_mut())
+    }
+
+    fn push_params(&self, params: &mut ParamStore) -> GPResult<()> {
+        self.binding.push(params, &self.param_tensors())
+    }
 }
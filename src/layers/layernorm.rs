@@ -0,0 +1,59 @@
+//! Layer Normalization layer.
+//!
+//! Normalizes each sample over its feature dimension, then applies a
+//! learnable affine transform: `output = gamma * normalized + beta`.
+//!
+//! Unlike [`BatchNorm`](super::BatchNorm), statistics never cross the batch
+//! dimension, so the layer behaves identically at batch size 1 — the regime
+//! WASM agents run in — and needs no running statistics for inference.
+
+use crate::{GPResult, Tensor, Layer, NodeId, ParamStore};
+use crate::graph::dsl::GraphBuilder;
+use crate::layers::ParamBinding;
+use serde::{Serialize, Deserialize};
+
+/// Layer Normalization: `y = gamma * (x - mean) / sqrt(var + eps) + beta`.
+///
+/// - `gamma` (scale) and `beta` (shift) are learnable parameters.
+/// - Statistics computed per sample across the last dimension.
+///
+/// # Shape
+///
+/// Input: `[batch, features]` → Output: `[batch, features]`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LayerNorm {
+    pub num_features: usize,
+    pub epsilon: f32,
+    pub gamma: Tensor,
+    pub beta: Tensor,
+    #[serde(skip)]
+    pub binding: ParamBinding,
+}
+
+impl LayerNorm {
+    pub fn new(num_features: usize) -> Self {
+        Self {
+            num_features,
+            epsilon: 1e-5,
+            gamma: Tensor::new_ones(&[1, num_features]),
+            beta: Tensor::new_zeros(&[1, num_features]),
+            binding: ParamBinding::default(),
+        }
+    }
+}
+
+#[typetag::serde]
+impl Layer for LayerNorm {
+    fn forward(&mut self, input: NodeId, graph: &mut GraphBuilder) -> NodeId {
+        let params = self.binding.nodes(graph, "layernorm", &[("gamma", &self.gamma), ("beta", &self.beta)]);
+        graph.layer_norm(input, params[0], params[1], self.epsilon)
+    }
+
+    fn pull_params(&mut self, params: &ParamStore) -> GPResult<()> {
+        self.binding.pull(params, &mut [&mut self.gamma, &mut self.beta])
+    }
+
+    fn push_params(&self, params: &mut ParamStore) -> GPResult<()> {
+        self.binding.push(params, &[&self.gamma, &self.beta])
+    }
+}
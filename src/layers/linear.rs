@@ -1,11 +1,15 @@
-use crate::{Tensor, Layer, NodeId};
+use crate::{GPResult, Tensor, Layer, NodeId, ParamStore};
 use crate::graph::dsl::GraphBuilder;
+use crate::layers::ParamBinding;
 use serde::{Serialize, Deserialize};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Linear {
     pub weights: Tensor,
     pub biases: Tensor,
+    /// Parameters this layer registered; reused when called again in the same graph.
+    #[serde(skip)]
+    pub binding: ParamBinding,
 }
 
 impl Linear {
@@ -21,15 +25,22 @@ impl Linear {
         weights.scale_inplace(scale).unwrap();
         let biases = Tensor::new_zeros(&[1, output_dim]);
 
-        Self { weights, biases }
+        Self { weights, biases, binding: ParamBinding::default() }
     }
 }
 
 #[typetag::serde]
 impl Layer for Linear {
     fn forward(&mut self, input: NodeId, graph: &mut GraphBuilder) -> NodeId {
-        let w = graph.param(self.weights.clone());
-        let b = graph.param(self.biases.clone());
-        graph.linear(input, w, b)
+        let params = self.binding.nodes(graph, "linear", &[("weight", &self.weights), ("bias", &self.biases)]);
+        graph.linear(input, params[0], params[1])
+    }
+
+    fn pull_params(&mut self, params: &ParamStore) -> GPResult<()> {
+        self.binding.pull(params, &mut [&mut self.weights, &mut self.biases])
+    }
+
+    fn push_params(&self, params: &mut ParamStore) -> GPResult<()> {
+        self.binding.push(params, &[&self.weights, &self.biases])
     }
 }
@@ -0,0 +1,224 @@
+use crate::{GPResult, Tensor, Layer, NodeId, ParamStore};
+use crate::graph::dsl::GraphBuilder;
+use serde::{Serialize, Deserialize};
+use crate::graph::OpType;
+use crate::layers::{ParamBinding, RecurrentCell};
+
+/// A Long Short-Term Memory (LSTM) Cell.
+///
+/// Carries two recurrent states: the hidden state `h` (also the layer output)
+/// and the cell state `c`, which gives gradients an additive path through time.
+///
+/// ```text
+/// i_t = sigmoid(x W_i + h W_hi + b_i)      input gate
+/// f_t = sigmoid(x W_f + h W_hf + b_f)      forget gate
+/// g_t = tanh(x W_g + h W_hg + b_g)         cell candidate
+/// o_t = sigmoid(x W_o + h W_ho + b_o)      output gate
+/// c_t = f_t * c_{t-1} + i_t * g_t
+/// h_t = o_t * tanh(c_t)
+/// ```
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LSTMCell {
+    pub hidden_size: usize,
+    pub input_size: usize,
+
+    // Input Gate (i)
+    pub wi_ih: Tensor, pub bi_ih: Tensor,
+    pub wi_hh: Tensor, pub bi_hh: Tensor,
+
+    // Forget Gate (f)
+    pub wf_ih: Tensor, pub bf_ih: Tensor,
+    pub wf_hh: Tensor, pub bf_hh: Tensor,
+
+    // Cell Candidate (g)
+    pub wg_ih: Tensor, pub bg_ih: Tensor,
+    pub wg_hh: Tensor, pub bg_hh: Tensor,
+
+    // Output Gate (o)
+    pub wo_ih: Tensor, pub bo_ih: Tensor,
+    pub wo_hh: Tensor, pub bo_hh: Tensor,
+
+    // Persistent state
+    #[serde(skip)]
+    pub hidden_state: Option<Tensor>,
+    #[serde(skip)]
+    pub cell_state: Option<Tensor>,
+
+    // Internal trackers for the state nodes during forward pass
+    #[serde(skip)]
+    pub(crate) state_node_id: Option<NodeId>,
+    #[serde(skip)]
+    pub(crate) cell_node_id: Option<NodeId>,
+
+    /// Weights registered by `Layer::forward`; reused when called again in the same graph.
+    #[serde(skip)]
+    pub binding: ParamBinding,
+}
+
+/// Parameter names (the field names), in registration order.
+const PARAM_NAMES: [&str; 16] = [
+    "wi_ih", "bi_ih", "wi_hh", "bi_hh",
+    "wf_ih", "bf_ih", "wf_hh", "bf_hh",
+    "wg_ih", "bg_ih", "wg_hh", "bg_hh",
+    "wo_ih", "bo_ih", "wo_hh", "bo_hh",
+];
+
+impl LSTMCell {
+    pub fn new(input_size: usize, hidden_size: usize) -> Self {
+        let init_w = |i, h| Tensor::new_random(&[i, h]);
+        let init_b = |h| Tensor::new_zeros(&[1, h]);
+
+        Self {
+            hidden_size,
+            input_size,
+
+            wi_ih: init_w(input_size, hidden_size), bi_ih: init_b(hidden_size),
+            wi_hh: init_w(hidden_size, hidden_size), bi_hh: init_b(hidden_size),
+
+            // Forget bias starts at 1 so the cell remembers by default.
+            wf_ih: init_w(input_size, hidden_size), bf_ih: Tensor::new_ones(&[1, hidden_size]),
+            wf_hh: init_w(hidden_size, hidden_size), bf_hh: init_b(hidden_size),
+
+            wg_ih: init_w(input_size, hidden_size), bg_ih: init_b(hidden_size),
+            wg_hh: init_w(hidden_size, hidden_size), bg_hh: init_b(hidden_size),
+
+            wo_ih: init_w(input_size, hidden_size), bo_ih: init_b(hidden_size),
+            wo_hh: init_w(hidden_size, hidden_size), bo_hh: init_b(hidden_size),
+
+            hidden_state: None,
+            cell_state: None,
+            state_node_id: None,
+            cell_node_id: None,
+            binding: ParamBinding::default(),
+        }
+    }
+
+    pub fn reset_memory(&mut self) {
+        self.hidden_state = None;
+        self.cell_state = None;
+    }
+
+    /// Weight tensors in parameter registration order (see [`PARAM_NAMES`]).
+    fn param_tensors(&self) -> [&Tensor; 16] {
+        [
+            &self.wi_ih, &self.bi_ih, &self.wi_hh, &self.bi_hh,
+            &self.wf_ih, &self.bf_ih, &self.wf_hh, &self.bf_hh,
+            &self.wg_ih, &self.bg_ih, &self.wg_hh, &self.bg_hh,
+            &self.wo_ih, &self.bo_ih, &self.wo_hh, &self.bo_hh,
+        ]
+    }
+
+    fn param_tensors_mut(&mut self) -> [&mut Tensor; 16] {
+        [
+            &mut self.wi_ih, &mut self.bi_ih, &mut self.wi_hh, &mut self.bi_hh,
+            &mut self.wf_ih, &mut self.bf_ih, &mut self.wf_hh, &mut self.bf_hh,
+            &mut self.wg_ih, &mut self.bg_ih, &mut self.wg_hh, &mut self.bg_hh,
+            &mut self.wo_ih, &mut self.bo_ih, &mut self.wo_hh, &mut self.bo_hh,
+        ]
+    }
+}
+
+/// Builds `x W_ih + b_ih + h W_hh + b_hh` for one gate from its four parameter nodes.
+fn gate_preactivation(graph: &mut GraphBuilder, input: NodeId, h_prev: NodeId, params: &[NodeId]) -> NodeId {
+    let ih_proj = graph.linear(input, params[0], params[1]);
+    let hh_proj = graph.linear(h_prev, params[2], params[3]);
+    graph.add(ih_proj, hh_proj)
+}
+
+impl RecurrentCell for LSTMCell {
+    fn input_size(&self) -> usize {
+        self.input_size
+    }
+
+    fn hidden_size(&self) -> usize {
+        self.hidden_size
+    }
+
+    fn num_states(&self) -> usize {
+        2
+    }
+
+    fn register_params(&self, graph: &mut GraphBuilder) -> Vec<NodeId> {
+        let named: Vec<(&str, &Tensor)> = PARAM_NAMES.into_iter().zip(self.param_tensors()).collect();
+        graph.layer_params("lstm", &named)
+    }
+
+    fn binding_mut(&mut self) -> &mut ParamBinding {
+        &mut self.binding
+    }
+
+    fn step(&self, graph: &mut GraphBuilder, params: &[NodeId], input: NodeId, states: &[NodeId]) -> Vec<NodeId> {
+        let (h_prev, c_prev) = (states[0], states[1]);
+
+        // --- Gates ---
+        let i_sum = gate_preactivation(graph, input, h_prev, &params[0..4]);
+        let i_t = graph.node(OpType::Sigmoid, vec![i_sum]);
+
+        let f_sum = gate_preactivation(graph, input, h_prev, &params[4..8]);
+        let f_t = graph.node(OpType::Sigmoid, vec![f_sum]);
+
+        let g_sum = gate_preactivation(graph, input, h_prev, &params[8..12]);
+        let g_t = graph.node(OpType::Tanh, vec![g_sum]);
+
+        let o_sum = gate_preactivation(graph, input, h_prev, &params[12..16]);
+        let o_t = graph.node(OpType::Sigmoid, vec![o_sum]);
+
+        // --- Cell State (c_t) ---
+        let keep = graph.node(OpType::Mul, vec![f_t, c_prev]);
+        let write = graph.node(OpType::Mul, vec![i_t, g_t]);
+        let c_t = graph.node(OpType::Add, vec![keep, write]);
+
+        // --- Hidden State (h_t) ---
+        let c_act = graph.node(OpType::Tanh, vec![c_t]);
+        let h_t = graph.node(OpType::Mul, vec![o_t, c_act]);
+
+        vec![h_t, c_t]
+    }
+}
+
+#[typetag::serde]
+impl Layer for LSTMCell {
+    fn forward(&mut self, input: NodeId, graph: &mut GraphBuilder) -> NodeId {
+        let zeros = || Tensor::new_zeros(&[1, self.hidden_size]);
+        let h_prev = graph.val(self.hidden_state.clone().unwrap_or_else(zeros));
+        let c_prev = graph.val(self.cell_state.clone().unwrap_or_else(zeros));
+
+        let params = self.bind_params(graph);
+        let states = self.step(graph, &params, input, &[h_prev, c_prev]);
+
+        self.state_node_id = Some(states[0]);
+        self.cell_node_id = Some(states[1]);
+        states[0]
+    }
+    fn state_node(&self) -> Option<NodeId> {
+        self.state_node_id
+    }
+
+    fn update_state(&mut self, tensor: Tensor) {
+        self.hidden_state = Some(tensor);
+    }
+
+    fn state_nodes(&self) -> Vec<NodeId> {
+        self.state_node_id.into_iter().chain(self.cell_node_id).collect()
+    }
+
+    fn update_states(&mut self, tensors: Vec<Tensor>) {
+        let mut tensors = tensors.into_iter();
+        self.hidden_state = tensors.next();
+        self.cell_state = tensors.next();
+    }
+
+    fn reset_state(&mut self) {
+        self.reset_memory();
+    }
+
+    fn pull_params(&mut self, params: &ParamStore) -> GPResult<()> {
+        // The binding is cloned so the tensors can be borrowed mutably.
+        let binding = self.binding.clone();
+        binding.pull(params, &mut self.param_tensors_mut())
+    }
+
+    fn push_params(&self, params: &mut ParamStore) -> GPResult<()> {
+        self.binding.push(params, &self.param_tensors())
+    }
+}
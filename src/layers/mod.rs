@@ -1,11 +1,21 @@
+pub mod binding;
 pub mod linear;
 pub mod activation;
 pub mod rnn;
 pub mod gru;
+pub mod lstm;
+pub mod recurrent;
 pub mod batchnorm;
+pub mod conv;
+pub mod layernorm;
 
+pub use binding::ParamBinding;
 pub use linear::Linear;
 pub use activation::{Activation, ActivationType};
 pub use rnn::RNNCell;
 pub use gru::GRUCell;
+pub use lstm::LSTMCell;
+pub use recurrent::{RecurrentCell, Unrolled, unroll};
 pub use batchnorm::BatchNorm;
+pub use conv::Conv2D;
+pub use layernorm::LayerNorm;
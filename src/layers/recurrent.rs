@@ -0,0 +1,197 @@
+//! Backpropagation through time (BPTT) for recurrent cells.
+//!
+//! A recurrent [`Layer`](crate::Layer) builds a single timestep and feeds its
+//! previous state in as a constant, so gradients stop at the step boundary.
+//! [`unroll`] instead builds all `T` steps into one graph: the cell's weights
+//! are registered once and every step reuses the same parameter nodes (and
+//! therefore the same `ParamId`s), while each step's state is the previous
+//! step's output node. A single backward pass then accumulates gradients from
+//! every timestep into the shared parameters.
+//!
+//! The weights are bound like those of any layer (see
+//! [`ParamBinding`]): a cell already bound to the graph reuses its
+//! parameters, and after training [`Layer::pull_params`](crate::Layer::pull_params)
+//! copies them back into the cell.
+//!
+//! # Example
+//! ```ignore
+//! let mut cell = GRUCell::new(3, 8);
+//! let mut gb = GraphBuilder::new(&mut graph);
+//! let unrolled = unroll(&mut cell, &mut gb, seq_len, batch, None)?;
+//! let last = *unrolled.outputs.last().unwrap();
+//! let target = gb.val(y);
+//! let loss = gb.mse_loss(last, target);
+//!
+//! unrolled.feed(&mut graph, &sequence)?; // [seq_len, batch, 3]
+//! graph.execute(loss)?;
+//! graph.backward_scalar(loss)?;
+//! cell.pull_params(graph.params())?;
+//! ```
+
+use crate::graph::{Graph, dsl::GraphBuilder};
+use crate::layers::ParamBinding;
+use crate::{GPError, GPResult, NodeId, Tensor};
+
+/// A recurrent cell that can be stepped repeatedly with shared weights.
+pub trait RecurrentCell {
+    fn input_size(&self) -> usize;
+    fn hidden_size(&self) -> usize;
+
+    /// Number of state tensors carried between steps (2 for LSTM: hidden, cell).
+    fn num_states(&self) -> usize {
+        1
+    }
+
+    /// Registers the cell's weights in the graph, returning their nodes in a
+    /// cell-specific order understood by [`RecurrentCell::step`].
+    fn register_params(&self, graph: &mut GraphBuilder) -> Vec<NodeId>;
+
+    /// The binding recording which parameters the cell's weights live in.
+    fn binding_mut(&mut self) -> &mut ParamBinding;
+
+    /// The cell's weight nodes: reused if the cell is bound to this graph,
+    /// otherwise registered via [`register_params`](Self::register_params)
+    /// and recorded in its binding.
+    fn bind_params(&mut self, graph: &mut GraphBuilder) -> Vec<NodeId> {
+        // Moved out so `register_params` can borrow the cell
+        let mut binding = std::mem::take(self.binding_mut());
+        let nodes = binding.get_or_register(graph, |g| self.register_params(g));
+        *self.binding_mut() = binding;
+        nodes
+    }
+
+    /// Builds one timestep from `input` (`[batch, input_size]`) and the
+    /// previous `states` (each `[batch, hidden_size]`).
+    ///
+    /// Returns the new states; the first is the hidden state, which is also
+    /// the step's output.
+    fn step(&self, graph: &mut GraphBuilder, params: &[NodeId], input: NodeId, states: &[NodeId]) -> Vec<NodeId>;
+}
+
+/// Node handles for an unrolled sequence.
+#[derive(Debug, Clone)]
+pub struct Unrolled {
+    /// Shared weight nodes, in [`RecurrentCell::register_params`] order.
+    pub params: Vec<NodeId>,
+    /// Per-step input nodes, each `[batch, input_size]`.
+    pub inputs: Vec<NodeId>,
+    /// Per-step hidden state outputs, each `[batch, hidden_size]`.
+    pub outputs: Vec<NodeId>,
+    /// Initial state inputs (zeros until set via [`Unrolled::set_initial_states`]).
+    pub initial_states: Vec<NodeId>,
+    /// States after the last step.
+    pub final_states: Vec<NodeId>,
+}
+
+/// Unrolls `cell` over `steps` timesteps for a fixed `batch` size.
+///
+/// The cell's weights come from [`RecurrentCell::bind_params`], so they are
+/// recorded in (or reused from) the cell's binding.
+///
+/// With `truncation: Some(k)`, the states are passed through a
+/// `StopGradient` every `k` steps, so gradients flow back at most to the
+/// start of their `k`-step chunk (truncated BPTT). The forward values are
+/// unaffected.
+///
+/// Fails if `truncation` is `Some(0)`.
+pub fn unroll<C: RecurrentCell + ?Sized>(
+    cell: &mut C,
+    graph: &mut GraphBuilder,
+    steps: usize,
+    batch: usize,
+    truncation: Option<usize>,
+) -> GPResult<Unrolled> {
+    if truncation == Some(0) {
+        return Err(GPError::InferenceError("Truncation must be > 0".to_string()));
+    }
+    let params = cell.bind_params(graph);
+    let initial_states: Vec<NodeId> = (0..cell.num_states())
+        .map(|_| graph.placeholder(&[batch, cell.hidden_size()]))
+        .collect();
+
+    let mut states = initial_states.clone();
+    let mut inputs = Vec::with_capacity(steps);
+    let mut outputs = Vec::with_capacity(steps);
+    for t in 0..steps {
+        if let Some(k) = truncation {
+            if t > 0 && t.is_multiple_of(k) {
+                states = states.iter().map(|&s| graph.stop_gradient(s)).collect();
+            }
+        }
+        let x = graph.placeholder(&[batch, cell.input_size()]);
+        states = cell.step(graph, &params, x, &states);
+        inputs.push(x);
+        outputs.push(states[0]);
+    }
+
+    Ok(Unrolled { params, inputs, outputs, initial_states, final_states: states })
+}
+
+impl Unrolled {
+    /// Number of unrolled timesteps.
+    pub fn steps(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// Writes a `[steps, batch, input_size]` sequence into the per-step inputs.
+    pub fn feed(&self, graph: &mut Graph, sequence: &Tensor) -> GPResult<()> {
+        let shape = sequence.shape();
+        let expected = match self.inputs.first() {
+            Some(&first) => {
+                let step_shape = input_shape(graph, first)?;
+                vec![self.steps(), step_shape[0], step_shape[1]]
+            }
+            None => vec![0],
+        };
+        if shape != expected.as_slice() {
+            return Err(GPError::IncompatibleShapes {
+                expected: expected.clone(),
+                found: shape.to_vec(),
+                exp_len: expected.iter().product(),
+                found_len: sequence.len(),
+            });
+        }
+
+        let data = sequence.as_slice()?;
+        let step_len = expected[1] * expected[2];
+        for (&node, chunk) in self.inputs.iter().zip(data.chunks(step_len)) {
+            graph.feed(node, &Tensor::from_shape_vec(&expected[1..], chunk.to_vec())?)?;
+        }
+        Ok(())
+    }
+
+    /// Sets the initial states (e.g. the final states of a previous chunk).
+    pub fn set_initial_states(&self, graph: &mut Graph, states: &[Tensor]) -> GPResult<()> {
+        if states.len() != self.initial_states.len() {
+            return Err(GPError::InferenceError(format!(
+                "Unrolled: expected {} initial states, got {}",
+                self.initial_states.len(), states.len()
+            )));
+        }
+        for (&node, state) in self.initial_states.iter().zip(states) {
+            graph.feed(node, state)?;
+        }
+        Ok(())
+    }
+
+    /// Feeds `sequence` and runs the forward pass over every step.
+    ///
+    /// Returns the per-step hidden states.
+    pub fn run(&self, graph: &mut Graph, sequence: &Tensor) -> GPResult<Vec<Tensor>> {
+        self.feed(graph, sequence)?;
+        for &state in &self.final_states {
+            graph.execute(state)?;
+        }
+        self.outputs.iter()
+            .map(|id| graph.values().get(id.0).cloned().flatten().ok_or_else(|| {
+                GPError::InferenceError(format!("Unrolled: output {:?} not computed", id))
+            }))
+            .collect()
+    }
+}
+
+fn input_shape(graph: &Graph, node: NodeId) -> GPResult<Vec<usize>> {
+    graph.input_value(node)
+        .map(|t| t.shape().to_vec())
+        .ok_or_else(|| GPError::InferenceError(format!("Unrolled: {:?} is not an input node", node)))
+}
@@ -1,5 +1,6 @@
-use crate::{Tensor, Layer, NodeId};
+use crate::{GPResult, Tensor, Layer, NodeId, ParamStore};
 use crate::graph::dsl::GraphBuilder;
+use crate::layers::{ParamBinding, RecurrentCell};
 use serde::{Serialize, Deserialize};
 
 /// A standard Recurrent Neural Network (RNN) Cell.
@@ -22,6 +23,10 @@ pub struct RNNCell {
     // Internal tracker for the state node during forward pass
     #[serde(skip)]
     pub(crate) state_node_id: Option<NodeId>,
+
+    /// Weights registered by `Layer::forward`; reused when called again in the same graph.
+    #[serde(skip)]
+    pub binding: ParamBinding,
 }
 
 impl RNNCell {
@@ -41,6 +46,7 @@ impl RNNCell {
             bias_hh,
             hidden_state: None,
             state_node_id: None,
+            binding: ParamBinding::default(),
         }
     }
 
@@ -50,40 +56,65 @@ impl RNNCell {
     }
 }
 
-#[typetag::serde]
-impl Layer for RNNCell {
-    fn forward(&mut self, input: NodeId, graph: &mut GraphBuilder) -> NodeId {
-        let w_ih = graph.param(self.weight_ih.clone());
-        let b_ih = graph.param(self.bias_ih.clone());
-        
+impl RecurrentCell for RNNCell {
+    fn input_size(&self) -> usize {
+        self.input_size
+    }
+
+    fn hidden_size(&self) -> usize {
+        self.hidden_size
+    }
+
+    fn register_params(&self, graph: &mut GraphBuilder) -> Vec<NodeId> {
+        graph.layer_params("rnn", &[
+            ("weight_ih", &self.weight_ih),
+            ("bias_ih", &self.bias_ih),
+            ("weight_hh", &self.weight_hh),
+            ("bias_hh", &self.bias_hh),
+        ])
+    }
+
+    fn binding_mut(&mut self) -> &mut ParamBinding {
+        &mut self.binding
+    }
+
+    fn step(&self, graph: &mut GraphBuilder, params: &[NodeId], input: NodeId, states: &[NodeId]) -> Vec<NodeId> {
+        let (w_ih, b_ih, w_hh, b_hh) = (params[0], params[1], params[2], params[3]);
+
         // 1. Input transformation: x_t * W_ih + b_ih
         let ih_proj = graph.linear(input, w_ih, b_ih);
-        
+
         // 2. Hidden transformation: W_hh * h_{t-1} + b_hh
-        let w_hh = graph.param(self.weight_hh.clone());
-        let b_hh = graph.param(self.bias_hh.clone());
-        
+        let hh_proj = graph.linear(states[0], w_hh, b_hh);
+
+        // 3. Combine: ih_proj + hh_proj
+        let combined = graph.add(ih_proj, hh_proj);
+
+        // 4. Activation: tanh(combined)
+        vec![graph.tanh(combined)]
+    }
+}
+
+#[typetag::serde]
+impl Layer for RNNCell {
+    fn forward(&mut self, input: NodeId, graph: &mut GraphBuilder) -> NodeId {
+        let params = self.bind_params(graph);
+
         let h_prev_tensor = match &self.hidden_state {
             Some(t) => t.clone(),
             None => Tensor::new_zeros(&[1, self.hidden_size]),
         };
-        
-        // We inject the previous hidden state as a "Val" (constant for this step)
+
+        // We inject the previous hidden state as a "Val" (constant for this step).
+        // Use `layers::unroll` to backpropagate across timesteps instead.
         let h_prev_node = graph.val(h_prev_tensor);
-        let hh_proj = graph.linear(h_prev_node, w_hh, b_hh);
-        
-        // 3. Combine: ih_proj + hh_proj
-        use crate::graph::OpType;
-        let combined = graph.node(OpType::Add, vec![ih_proj, hh_proj]);
-        
-        // 4. Activation: tanh(combined)
-        let h_t = graph.node(OpType::Tanh, vec![combined]);
-        
-        // We return the output of the cell (h_t). 
-        // The calling code (e.g., `NeuralBrain::compute`) is responsible for reading 
-        // the evaluated tensor from h_t and writing it back to `self.hidden_state` 
+        let h_t = self.step(graph, &params, input, &[h_prev_node])[0];
+
+        // We return the output of the cell (h_t).
+        // The calling code (e.g., `NeuralBrain::compute`) is responsible for reading
+        // the evaluated tensor from h_t and writing it back to `self.hidden_state`
         // for the next frame. We cannot do this here since this is just the graph building phase.
-        
+
         self.state_node_id = Some(h_t);
         h_t
     }
@@ -99,4 +130,14 @@ impl Layer for RNNCell {
     fn reset_state(&mut self) {
         self.reset_memory();
     }
+
+    fn pull_params(&mut self, params: &ParamStore) -> GPResult<()> {
+        self.binding.pull(params, &mut [
+            &mut self.weight_ih, &mut self.bias_ih, &mut self.weight_hh, &mut self.bias_hh,
+        ])
+    }
+
+    fn push_params(&self, params: &mut ParamStore) -> GPResult<()> {
+        self.binding.push(params, &[&self.weight_ih, &self.bias_ih, &self.weight_hh, &self.bias_hh])
+    }
 }
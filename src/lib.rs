@@ -7,13 +7,15 @@ pub mod types;
 pub mod loss;
 pub mod optim;
 pub mod params;
+pub mod safetensors;
 pub mod network_def;
 pub mod scheduler;
+pub mod checkpoint;
 
 pub use tensor::Tensor;
 pub use errors::{GPError, GPResult};
 pub use types::{NodeId, Shape, Device};
-pub use params::{ParamStore, ParamId};
+pub use params::{ParamStore, ParamId, StateDict, LoadReport};
 pub use graph::{Architecture, ExecutionEngine};
 
 /// Base trait for all neural network layers.
@@ -27,9 +29,33 @@ pub trait Layer: Send + Sync + std::fmt::Debug {
     
     /// Updates the internal state with the evaluated tensor.
     fn update_state(&mut self, _tensor: Tensor) {}
+
+    /// All state nodes, for layers carrying more than one state tensor
+    /// (e.g. LSTM hidden and cell state). Defaults to [`Layer::state_node`].
+    fn state_nodes(&self) -> Vec<NodeId> {
+        self.state_node().into_iter().collect()
+    }
+
+    /// Updates every state from tensors evaluated at [`Layer::state_nodes`], in order.
+    fn update_states(&mut self, tensors: Vec<Tensor>) {
+        if let Some(tensor) = tensors.into_iter().next() {
+            self.update_state(tensor);
+        }
+    }
     
     /// Resets the internal state (e.g. at the start of a new episode).
     fn reset_state(&mut self) {}
+
+    /// Copies the current values of the parameters this layer registered
+    /// (see [`layers::ParamBinding`]) back into its fields, so serializing the
+    /// layer after training saves the trained weights.
+    ///
+    /// Returns [`GPError::UnboundParams`] if the layer is not bound to
+    /// `params`. Layers without parameters have nothing to copy and succeed.
+    fn pull_params(&mut self, _params: &ParamStore) -> GPResult<()> { Ok(()) }
+
+    /// Writes the layer's fields into the parameters it is bound to.
+    fn push_params(&self, _params: &mut ParamStore) -> GPResult<()> { Ok(()) }
 }
 
 #[cfg(test)]
@@ -2,7 +2,15 @@
 //!
 //! [`NetworkDef`] describes a network architecture as a list of [`LayerDef`]s
 //! and connections between them. It is purely declarative — no tensors are
-//! allocated, no graph is built. This enables:
+//! allocated, no graph is built. Networks come in two forms:
+//!
+//! - **Sequential**: `layers` is a plain stack from one input to one output.
+//! - **Graph**: named `inputs`, `nodes` that each have an id and list the ids
+//!   they read from, and `outputs`. Merge layers (Add, Multiply, Concat)
+//!   combine branches, so residual blocks, skip connections and multi-input
+//!   models can be declared.
+//!
+//! This enables:
 //!
 //! - **Serialization**: Save/load architectures as JSON before training
 //! - **Validation**: Check for errors (missing connections, size mismatches)
@@ -21,13 +29,14 @@
 //! `NetworkDef` replaces the ad-hoc `WasmArchitecture` JSON format in the
 //! WASM bridge with a canonical, core-level representation.
 
+use std::collections::{HashMap, HashSet};
 use serde::{Serialize, Deserialize};
-use crate::{GPError, GPResult, Tensor};
-use crate::layers::{Linear, Activation, RNNCell, GRUCell, BatchNorm};
+use crate::{GPError, GPResult, NodeId, Tensor};
+use crate::layers::{Linear, Activation, RNNCell, GRUCell, LSTMCell, BatchNorm, LayerNorm, Conv2D};
 
 // Re-export ActivationType as the canonical activation enum for this module.
 pub use crate::layers::ActivationType;
-use crate::graph::{Graph, dsl::GraphBuilder};
+use crate::graph::{Graph, dsl::GraphBuilder, ops};
 use crate::backend::Backend;
 use crate::Layer;
 
@@ -60,6 +69,11 @@ pub enum LayerDef {
         input_size: usize,
         hidden_size: usize,
     },
+    /// Long Short-Term Memory: gated cell with separate hidden and cell state.
+    Lstm {
+        input_size: usize,
+        hidden_size: usize,
+    },
     /// Dropout: zeroes random elements during training (identity during inference).
     Dropout {
         rate: f32,
@@ -68,18 +82,165 @@ pub enum LayerDef {
     BatchNorm {
         num_features: usize,
     },
+    /// Layer Normalization: per-sample normalization with learnable affine transform.
+    LayerNorm {
+        num_features: usize,
+    },
+    /// 2D convolution (with bias) over `[channels, height, width]` inputs.
+    Conv2D {
+        in_channels: usize,
+        out_channels: usize,
+        kernel_size: usize,
+        #[serde(default = "default_stride")]
+        stride: usize,
+        #[serde(default)]
+        padding: usize,
+    },
+    /// 2D max pooling over `[channels, height, width]` inputs.
+    MaxPool2D {
+        kernel_size: usize,
+        stride: usize,
+    },
+    /// Flattens the per-sample shape to `[features]`, e.g. between a
+    /// convolution stack and a Linear head.
+    Flatten,
+    /// Reshapes the per-sample shape (batch dimension excluded).
+    Reshape {
+        shape: Vec<usize>,
+    },
+    /// Element-wise sum of two or more inputs with identical shapes,
+    /// e.g. a residual connection.
+    Add,
+    /// Element-wise product of two or more inputs with identical shapes.
+    Multiply,
+    /// Concatenates two or more inputs along a per-sample `axis` (batch
+    /// excluded; 0 is the feature axis of flat inputs).
+    Concat {
+        #[serde(default)]
+        axis: usize,
+    },
 }
 
+fn default_stride() -> usize { 1 }
+
 impl LayerDef {
     /// Returns the output dimension of this layer given the input dimension.
     ///
     /// For layers that don't change dimension (e.g., Activation), returns `input_dim`.
+    /// The output size of Conv2D, MaxPool2D and Concat depends on the full
+    /// input shape(s), which a flat dimension does not carry; they return
+    /// `input_dim` too. Use [`output_shape`](Self::output_shape) for those.
     pub fn output_dim(&self, input_dim: usize) -> usize {
         match self {
             LayerDef::Linear { out_features, .. } => *out_features,
-            LayerDef::Activation { .. } | LayerDef::Dropout { .. } | LayerDef::BatchNorm { .. } => input_dim,
+            LayerDef::Activation { .. } | LayerDef::Dropout { .. } | LayerDef::BatchNorm { .. }
+            | LayerDef::LayerNorm { .. } | LayerDef::Flatten | LayerDef::Add | LayerDef::Multiply => input_dim,
             LayerDef::Rnn { hidden_size, .. } => *hidden_size,
             LayerDef::Gru { hidden_size, .. } => *hidden_size,
+            LayerDef::Lstm { hidden_size, .. } => *hidden_size,
+            LayerDef::Reshape { shape } => shape.iter().product(),
+            LayerDef::Conv2D { .. } | LayerDef::MaxPool2D { .. } | LayerDef::Concat { .. } => input_dim,
+        }
+    }
+
+    /// Returns true for layers that combine several inputs (Add, Multiply, Concat).
+    pub fn is_merge(&self) -> bool {
+        matches!(self, LayerDef::Add | LayerDef::Multiply | LayerDef::Concat { .. })
+    }
+
+    /// Returns the per-sample output shape (batch dimension excluded) for a
+    /// per-sample input shape, e.g. `[features]` or `[channels, height, width]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layer cannot accept `input`.
+    pub fn output_shape(&self, input: &[usize]) -> GPResult<Vec<usize>> {
+        self.infer_shape(input)
+            .map_err(|msg| GPError::InferenceError(format!("LayerDef {}", msg)))
+    }
+
+    /// Shape inference over every input of the layer.
+    fn infer_shapes(&self, inputs: &[Vec<usize>]) -> Result<Vec<usize>, String> {
+        if !self.is_merge() {
+            return match inputs {
+                [input] => self.infer_shape(input),
+                _ => Err(format!("takes exactly 1 input, got {}", inputs.len())),
+            };
+        }
+        let first = match inputs {
+            [first, _, ..] => first,
+            _ => return Err(format!("merges at least 2 inputs, got {}", inputs.len())),
+        };
+        match self {
+            LayerDef::Concat { axis } => ops::concat_shape(inputs, *axis)
+                .map_err(|e| format!("cannot concatenate along axis {}: {}", axis, e)),
+            _ => match inputs.iter().find(|shape| *shape != first) {
+                Some(shape) => Err(format!(
+                    "expects inputs of identical shape, got {:?} and {:?}", first, shape
+                )),
+                None => Ok(first.clone()),
+            },
+        }
+    }
+
+    /// Shape inference; errors are phrased to follow "layer {i} ".
+    fn infer_shape(&self, input: &[usize]) -> Result<Vec<usize>, String> {
+        match self {
+            LayerDef::Add | LayerDef::Multiply | LayerDef::Concat { .. } => {
+                Err("merges at least 2 inputs, got 1".to_string())
+            }
+            LayerDef::Activation { .. } | LayerDef::Dropout { .. } => Ok(input.to_vec()),
+            LayerDef::Flatten => Ok(vec![input.iter().product()]),
+            LayerDef::Reshape { shape } => {
+                let (from, to): (usize, usize) = (input.iter().product(), shape.iter().product());
+                if from != to {
+                    return Err(format!(
+                        "cannot reshape {:?} ({} elements) into {:?} ({} elements)",
+                        input, from, shape, to
+                    ));
+                }
+                Ok(shape.clone())
+            }
+            LayerDef::Conv2D { in_channels, out_channels, kernel_size, stride, padding } => {
+                let (c, h, w) = image_dims(input)?;
+                if c != *in_channels {
+                    return Err(format!(
+                        "expects in_channels={}, but previous output has {} channels", in_channels, c
+                    ));
+                }
+                let oh = window_output(h, *kernel_size, *stride, *padding)?;
+                let ow = window_output(w, *kernel_size, *stride, *padding)?;
+                Ok(vec![*out_channels, oh, ow])
+            }
+            LayerDef::MaxPool2D { kernel_size, stride } => {
+                let (c, h, w) = image_dims(input)?;
+                Ok(vec![c, window_output(h, *kernel_size, *stride, 0)?, window_output(w, *kernel_size, *stride, 0)?])
+            }
+            LayerDef::Linear { .. } | LayerDef::Rnn { .. } | LayerDef::Gru { .. } | LayerDef::Lstm { .. }
+            | LayerDef::BatchNorm { .. } | LayerDef::LayerNorm { .. } => {
+                let dim = match input {
+                    [dim] => *dim,
+                    _ => return Err(format!(
+                        "expects a flat input, but previous output has shape {:?} (add a Flatten layer)",
+                        input
+                    )),
+                };
+                if let Some(expected) = self.expected_input_dim() {
+                    if expected != dim {
+                        return Err(format!(
+                            "expects input_dim={}, but previous output is {}", expected, dim
+                        ));
+                    }
+                }
+                let out = match self {
+                    LayerDef::Linear { out_features, .. } => *out_features,
+                    LayerDef::Rnn { hidden_size, .. } | LayerDef::Gru { hidden_size, .. }
+                    | LayerDef::Lstm { hidden_size, .. } => *hidden_size,
+                    // Normalization keeps the feature count
+                    _ => dim,
+                };
+                Ok(vec![out])
+            }
         }
     }
 
@@ -89,17 +250,86 @@ impl LayerDef {
             LayerDef::Linear { in_features, .. } => Some(*in_features),
             LayerDef::Rnn { input_size, .. } => Some(*input_size),
             LayerDef::Gru { input_size, .. } => Some(*input_size),
-            LayerDef::Activation { .. } | LayerDef::Dropout { .. } | LayerDef::BatchNorm { .. } => None,
+            LayerDef::Lstm { input_size, .. } => Some(*input_size),
+            LayerDef::LayerNorm { num_features } => Some(*num_features),
+            LayerDef::Activation { .. } | LayerDef::Dropout { .. } | LayerDef::BatchNorm { .. }
+            | LayerDef::Conv2D { .. } | LayerDef::MaxPool2D { .. } | LayerDef::Flatten
+            | LayerDef::Reshape { .. } | LayerDef::Add | LayerDef::Multiply
+            | LayerDef::Concat { .. } => None,
         }
     }
 }
 
+/// Splits a per-sample image shape into `(channels, height, width)`.
+fn image_dims(input: &[usize]) -> Result<(usize, usize, usize), String> {
+    match input {
+        [c, h, w] => Ok((*c, *h, *w)),
+        _ => Err(format!("expects a [channels, height, width] input, but previous output has shape {:?}", input)),
+    }
+}
+
+/// Output length of a sliding window over `size` (convolution/pooling).
+fn window_output(size: usize, kernel: usize, stride: usize, padding: usize) -> Result<usize, String> {
+    if kernel == 0 || stride == 0 {
+        return Err(format!("needs kernel_size and stride > 0 (got {} and {})", kernel, stride));
+    }
+    if size + 2 * padding < kernel {
+        return Err(format!(
+            "has kernel_size={} larger than its padded input ({} + 2*{})", kernel, size, padding
+        ));
+    }
+    Ok((size + 2 * padding - kernel) / stride + 1)
+}
+
 // ── Network Definition ─────────────────────────────────────────────────────
 
+/// A named input of a graph-form [`NetworkDef`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InputDef {
+    pub name: String,
+    /// Per-sample shape (batch dimension excluded).
+    pub shape: Vec<usize>,
+}
+
+impl InputDef {
+    pub fn new(name: &str, shape: Vec<usize>) -> Self {
+        Self { name: name.to_string(), shape }
+    }
+}
+
+/// A layer of a graph-form [`NetworkDef`]: its id, the ids it reads from,
+/// and the layer itself (flattened into the same JSON object).
+///
+/// An empty `inputs` list reads from the previous node (or from the only
+/// network input, for the first node), so plain chains need no wiring.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NodeDef {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<String>,
+    #[serde(flatten)]
+    pub layer: LayerDef,
+}
+
+impl NodeDef {
+    pub fn new(id: &str, layer: LayerDef, inputs: &[&str]) -> Self {
+        Self {
+            id: id.to_string(),
+            inputs: inputs.iter().map(|s| s.to_string()).collect(),
+            layer,
+        }
+    }
+}
+
 /// A declarative, serializable description of a neural network.
 ///
-/// Describes a sequential stack of layers from input to output.
-/// Can be validated and compiled into a live [`Graph`].
+/// Describes either a sequential stack of layers (`layers`) or a DAG of
+/// named layers (`inputs`, `nodes`, `outputs`). Can be validated and
+/// compiled into a live [`Graph`].
+///
+/// Shapes are tracked per sample (batch dimension excluded): flat networks
+/// only set `input_dim`, while image networks set `input_shape` to
+/// `[channels, height, width]`.
 ///
 /// # Example
 ///
@@ -115,74 +345,340 @@ impl LayerDef {
 ///
 /// assert!(net.validate().is_ok());
 /// ```
+///
+/// A residual block in graph form:
+///
+/// ```rust
+/// use gran_prix::network_def::{NetworkDef, LayerDef, InputDef, NodeDef, ActivationType};
+///
+/// let net = NetworkDef::graph(
+///     vec![InputDef::new("x", vec![8])],
+///     vec![
+///         NodeDef::new("fc", LayerDef::Linear { in_features: 8, out_features: 8 }, &["x"]),
+///         NodeDef::new("act", LayerDef::Activation { function: ActivationType::ReLU }, &[]),
+///         NodeDef::new("sum", LayerDef::Add, &["x", "act"]),
+///     ],
+///     vec!["sum".to_string()],
+/// );
+///
+/// assert!(net.validate().is_ok());
+/// ```
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct NetworkDef {
-    /// Number of input features.
+    /// Number of input features (the product of `input_shape` when set).
+    #[serde(default)]
     pub input_dim: usize,
+    /// Full per-sample input shape, e.g. `[1, 28, 28]` for grayscale images.
+    /// When absent the input is flat: `[input_dim]`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_shape: Option<Vec<usize>>,
     /// Sequential list of layers from input to output.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub layers: Vec<LayerDef>,
+    /// Optional names for `layers`, by position. A named layer's parameters
+    /// are keyed `{name}.{param}`, so its state-dict keys survive inserting
+    /// or removing other layers; unnamed layers fall back to `layers.{i}`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub layer_names: Vec<Option<String>>,
+    /// Named inputs (graph form).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<InputDef>,
+    /// Layers in topological order: every id a node reads from must be
+    /// declared before it (graph form).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub nodes: Vec<NodeDef>,
+    /// Ids of the nodes exposed as outputs; defaults to the last node (graph form).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<String>,
+}
+
+/// A validated network, in graph form even when declared sequentially.
+///
+/// Values are numbered by slot: the inputs first, then one slot per layer.
+struct Resolved<'a> {
+    inputs: Vec<(String, Vec<usize>)>,
+    layers: Vec<ResolvedLayer<'a>>,
+    outputs: Vec<(String, usize)>,
+}
+
+struct ResolvedLayer<'a> {
+    /// Parameter name scope: the node id, or in sequential form the layer
+    /// name (`layers.{i}` when unnamed).
+    id: String,
+    layer: &'a LayerDef,
+    inputs: Vec<usize>,
+    shape: Vec<usize>,
+}
+
+impl Resolved<'_> {
+    fn shape(&self, slot: usize) -> &[usize] {
+        match slot.checked_sub(self.inputs.len()) {
+            Some(layer) => &self.layers[layer].shape,
+            None => &self.inputs[slot].1,
+        }
+    }
 }
 
+/// Name of the single input of a sequential network.
+pub const SEQUENTIAL_INPUT: &str = "input";
+/// Name of the single output of a sequential network.
+pub const SEQUENTIAL_OUTPUT: &str = "output";
+
 impl NetworkDef {
-    /// Creates a new network definition.
+    /// Creates a new network definition with a flat input.
     pub fn new(input_dim: usize, layers: Vec<LayerDef>) -> Self {
-        Self { input_dim, layers }
+        Self { input_dim, layers, ..Self::empty() }
+    }
+
+    /// Creates a network definition with a multi-dimensional per-sample input,
+    /// e.g. `[channels, height, width]` for a CNN.
+    pub fn with_input_shape(input_shape: Vec<usize>, layers: Vec<LayerDef>) -> Self {
+        Self {
+            input_dim: input_shape.iter().product(),
+            input_shape: Some(input_shape),
+            layers,
+            ..Self::empty()
+        }
+    }
+
+    /// Creates a graph-form definition from named inputs, nodes (in
+    /// topological order) and output node ids.
+    pub fn graph(inputs: Vec<InputDef>, nodes: Vec<NodeDef>, outputs: Vec<String>) -> Self {
+        Self { inputs, nodes, outputs, ..Self::empty() }
+    }
+
+    fn empty() -> Self {
+        Self {
+            input_dim: 0,
+            input_shape: None,
+            layers: Vec::new(),
+            layer_names: Vec::new(),
+            inputs: Vec::new(),
+            nodes: Vec::new(),
+            outputs: Vec::new(),
+        }
+    }
+
+    /// Returns true if this definition uses the graph form (`nodes`).
+    pub fn is_graph(&self) -> bool {
+        !self.nodes.is_empty()
+    }
+
+    /// Returns the per-sample input shape (of the first input, in graph form).
+    pub fn input_shape(&self) -> Vec<usize> {
+        if let Some(input) = self.inputs.first() {
+            return input.shape.clone();
+        }
+        match &self.input_shape {
+            Some(shape) => shape.clone(),
+            None => vec![self.input_dim],
+        }
+    }
+
+    /// Returns every named input with its per-sample shape, in declaration
+    /// order ([`SEQUENTIAL_INPUT`] for sequential networks).
+    pub fn input_shapes(&self) -> Vec<(String, Vec<usize>)> {
+        if self.is_graph() {
+            self.inputs.iter().map(|i| (i.name.clone(), i.shape.clone())).collect()
+        } else {
+            vec![(SEQUENTIAL_INPUT.to_string(), self.input_shape())]
+        }
+    }
+
+    /// Returns every named output with its per-sample shape
+    /// ([`SEQUENTIAL_OUTPUT`] for sequential networks).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the definition does not validate.
+    pub fn output_shapes(&self) -> GPResult<Vec<(String, Vec<usize>)>> {
+        let resolved = self.resolve()?;
+        Ok(resolved.outputs.iter()
+            .map(|(name, slot)| (name.clone(), resolved.shape(*slot).to_vec()))
+            .collect())
+    }
+
+    /// Returns the per-sample output shape of the network (of the first
+    /// output, in graph form).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the definition does not validate.
+    pub fn output_shape(&self) -> GPResult<Vec<usize>> {
+        let resolved = self.resolve()?;
+        Ok(resolved.shape(resolved.outputs[0].1).to_vec())
     }
 
     /// Returns the output dimension of the network.
+    ///
+    /// This is the number of output elements per sample when the definition
+    /// validates (see [`output_shape`](Self::output_shape)); otherwise the
+    /// layers' [`LayerDef::output_dim`]s are chained from `input_dim`.
     pub fn output_dim(&self) -> usize {
-        let mut dim = self.input_dim;
-        for layer in &self.layers {
-            dim = layer.output_dim(dim);
+        match self.output_shape() {
+            Ok(shape) => shape.iter().product(),
+            Err(_) => self.layers.iter().fold(self.input_dim, |dim, layer| layer.output_dim(dim)),
         }
-        dim
     }
 
     /// Returns the total number of layers.
     pub fn layer_count(&self) -> usize {
-        self.layers.len()
+        self.layers.len() + self.nodes.len()
     }
 
     /// Validates the network definition for consistency.
     ///
     /// Checks:
     /// - At least one layer exists
-    /// - Input dimensions chain correctly through all layers
+    /// - `input_dim` agrees with `input_shape` when both are given
+    /// - Input shapes chain correctly through all layers
     /// - No zero-dimensional layers
+    /// - In graph form: unique ids, every referenced id declared earlier,
+    ///   merge layers read at least 2 inputs, and outputs name layers
     pub fn validate(&self) -> GPResult<()> {
-        if self.input_dim == 0 {
+        self.resolve().map(|_| ())
+    }
+
+    fn resolve(&self) -> GPResult<Resolved<'_>> {
+        if self.is_graph() {
+            self.resolve_graph()
+        } else {
+            self.resolve_sequential()
+        }
+    }
+
+    fn resolve_sequential(&self) -> GPResult<Resolved<'_>> {
+        if !self.inputs.is_empty() || !self.outputs.is_empty() {
+            return Err(GPError::InferenceError(
+                "NetworkDef: named inputs and outputs need graph-form nodes".to_string()
+            ));
+        }
+        let input_shape = self.input_shape();
+        if input_shape.is_empty() || input_shape.contains(&0) {
             return Err(GPError::InferenceError(
                 "NetworkDef: input_dim must be > 0".to_string()
             ));
         }
+        if let Some(shape) = &self.input_shape {
+            let numel: usize = shape.iter().product();
+            if self.input_dim != 0 && self.input_dim != numel {
+                return Err(GPError::InferenceError(format!(
+                    "NetworkDef: input_dim={} does not match input_shape {:?}",
+                    self.input_dim, shape
+                )));
+            }
+        }
         if self.layers.is_empty() {
             return Err(GPError::InferenceError(
                 "NetworkDef: must have at least one layer".to_string()
             ));
         }
 
-        let mut current_dim = self.input_dim;
-        for (i, layer) in self.layers.iter().enumerate() {
-            // Check that the layer's expected input matches the current flow
-            if let Some(expected) = layer.expected_input_dim() {
-                if expected != current_dim {
-                    return Err(GPError::InferenceError(format!(
-                        "NetworkDef: layer {} expects input_dim={}, but previous output is {}",
-                        i, expected, current_dim
-                    )));
-                }
+        if self.layer_names.len() > self.layers.len() {
+            return Err(GPError::InferenceError(format!(
+                "NetworkDef: {} layer names for {} layers", self.layer_names.len(), self.layers.len()
+            )));
+        }
+        let mut seen = HashSet::new();
+        for name in self.layer_names.iter().flatten() {
+            if name.is_empty() || !seen.insert(name.as_str()) {
+                return Err(GPError::InferenceError(format!("NetworkDef: invalid or duplicate layer name '{}'", name)));
             }
+        }
+
+        let mut resolved = Resolved {
+            inputs: vec![(SEQUENTIAL_INPUT.to_string(), input_shape)],
+            layers: Vec::with_capacity(self.layers.len()),
+            outputs: vec![(SEQUENTIAL_OUTPUT.to_string(), self.layers.len())],
+        };
+        for (i, layer) in self.layers.iter().enumerate() {
+            // Layer i reads slot i: the input, then each previous layer
+            let shape = layer.infer_shape(resolved.shape(i)).map_err(|msg| {
+                GPError::InferenceError(format!("NetworkDef: layer {} {}", i, msg))
+            })?;
+            check_output_shape(&shape, &i.to_string())?;
+            let id = match self.layer_names.get(i) {
+                Some(Some(name)) => name.clone(),
+                _ => format!("layers.{}", i),
+            };
+            resolved.layers.push(ResolvedLayer { id, layer, inputs: vec![i], shape });
+        }
+        Ok(resolved)
+    }
+
+    fn resolve_graph(&self) -> GPResult<Resolved<'_>> {
+        if !self.layers.is_empty() || !self.layer_names.is_empty() {
+            return Err(GPError::InferenceError(
+                "NetworkDef: declare either `layers` or `nodes`, not both".to_string()
+            ));
+        }
+        if self.inputs.is_empty() {
+            return Err(GPError::InferenceError(
+                "NetworkDef: graph form needs at least one input".to_string()
+            ));
+        }
 
-            let out = layer.output_dim(current_dim);
-            if out == 0 {
+        let mut slots: HashMap<&str, usize> = HashMap::new();
+        let mut resolved = Resolved {
+            inputs: Vec::with_capacity(self.inputs.len()),
+            layers: Vec::with_capacity(self.nodes.len()),
+            outputs: Vec::new(),
+        };
+        for input in &self.inputs {
+            if input.shape.is_empty() || input.shape.contains(&0) {
                 return Err(GPError::InferenceError(format!(
-                    "NetworkDef: layer {} has zero output dimension", i
+                    "NetworkDef: input '{}' must have a non-empty shape without zero dims", input.name
                 )));
             }
-            current_dim = out;
+            if slots.insert(&input.name, resolved.inputs.len()).is_some() {
+                return Err(GPError::InferenceError(format!("NetworkDef: duplicate id '{}'", input.name)));
+            }
+            resolved.inputs.push((input.name.clone(), input.shape.clone()));
         }
 
-        Ok(())
+        let num_inputs = self.inputs.len();
+        for (i, node) in self.nodes.iter().enumerate() {
+            let sources: Vec<usize> = if node.inputs.is_empty() {
+                match i {
+                    0 if num_inputs == 1 => vec![0],
+                    0 => return Err(GPError::InferenceError(format!(
+                        "NetworkDef: layer '{}' must list its inputs", node.id
+                    ))),
+                    _ => vec![num_inputs + i - 1],
+                }
+            } else {
+                node.inputs.iter()
+                    .map(|src| slots.get(src.as_str()).copied().ok_or_else(|| {
+                        GPError::InferenceError(format!(
+                            "NetworkDef: layer '{}' reads from unknown id '{}' (ids must be declared before use)",
+                            node.id, src
+                        ))
+                    }))
+                    .collect::<GPResult<_>>()?
+            };
+
+            let shapes: Vec<Vec<usize>> = sources.iter().map(|&s| resolved.shape(s).to_vec()).collect();
+            let shape = node.layer.infer_shapes(&shapes).map_err(|msg| {
+                GPError::InferenceError(format!("NetworkDef: layer '{}' {}", node.id, msg))
+            })?;
+            check_output_shape(&shape, &format!("'{}'", node.id))?;
+            if slots.insert(&node.id, num_inputs + i).is_some() {
+                return Err(GPError::InferenceError(format!("NetworkDef: duplicate id '{}'", node.id)));
+            }
+            resolved.layers.push(ResolvedLayer { id: node.id.clone(), layer: &node.layer, inputs: sources, shape });
+        }
+
+        let default_output = self.nodes.last().map(|n| n.id.clone());
+        let outputs = if self.outputs.is_empty() { default_output.as_slice() } else { &self.outputs[..] };
+        for name in outputs {
+            match slots.get(name.as_str()) {
+                Some(&slot) if slot >= num_inputs => resolved.outputs.push((name.clone(), slot)),
+                _ => return Err(GPError::InferenceError(format!(
+                    "NetworkDef: output '{}' is not a layer id", name
+                ))),
+            }
+        }
+        Ok(resolved)
     }
 
     /// Compiles this definition into a live [`Graph`] with allocated weights.
@@ -192,56 +688,93 @@ impl NetworkDef {
     ///
     /// # Returns
     ///
-    /// A tuple of `(Graph, input_node_id, output_node_id)` ready for execution.
+    /// A [`CompiledNetwork`] with named input and output nodes, ready for execution.
     ///
     /// # Errors
     ///
     /// Returns an error if validation fails.
     pub fn compile(&self, backend: Box<dyn Backend>) -> GPResult<CompiledNetwork> {
-        self.validate()?;
+        let resolved = self.resolve()?;
 
         let mut graph = Graph::new(backend);
-        let input_tensor = Tensor::new_zeros(&[1, self.input_dim]);
-        let input_id = graph.input(input_tensor);
+        let mut values: Vec<NodeId> = resolved.inputs.iter()
+            .map(|(_, shape)| graph.batched_placeholder(&batched(shape)))
+            .collect();
 
         let mut gb = GraphBuilder::new(&mut graph);
-        let mut last_node = input_id;
         let mut stateful_layers: Vec<Box<dyn Layer>> = Vec::new();
 
-        for layer_def in &self.layers {
-            match layer_def {
+        for node in &resolved.layers {
+            let sources: Vec<NodeId> = node.inputs.iter().map(|&slot| values[slot]).collect();
+            let x = sources[0];
+            // Parameters are named after the layer id, e.g. `fc1.weight`
+            let out = gb.scoped(&node.id, |gb| match node.layer {
                 LayerDef::Linear { in_features, out_features } => {
                     let mut linear = Linear::new(*in_features, *out_features);
-                    last_node = linear.forward(last_node, &mut gb);
+                    linear.forward(x, gb)
                 }
                 LayerDef::Activation { function } => {
                     let mut act = Activation::new(function.clone());
-                    last_node = act.forward(last_node, &mut gb);
+                    act.forward(x, gb)
                 }
                 LayerDef::Rnn { input_size, hidden_size } => {
                     let mut rnn = RNNCell::new(*input_size, *hidden_size);
-                    last_node = rnn.forward(last_node, &mut gb);
+                    let out = rnn.forward(x, gb);
                     stateful_layers.push(Box::new(rnn));
+                    out
                 }
                 LayerDef::Gru { input_size, hidden_size } => {
                     let mut gru = GRUCell::new(*input_size, *hidden_size);
-                    last_node = gru.forward(last_node, &mut gb);
+                    let out = gru.forward(x, gb);
                     stateful_layers.push(Box::new(gru));
+                    out
                 }
-                LayerDef::Dropout { rate } => {
-                    last_node = gb.dropout(last_node, *rate);
+                LayerDef::Lstm { input_size, hidden_size } => {
+                    let mut lstm = LSTMCell::new(*input_size, *hidden_size);
+                    let out = lstm.forward(x, gb);
+                    stateful_layers.push(Box::new(lstm));
+                    out
                 }
+                LayerDef::Dropout { rate } => gb.dropout(x, *rate),
                 LayerDef::BatchNorm { num_features } => {
                     let mut bn = BatchNorm::new(*num_features);
-                    last_node = bn.forward(last_node, &mut gb);
+                    bn.forward(x, gb)
                 }
-            }
+                LayerDef::LayerNorm { num_features } => {
+                    let mut ln = LayerNorm::new(*num_features);
+                    ln.forward(x, gb)
+                }
+                LayerDef::Conv2D { in_channels, out_channels, kernel_size, stride, padding } => {
+                    let mut conv = Conv2D::new(*in_channels, *out_channels, *kernel_size, *stride, *padding);
+                    conv.forward(x, gb)
+                }
+                LayerDef::MaxPool2D { kernel_size, stride } => gb.max_pool2d(x, *kernel_size, *stride),
+                LayerDef::Flatten | LayerDef::Reshape { .. } => {
+                    // Keep whatever batch size is fed: [-1, ...per-sample shape]
+                    let target = std::iter::once(-1).chain(node.shape.iter().map(|&d| d as isize)).collect();
+                    gb.reshape(x, target)
+                }
+                LayerDef::Add => sources[1..].iter().fold(x, |acc, &s| gb.add(acc, s)),
+                LayerDef::Multiply => sources[1..].iter().fold(x, |acc, &s| gb.mul(acc, s)),
+                // Per-sample axis → tensor axis (after the batch dimension)
+                LayerDef::Concat { axis } => gb.concat(sources, axis + 1),
+            });
+            values.push(out);
         }
 
+        let input_nodes: Vec<(String, NodeId)> = resolved.inputs.iter().zip(&values)
+            .map(|((name, _), &id)| (name.clone(), id))
+            .collect();
+        let output_nodes: Vec<(String, NodeId)> = resolved.outputs.iter()
+            .map(|(name, slot)| (name.clone(), values[*slot]))
+            .collect();
+
         Ok(CompiledNetwork {
             graph,
-            input_node: input_id,
-            output_node: last_node,
+            input_node: input_nodes[0].1,
+            output_node: output_nodes[0].1,
+            input_nodes,
+            output_nodes,
             stateful_layers,
         })
     }
@@ -259,36 +792,105 @@ impl NetworkDef {
     }
 }
 
+/// Rejects empty or zero-sized layer outputs.
+fn check_output_shape(shape: &[usize], label: &str) -> GPResult<()> {
+    if shape.is_empty() || shape.contains(&0) {
+        return Err(GPError::InferenceError(format!(
+            "NetworkDef: layer {} has zero output dimension", label
+        )));
+    }
+    Ok(())
+}
+
+/// Prepends a batch dimension of 1 to a per-sample shape (the initial shape
+/// of a batched input).
+fn batched(shape: &[usize]) -> Vec<usize> {
+    std::iter::once(1).chain(shape.iter().copied()).collect()
+}
+
 // ── Compiled Network ───────────────────────────────────────────────────────
 
 /// A compiled network — a [`Graph`] with tracked input/output nodes
-/// and stateful layers (RNN/GRU) that need state management.
+/// and stateful layers (RNN/GRU/LSTM) that need state management.
 pub struct CompiledNetwork {
     /// The live computation graph.
     pub graph: Graph,
-    /// The input node ID (where to inject data).
-    pub input_node: crate::NodeId,
-    /// The output node ID (where to read predictions).
-    pub output_node: crate::NodeId,
-    /// Layers with internal state (RNN, GRU) that need update/reset.
+    /// The input node ID (where to inject data); the first input in graph form.
+    pub input_node: NodeId,
+    /// The output node ID (where to read predictions); the first output in graph form.
+    pub output_node: NodeId,
+    /// Named input nodes in declaration order ([`SEQUENTIAL_INPUT`] for sequential networks).
+    pub input_nodes: Vec<(String, NodeId)>,
+    /// Named output nodes ([`SEQUENTIAL_OUTPUT`] for sequential networks).
+    pub output_nodes: Vec<(String, NodeId)>,
+    /// Layers with internal state (RNN, GRU, LSTM) that need update/reset.
     pub stateful_layers: Vec<Box<dyn Layer>>,
 }
 
 impl CompiledNetwork {
-    /// Updates stateful layers (RNN/GRU) after a forward pass.
+    /// Looks up an input node by name.
+    pub fn input(&self, name: &str) -> Option<NodeId> {
+        self.input_nodes.iter().find(|(n, _)| n == name).map(|(_, id)| *id)
+    }
+
+    /// Looks up an output node by name.
+    pub fn output(&self, name: &str) -> Option<NodeId> {
+        self.output_nodes.iter().find(|(n, _)| n == name).map(|(_, id)| *id)
+    }
+
+    /// Updates stateful layers (RNN/GRU/LSTM) after a forward pass.
     ///
-    /// Reads the state node's computed value from the graph and
-    /// writes it back into the layer's hidden state.
+    /// Reads the state nodes' computed values from the graph and
+    /// writes them back into the layer's recurrent state.
     pub fn update_states(&mut self) {
         for layer in &mut self.stateful_layers {
-            if let Some(state_node_id) = layer.state_node() {
-                if let Some(Some(tensor)) = self.graph.values().get(state_node_id.0) {
-                    layer.update_state(tensor.clone());
-                }
+            let tensors: Option<Vec<Tensor>> = layer.state_nodes().iter()
+                .map(|id| self.graph.values().get(id.0).cloned().flatten())
+                .collect();
+            if let Some(tensors) = tensors {
+                layer.update_states(tensors);
             }
         }
     }
 
+    /// Feeds named inputs, runs one forward pass covering every output, and
+    /// returns the outputs by name.
+    ///
+    /// Every declared input must be fed as `[batch, ...per-sample shape]`,
+    /// with any batch size. Stateful layers are updated afterwards, as by
+    /// [`update_states`](Self::update_states).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let feeds = HashMap::from([
+    ///     ("board".to_string(), board),
+    ///     ("features".to_string(), features),
+    /// ]);
+    /// let outputs = compiled.run(&feeds)?;
+    /// let (policy, value) = (&outputs["policy"], &outputs["value"]);
+    /// ```
+    pub fn run(&mut self, feeds: &HashMap<String, Tensor>) -> GPResult<HashMap<String, Tensor>> {
+        if let Some(name) = feeds.keys().find(|name| self.input(name).is_none()) {
+            return Err(GPError::InferenceError(format!(
+                "CompiledNetwork: unknown input '{}'", name
+            )));
+        }
+        let mut inputs = Vec::with_capacity(self.input_nodes.len());
+        for (name, id) in &self.input_nodes {
+            let tensor = feeds.get(name).ok_or_else(|| {
+                GPError::InferenceError(format!("CompiledNetwork: missing input '{}'", name))
+            })?;
+            inputs.push((*id, tensor));
+        }
+
+        let fetches: Vec<NodeId> = self.output_nodes.iter().map(|(_, id)| *id).collect();
+        let values = self.graph.run(&inputs, &fetches)?;
+        self.update_states();
+
+        Ok(self.output_nodes.iter().map(|(name, _)| name.clone()).zip(values).collect())
+    }
+
     /// Resets all stateful layers (e.g., at the start of a new episode).
     pub fn reset_states(&mut self) {
         for layer in &mut self.stateful_layers {
@@ -350,6 +952,7 @@ impl NetworkDef {
 mod tests {
     use super::*;
     use crate::backend::cpu::CPUBackend;
+    use crate::graph::verifier::{Dim, Verifier};
 
     #[test]
     fn test_mlp_builder() {
@@ -482,6 +1085,81 @@ mod tests {
         assert_eq!(result.shape(), &[1, 1]);
     }
 
+    #[test]
+    fn test_compile_with_layernorm() {
+        let net = NetworkDef::new(3, vec![
+            LayerDef::Linear { in_features: 3, out_features: 4 },
+            LayerDef::LayerNorm { num_features: 4 },
+            LayerDef::Activation { function: ActivationType::ReLU },
+        ]);
+        let json = net.to_json().unwrap();
+        assert!(json.contains("\"type\": \"layernorm\""));
+        assert_eq!(NetworkDef::from_json(&json).unwrap().layers, net.layers);
+
+        let compiled = net.compile(Box::new(CPUBackend)).unwrap();
+        let mut graph = compiled.graph;
+        let result = graph.execute(compiled.output_node).unwrap();
+        assert_eq!(result.shape(), &[1, 4]);
+
+        let bad = NetworkDef::new(3, vec![
+            LayerDef::Linear { in_features: 3, out_features: 4 },
+            LayerDef::LayerNorm { num_features: 5 },
+        ]);
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_extended_activations_from_json() {
+        let json = r#"{
+            "input_dim": 2,
+            "layers": [
+                {"type": "linear", "in_features": 2, "out_features": 4},
+                {"type": "activation", "function": {"leakyrelu": {"slope": 0.2}}},
+                {"type": "activation", "function": {"elu": {}}},
+                {"type": "activation", "function": "gelu"},
+                {"type": "activation", "function": "swish"},
+                {"type": "activation", "function": "softplus"},
+                {"type": "activation", "function": {"hardtanh": {}}}
+            ]
+        }"#;
+        let net = NetworkDef::from_json(json).unwrap();
+        let functions: Vec<ActivationType> = net.layers.iter().filter_map(|l| match l {
+            LayerDef::Activation { function } => Some(function.clone()),
+            _ => None,
+        }).collect();
+        assert_eq!(functions, vec![
+            ActivationType::LeakyReLU { slope: 0.2 },
+            ActivationType::elu(),
+            ActivationType::GELU,
+            ActivationType::SiLU,
+            ActivationType::Softplus,
+            ActivationType::hardtanh(),
+        ]);
+
+        let compiled = net.compile(Box::new(CPUBackend)).unwrap();
+        let mut graph = compiled.graph;
+        let result = graph.execute(compiled.output_node).unwrap();
+        assert_eq!(result.shape(), &[1, 4]);
+    }
+
+    #[test]
+    fn test_parameterized_activations_from_bare_names() {
+        let parse = |json: &str| serde_json::from_str::<ActivationType>(json);
+        assert_eq!(parse(r#""leakyrelu""#).unwrap(), ActivationType::leaky_relu());
+        assert_eq!(parse(r#""elu""#).unwrap(), ActivationType::elu());
+        assert_eq!(parse(r#""hardtanh""#).unwrap(), ActivationType::hardtanh());
+        assert_eq!(parse(r#""relu""#).unwrap(), ActivationType::ReLU);
+        assert!(parse(r#""leaky""#).is_err());
+        assert!(parse(r#"{"leakyrelu": {"slope": "steep"}}"#).is_err());
+
+        // Serialization keeps the tagged form, which round-trips
+        for function in [ActivationType::LeakyReLU { slope: 0.3 }, ActivationType::Tanh] {
+            let json = serde_json::to_string(&function).unwrap();
+            assert_eq!(parse(&json).unwrap(), function);
+        }
+        assert_eq!(serde_json::to_string(&ActivationType::elu()).unwrap(), r#"{"elu":{"alpha":1.0}}"#);
+    }
+
     #[test]
     fn test_compile_invalid_definition() {
         let net = NetworkDef::new(4, vec![
@@ -490,6 +1168,26 @@ mod tests {
         assert!(net.compile(Box::new(CPUBackend)).is_err());
     }
 
+    #[test]
+    fn test_compile_with_lstm() {
+        let net = NetworkDef::new(3, vec![
+            LayerDef::Lstm { input_size: 3, hidden_size: 5 },
+            LayerDef::Linear { in_features: 5, out_features: 2 },
+        ]);
+        let json = net.to_json().unwrap();
+        assert!(json.contains("\"type\": \"lstm\""));
+        assert_eq!(NetworkDef::from_json(&json).unwrap().layers, net.layers);
+
+        let mut compiled = net.compile(Box::new(CPUBackend)).unwrap();
+        assert_eq!(compiled.stateful_layers.len(), 1);
+        assert_eq!(compiled.stateful_layers[0].state_nodes().len(), 2);
+
+        let result = compiled.graph.execute(compiled.output_node).unwrap();
+        assert_eq!(result.shape(), &[1, 2]);
+        compiled.update_states();
+        compiled.reset_states();
+    }
+
     #[test]
     fn test_compiled_network_states() {
         let net = NetworkDef::new(2, vec![
@@ -524,5 +1222,324 @@ mod tests {
             LayerDef::Gru { input_size: 4, hidden_size: 32 }.output_dim(4),
             32
         );
+        assert_eq!(LayerDef::Reshape { shape: vec![2, 3] }.output_dim(6), 6);
+    }
+
+    #[test]
+    fn test_cnn_shape_tracking() {
+        let net = NetworkDef::with_input_shape(vec![1, 10, 10], vec![
+            LayerDef::Conv2D { in_channels: 1, out_channels: 4, kernel_size: 3, stride: 1, padding: 1 },
+            LayerDef::Activation { function: ActivationType::ReLU },
+            LayerDef::MaxPool2D { kernel_size: 2, stride: 2 },
+            LayerDef::Flatten,
+            LayerDef::Linear { in_features: 100, out_features: 1 },
+        ]);
+        assert!(net.validate().is_ok());
+        assert_eq!(net.input_dim, 100);
+        assert_eq!(net.output_shape().unwrap(), vec![1]);
+        assert_eq!(net.output_dim(), 1);
+        let features = NetworkDef::with_input_shape(vec![1, 10, 10], net.layers[..3].to_vec());
+        assert_eq!(features.output_dim(), 4 * 5 * 5);
+        assert_eq!(
+            LayerDef::Conv2D { in_channels: 3, out_channels: 8, kernel_size: 3, stride: 2, padding: 0 }
+                .output_shape(&[3, 9, 9]).unwrap(),
+            vec![8, 4, 4]
+        );
+        assert_eq!(LayerDef::Reshape { shape: vec![2, 50] }.output_shape(&[100]).unwrap(), vec![2, 50]);
+    }
+
+    #[test]
+    fn test_validate_cnn_errors() {
+        // Linear directly after a conv needs a Flatten
+        let net = NetworkDef::with_input_shape(vec![1, 4, 4], vec![
+            LayerDef::Conv2D { in_channels: 1, out_channels: 2, kernel_size: 3, stride: 1, padding: 0 },
+            LayerDef::Linear { in_features: 8, out_features: 1 },
+        ]);
+        let err = net.validate().unwrap_err().to_string();
+        assert!(err.contains("layer 1") && err.contains("Flatten"), "{}", err);
+
+        // Channel mismatch
+        let net = NetworkDef::with_input_shape(vec![3, 4, 4], vec![
+            LayerDef::Conv2D { in_channels: 1, out_channels: 2, kernel_size: 3, stride: 1, padding: 0 },
+        ]);
+        assert!(net.validate().unwrap_err().to_string().contains("in_channels=1"));
+
+        // Kernel larger than the image
+        let net = NetworkDef::with_input_shape(vec![1, 2, 2], vec![
+            LayerDef::MaxPool2D { kernel_size: 3, stride: 1 },
+        ]);
+        assert!(net.validate().is_err());
+
+        // Conv on a flat input
+        let net = NetworkDef::new(16, vec![
+            LayerDef::Conv2D { in_channels: 1, out_channels: 2, kernel_size: 3, stride: 1, padding: 0 },
+        ]);
+        assert!(net.validate().is_err());
+
+        // Reshape must preserve the element count
+        let net = NetworkDef::new(6, vec![LayerDef::Reshape { shape: vec![4, 2] }]);
+        assert!(net.validate().is_err());
+
+        // input_dim disagreeing with input_shape
+        let mut net = NetworkDef::with_input_shape(vec![1, 4, 4], vec![LayerDef::Flatten]);
+        net.input_dim = 10;
+        assert!(net.validate().is_err());
+    }
+
+    #[test]
+    fn test_cnn_json_defaults() {
+        let json = r#"{
+            "input_shape": [1, 6, 6],
+            "layers": [
+                {"type": "conv2d", "in_channels": 1, "out_channels": 2, "kernel_size": 3},
+                {"type": "maxpool2d", "kernel_size": 2, "stride": 2},
+                {"type": "flatten"}
+            ]
+        }"#;
+        let net = NetworkDef::from_json(json).unwrap();
+        assert_eq!(
+            net.layers[0],
+            LayerDef::Conv2D { in_channels: 1, out_channels: 2, kernel_size: 3, stride: 1, padding: 0 }
+        );
+        assert_eq!(net.input_shape(), vec![1, 6, 6]);
+        assert_eq!(net.output_shape().unwrap(), vec![8]);
+
+        let restored = NetworkDef::from_json(&net.to_json().unwrap()).unwrap();
+        assert_eq!(restored.layers, net.layers);
+        assert_eq!(restored.input_shape, Some(vec![1, 6, 6]));
+    }
+
+    fn residual_def() -> NetworkDef {
+        NetworkDef::graph(
+            vec![InputDef::new("x", vec![4])],
+            vec![
+                NodeDef::new("fc1", LayerDef::Linear { in_features: 4, out_features: 4 }, &["x"]),
+                NodeDef::new("act", LayerDef::Activation { function: ActivationType::ReLU }, &[]),
+                NodeDef::new("skip", LayerDef::Add, &["x", "act"]),
+                NodeDef::new("head", LayerDef::Linear { in_features: 4, out_features: 2 }, &[]),
+            ],
+            vec!["head".to_string()],
+        )
+    }
+
+    #[test]
+    fn test_graph_residual_block() {
+        let net = residual_def();
+        assert!(net.is_graph());
+        assert!(net.validate().is_ok());
+        assert_eq!(net.output_shape().unwrap(), vec![2]);
+        assert_eq!(net.layer_count(), 4);
+
+        let mut compiled = net.compile(Box::new(CPUBackend)).unwrap();
+        assert_eq!(compiled.input("x"), Some(compiled.input_node));
+        assert_eq!(compiled.output("head"), Some(compiled.output_node));
+        assert_eq!(compiled.output("output"), None);
+        let out = compiled.graph.execute(compiled.output_node).unwrap();
+        assert_eq!(out.shape(), &[1, 2]);
+    }
+
+    #[test]
+    fn test_graph_multi_input_concat() {
+        let json = r#"{
+            "inputs": [
+                {"name": "image", "shape": [1, 4, 4]},
+                {"name": "features", "shape": [3]}
+            ],
+            "nodes": [
+                {"id": "conv", "type": "conv2d", "in_channels": 1, "out_channels": 2, "kernel_size": 3, "inputs": ["image"]},
+                {"id": "flat", "type": "flatten"},
+                {"id": "joined", "type": "concat", "inputs": ["flat", "features"]},
+                {"id": "policy", "type": "linear", "in_features": 11, "out_features": 4},
+                {"id": "value", "type": "linear", "in_features": 11, "out_features": 1, "inputs": ["joined"]},
+                {"id": "gate", "type": "multiply", "inputs": ["value", "value"]}
+            ],
+            "outputs": ["policy", "gate"]
+        }"#;
+        let net = NetworkDef::from_json(json).unwrap();
+        assert!(net.validate().is_ok());
+        assert_eq!(net.nodes[2].layer, LayerDef::Concat { axis: 0 });
+        assert_eq!(net.output_shape().unwrap(), vec![4]);
+
+        let mut compiled = net.compile(Box::new(CPUBackend)).unwrap();
+        assert_eq!(compiled.input_nodes.len(), 2);
+        let names: Vec<&str> = compiled.output_nodes.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["policy", "gate"]);
+
+        let gate = compiled.output("gate").unwrap();
+        assert_eq!(compiled.graph.execute(gate).unwrap().shape(), &[1, 1]);
+        let policy = compiled.output("policy").unwrap();
+        assert_eq!(compiled.graph.execute(policy).unwrap().shape(), &[1, 4]);
+
+        let restored = NetworkDef::from_json(&net.to_json().unwrap()).unwrap();
+        assert_eq!(restored.nodes, net.nodes);
+        assert_eq!(restored.outputs, net.outputs);
+    }
+
+    #[test]
+    fn test_graph_validation_errors() {
+        let err_of = |net: NetworkDef| net.validate().unwrap_err().to_string();
+
+        // Unknown (or not yet declared) id
+        let mut net = residual_def();
+        net.nodes[0].inputs = vec!["act".to_string()];
+        assert!(err_of(net).contains("unknown id 'act'"));
+
+        // Duplicate id
+        let mut net = residual_def();
+        net.nodes[1].id = "x".to_string();
+        assert!(err_of(net).contains("duplicate id 'x'"));
+
+        // Merge of mismatched shapes
+        let net = NetworkDef::graph(
+            vec![InputDef::new("a", vec![4]), InputDef::new("b", vec![3])],
+            vec![NodeDef::new("sum", LayerDef::Add, &["a", "b"])],
+            vec![],
+        );
+        assert!(err_of(net).contains("identical shape"));
+
+        // Concat of shapes that differ off the axis, or along a missing axis
+        let concat = |axis| NetworkDef::graph(
+            vec![InputDef::new("a", vec![2, 4]), InputDef::new("b", vec![3, 3])],
+            vec![NodeDef::new("joined", LayerDef::Concat { axis }, &["a", "b"])],
+            vec![],
+        );
+        assert!(err_of(concat(0)).contains("cannot concatenate along axis 0"));
+        assert!(err_of(concat(2)).contains("axis 2 out of range"));
+
+        // Merge with a single input
+        let mut net = residual_def();
+        net.nodes[2].inputs = vec!["act".to_string()];
+        assert!(err_of(net).contains("at least 2 inputs"));
+
+        // Non-merge layer with two inputs
+        let mut net = residual_def();
+        net.nodes[1].inputs = vec!["x".to_string(), "fc1".to_string()];
+        assert!(err_of(net).contains("exactly 1 input"));
+
+        // Outputs must name layers
+        let mut net = residual_def();
+        net.outputs = vec!["x".to_string()];
+        assert!(err_of(net).contains("output 'x'"));
+
+        // Ambiguous first node with several inputs
+        let net = NetworkDef::graph(
+            vec![InputDef::new("a", vec![4]), InputDef::new("b", vec![4])],
+            vec![NodeDef::new("act", LayerDef::Activation { function: ActivationType::ReLU }, &[])],
+            vec![],
+        );
+        assert!(err_of(net).contains("must list its inputs"));
+
+        // Mixing the sequential and graph forms
+        let mut net = residual_def();
+        net.layers = vec![LayerDef::Flatten];
+        assert!(net.validate().is_err());
+    }
+
+    #[test]
+    fn test_named_layer_keys_survive_inserting_a_layer() {
+        let keys = |net: &NetworkDef| -> Vec<String> {
+            let compiled = net.compile(Box::new(CPUBackend)).unwrap();
+            compiled.graph.params().state_dict().unwrap().into_keys().collect()
+        };
+        let mut net = NetworkDef::new(4, vec![
+            LayerDef::Linear { in_features: 4, out_features: 4 },
+            LayerDef::Linear { in_features: 4, out_features: 2 },
+        ]);
+        net.layer_names = vec![Some("enc".to_string()), Some("head".to_string())];
+        let before = keys(&net);
+        assert!(before.contains(&"enc.weight".to_string()) && before.contains(&"head.bias".to_string()));
+
+        // An unnamed layer inserted in between is keyed by its index
+        net.layers.insert(1, LayerDef::Linear { in_features: 4, out_features: 4 });
+        net.layer_names.insert(1, None);
+        let after = keys(&net);
+        assert!(before.iter().all(|key| after.contains(key)));
+        assert!(after.contains(&"layers.1.weight".to_string()));
+
+        net.layer_names[1] = Some("enc".to_string());
+        assert!(net.validate().is_err());
+    }
+
+    #[test]
+    fn test_sequential_compile_names() {
+        let net = NetworkDef::mlp(2, &[4], 1, ActivationType::ReLU, None);
+        let compiled = net.compile(Box::new(CPUBackend)).unwrap();
+        assert_eq!(compiled.input(SEQUENTIAL_INPUT), Some(compiled.input_node));
+        assert_eq!(compiled.output(SEQUENTIAL_OUTPUT), Some(compiled.output_node));
+    }
+
+    #[test]
+    fn test_run_named_feeds_and_fetches() {
+        let net = NetworkDef::graph(
+            vec![InputDef::new("a", vec![2]), InputDef::new("b", vec![2])],
+            vec![
+                NodeDef::new("sum", LayerDef::Add, &["a", "b"]),
+                NodeDef::new("policy", LayerDef::Activation { function: ActivationType::Softmax }, &["sum"]),
+                NodeDef::new("value", LayerDef::Multiply, &["a", "b"]),
+            ],
+            vec!["policy".to_string(), "value".to_string()],
+        );
+        let shapes = net.output_shapes().unwrap();
+        assert_eq!(shapes, vec![("policy".to_string(), vec![2]), ("value".to_string(), vec![2])]);
+        let mut compiled = net.compile(Box::new(CPUBackend)).unwrap();
+
+        let mut feeds = HashMap::from([
+            ("a".to_string(), Tensor::from_shape_vec(&[1, 2], vec![1.0, 2.0]).unwrap()),
+            ("b".to_string(), Tensor::from_shape_vec(&[1, 2], vec![3.0, -1.0]).unwrap()),
+        ]);
+        let outputs = compiled.run(&feeds).unwrap();
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs["value"].as_slice().unwrap(), &[3.0, -2.0]);
+        let policy = outputs["policy"].as_slice().unwrap();
+        assert!((policy[0] + policy[1] - 1.0).abs() < 1e-6 && policy[0] > policy[1]);
+
+        // Wrong shape, unknown name and missing input are all rejected
+        feeds.insert("b".to_string(), Tensor::new_zeros(&[1, 3]));
+        assert!(compiled.run(&feeds).is_err());
+        feeds.insert("b".to_string(), Tensor::new_zeros(&[1, 2]));
+        feeds.insert("c".to_string(), Tensor::new_zeros(&[1, 2]));
+        assert!(compiled.run(&feeds).unwrap_err().to_string().contains("unknown input 'c'"));
+        feeds.remove("c");
+        feeds.remove("a");
+        assert!(compiled.run(&feeds).unwrap_err().to_string().contains("missing input 'a'"));
+    }
+
+    #[test]
+    fn test_compiled_graph_accepts_any_batch() {
+        let net = NetworkDef::with_input_shape(vec![1, 4, 4], vec![
+            LayerDef::Conv2D { in_channels: 1, out_channels: 2, kernel_size: 3, stride: 1, padding: 1 },
+            LayerDef::MaxPool2D { kernel_size: 2, stride: 2 },
+            LayerDef::Flatten,
+            LayerDef::Linear { in_features: 8, out_features: 3 },
+        ]);
+        let mut compiled = net.compile(Box::new(CPUBackend)).unwrap();
+
+        let symbolic = Verifier::verify_symbolic(&compiled.graph).unwrap();
+        assert_eq!(symbolic[&compiled.output_node], vec![Dim::Batch(1), Dim::Fixed(3)]);
+
+        let sample = |k: f32| (0..16).map(|i| (i as f32 * 0.1 - k).sin()).collect::<Vec<f32>>();
+        let single = |compiled: &mut CompiledNetwork, k: f32| {
+            let feeds = HashMap::from([(SEQUENTIAL_INPUT.to_string(), Tensor::from_shape_vec(&[1, 1, 4, 4], sample(k)).unwrap())]);
+            compiled.run(&feeds).unwrap()[SEQUENTIAL_OUTPUT].as_slice().unwrap().to_vec()
+        };
+        let first = single(&mut compiled, 0.0);
+
+        // A batch of three gives each row its single-sample result
+        let batch: Vec<f32> = [0.0, 1.0, 2.0].iter().flat_map(|&k| sample(k)).collect();
+        let feeds = HashMap::from([(SEQUENTIAL_INPUT.to_string(), Tensor::from_shape_vec(&[3, 1, 4, 4], batch).unwrap())]);
+        let out = compiled.run(&feeds).unwrap().remove(SEQUENTIAL_OUTPUT).unwrap();
+        assert_eq!(out.shape(), &[3, 3]);
+        let rows = out.as_slice().unwrap();
+        for (k, row) in rows.chunks(3).enumerate() {
+            let expected = single(&mut compiled, k as f32);
+            for (a, b) in row.iter().zip(&expected) {
+                assert!((a - b).abs() < 1e-5);
+            }
+        }
+        assert_eq!(single(&mut compiled, 0.0), first);
+
+        // Only the batch dimension may change
+        let feeds = HashMap::from([(SEQUENTIAL_INPUT.to_string(), Tensor::new_zeros(&[2, 1, 4, 5]))]);
+        assert!(compiled.run(&feeds).is_err());
     }
 }
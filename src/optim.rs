@@ -11,12 +11,112 @@
 //!
 //! For backward compatibility, there is also a `step_graph` method that
 //! takes `&mut Graph` and delegates to the param store within.
+//!
+//! Internal state (momentum buffers, moment estimates, step counters) can be
+//! captured with [`Optimizer::save_state`] and restored with
+//! [`Optimizer::restore_state`] so training can resume exactly.
 
-use std::collections::HashMap;
-use crate::{Tensor, GPResult};
+use std::collections::{BTreeMap, HashMap};
+use serde::{Serialize, Deserialize};
+use crate::{Tensor, GPError, GPResult};
 use crate::params::{ParamStore, ParamId};
 use crate::graph::Graph;
 
+/// Serializable snapshot of an optimizer's internal state.
+///
+/// Per-parameter buffers are grouped into named slots (e.g. `"velocity"`,
+/// `"m"`, `"v"`) and keyed by [`ParamStore::key`], so a state survives
+/// inserting or reordering parameters just like a
+/// [`StateDict`](crate::params::StateDict).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OptimizerState {
+    /// Optimizer type name (e.g. `"SGD"`, `"Adam"`), checked on restore.
+    pub kind: String,
+    /// Current learning rate.
+    pub lr: f32,
+    /// Number of update steps taken so far (for bias correction etc.).
+    pub step: u64,
+    /// Scalar hyperparameters (momentum, betas, weight decay, ...).
+    pub hyperparams: BTreeMap<String, f32>,
+    /// Per-parameter buffers: slot name → parameter key → tensor.
+    pub slots: BTreeMap<String, BTreeMap<String, Tensor>>,
+}
+
+impl OptimizerState {
+    /// Creates an empty state of the given kind.
+    pub fn new(kind: &str, lr: f32) -> Self {
+        Self {
+            kind: kind.to_string(),
+            lr,
+            step: 0,
+            hyperparams: BTreeMap::new(),
+            slots: BTreeMap::new(),
+        }
+    }
+
+    /// Returns an error unless this state was produced by an optimizer of `kind`.
+    fn expect_kind(&self, kind: &str) -> GPResult<()> {
+        if self.kind != kind {
+            return Err(GPError::SerializationError(format!(
+                "Optimizer state is for '{}', cannot restore into '{}'", self.kind, kind
+            )));
+        }
+        Ok(())
+    }
+
+    fn hyperparam(&self, name: &str) -> GPResult<f32> {
+        self.hyperparams.get(name).copied().ok_or_else(|| GPError::SerializationError(format!(
+            "Optimizer state for '{}' is missing hyperparameter '{}'", self.kind, name
+        )))
+    }
+
+    /// Stores a per-parameter buffer map under `name` (skipped when empty),
+    /// keyed by the parameters' names in `params`.
+    fn insert_slot(&mut self, name: &str, buffers: &HashMap<usize, Tensor>, params: &ParamStore) -> GPResult<()> {
+        if buffers.is_empty() {
+            return Ok(());
+        }
+        let mut slot = BTreeMap::new();
+        for (&index, tensor) in buffers {
+            let key = params.key(ParamId(index));
+            if slot.insert(key.clone(), tensor.clone()).is_some() {
+                return Err(GPError::DuplicateParamName(key));
+            }
+        }
+        self.slots.insert(name.to_string(), slot);
+        Ok(())
+    }
+
+    /// Resolves the buffers of slot `name` to parameter indices in `params`.
+    ///
+    /// Every key must name a parameter of the same shape.
+    fn slot(&self, name: &str, params: &ParamStore) -> GPResult<HashMap<usize, Tensor>> {
+        let Some(buffers) = self.slots.get(name) else {
+            return Ok(HashMap::new());
+        };
+        let indices: HashMap<String, usize> = (0..params.len())
+            .map(|i| (params.key(ParamId(i)), i))
+            .collect();
+        let mut slot = HashMap::with_capacity(buffers.len());
+        for (key, tensor) in buffers {
+            let index = *indices.get(key).ok_or_else(|| GPError::SerializationError(format!(
+                "Optimizer slot '{}' references unknown parameter '{}'", name, key
+            )))?;
+            let expected = params.tensor(ParamId(index)).shape();
+            if tensor.shape() != expected {
+                return Err(GPError::ParamShapeMismatch {
+                    name: format!("{}/{}", name, key),
+                    expected: expected.to_vec(),
+                    found: tensor.shape().to_vec(),
+                });
+            }
+            slot.insert(index, tensor.clone());
+        }
+        Ok(slot)
+    }
+}
+
+
 /// Trait for parameter optimizers.
 ///
 /// Implementations should iterate over trainable parameters in the store,
@@ -35,6 +135,27 @@ pub trait Optimizer {
 
     /// Returns the current learning rate.
     fn get_lr(&self) -> f32;
+
+    /// Captures the full internal state for checkpointing, keying buffers by
+    /// the names of the parameters in `params`.
+    ///
+    /// The default records only the type name and learning rate; optimizers
+    /// with internal state override this and [`restore_state`](Self::restore_state).
+    fn save_state(&self, _params: &ParamStore) -> GPResult<OptimizerState> {
+        Ok(OptimizerState::new(std::any::type_name::<Self>(), self.get_lr()))
+    }
+
+    /// Restores internal state captured by [`save_state`](Self::save_state),
+    /// matching buffers to the parameters of `params` by name.
+    ///
+    /// Returns an error if the state belongs to a different optimizer type or
+    /// a buffer does not match a parameter. On error the optimizer is left
+    /// unchanged. The default returns [`GPError::NotImplemented`].
+    fn restore_state(&mut self, _state: &OptimizerState, _params: &ParamStore) -> GPResult<()> {
+        Err(GPError::NotImplemented(format!(
+            "restore_state for optimizer {}", std::any::type_name::<Self>()
+        )))
+    }
 }
 
 /// Stochastic Gradient Descent with optional momentum and weight decay.
@@ -126,6 +247,27 @@ impl Optimizer for SGD {
 
     fn set_lr(&mut self, lr: f32) { self.lr = lr; }
     fn get_lr(&self) -> f32 { self.lr }
+
+    fn save_state(&self, params: &ParamStore) -> GPResult<OptimizerState> {
+        let mut state = OptimizerState::new("SGD", self.lr);
+        state.hyperparams.insert("momentum".to_string(), self.momentum);
+        state.hyperparams.insert("weight_decay".to_string(), self.weight_decay);
+        state.insert_slot("velocity", &self.velocities, params)?;
+        Ok(state)
+    }
+
+    fn restore_state(&mut self, state: &OptimizerState, params: &ParamStore) -> GPResult<()> {
+        state.expect_kind("SGD")?;
+        let momentum = state.hyperparam("momentum")?;
+        let weight_decay = state.hyperparam("weight_decay")?;
+        let velocities = state.slot("velocity", params)?;
+
+        self.lr = state.lr;
+        self.momentum = momentum;
+        self.weight_decay = weight_decay;
+        self.velocities = velocities;
+        Ok(())
+    }
 }
 
 /// Adam optimizer (Adaptive Moment Estimation).
@@ -234,4 +376,35 @@ impl Optimizer for Adam {
 
     fn set_lr(&mut self, lr: f32) { self.lr = lr; }
     fn get_lr(&self) -> f32 { self.lr }
+
+    fn save_state(&self, params: &ParamStore) -> GPResult<OptimizerState> {
+        let mut state = OptimizerState::new("Adam", self.lr);
+        state.step = self.t as u64;
+        state.hyperparams.insert("beta1".to_string(), self.beta1);
+        state.hyperparams.insert("beta2".to_string(), self.beta2);
+        state.hyperparams.insert("epsilon".to_string(), self.epsilon);
+        state.hyperparams.insert("weight_decay".to_string(), self.weight_decay);
+        state.insert_slot("m", &self.m, params)?;
+        state.insert_slot("v", &self.v, params)?;
+        Ok(state)
+    }
+
+    fn restore_state(&mut self, state: &OptimizerState, params: &ParamStore) -> GPResult<()> {
+        state.expect_kind("Adam")?;
+        let beta1 = state.hyperparam("beta1")?;
+        let beta2 = state.hyperparam("beta2")?;
+        let epsilon = state.hyperparam("epsilon")?;
+        let weight_decay = state.hyperparam("weight_decay")?;
+        let (m, v) = (state.slot("m", params)?, state.slot("v", params)?);
+
+        self.lr = state.lr;
+        self.t = state.step as usize;
+        self.beta1 = beta1;
+        self.beta2 = beta2;
+        self.epsilon = epsilon;
+        self.weight_decay = weight_decay;
+        self.m = m;
+        self.v = v;
+        Ok(())
+    }
 }
@@ -1,6 +1,7 @@
 //! Parameter Store — Decoupled parameter management for computation graphs.
 //!
 //! `ParamStore` owns all trainable parameter tensors and their gradients,
+//! plus non-trainable buffers (e.g. BatchNorm running statistics),
 //! independent of the graph topology. This separation enables:
 //!
 //! - Clean weight export/import without graph internals access
@@ -27,11 +28,39 @@
 //! // Export/import as flat vector
 //! let flat = store.export_flat().unwrap();
 //! store.import_flat(&flat).unwrap();
+//!
+//! // Export/import by name (robust to registration order)
+//! let dict = store.state_dict().unwrap();
+//! store.load_state_dict(&dict, true).unwrap();
 //! ```
 
+use std::collections::{BTreeMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
 use serde::{Serialize, Deserialize};
 use crate::{Tensor, GPError, GPResult};
 
+/// Named parameter checkpoint: maps parameter keys to tensor values.
+///
+/// Keys are the names given at registration time (see [`ParamStore::key`]).
+/// A `BTreeMap` keeps the serialized form deterministic.
+pub type StateDict = BTreeMap<String, Tensor>;
+
+/// Outcome of a non-strict [`ParamStore::load_state_dict`] call.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadReport {
+    /// Keys present in the store but absent from the state dict (left untouched).
+    pub missing_keys: Vec<String>,
+    /// Keys present in the state dict but unknown to the store (ignored).
+    pub unexpected_keys: Vec<String>,
+}
+
+impl LoadReport {
+    /// Returns true if every key matched exactly.
+    pub fn is_exact(&self) -> bool {
+        self.missing_keys.is_empty() && self.unexpected_keys.is_empty()
+    }
+}
+
 /// Unique identifier for a parameter within a [`ParamStore`].
 ///
 /// Distinct from [`NodeId`] to prevent accidental misuse — a `ParamId`
@@ -46,6 +75,10 @@ struct ParamMeta {
     name: String,
     /// If true, this parameter will not be updated by optimizers.
     frozen: bool,
+    /// If true, this is a non-trainable buffer: it never receives gradients,
+    /// is skipped by optimizers and flat export, but is part of the state dict.
+    #[serde(default)]
+    buffer: bool,
 }
 
 /// Centralized store for all trainable parameters and their gradients.
@@ -61,6 +94,16 @@ pub struct ParamStore {
     #[serde(skip)]
     gradients: Vec<Option<Tensor>>,
     meta: Vec<ParamMeta>,
+    /// Process-unique identity, so holders of a `ParamId` can tell which
+    /// store it was issued by. Not persisted.
+    #[serde(skip, default = "next_store_uid")]
+    uid: u64,
+}
+
+static NEXT_STORE_UID: AtomicU64 = AtomicU64::new(1);
+
+fn next_store_uid() -> u64 {
+    NEXT_STORE_UID.fetch_add(1, Ordering::Relaxed)
 }
 
 impl ParamStore {
@@ -70,15 +113,26 @@ impl ParamStore {
             tensors: Vec::new(),
             gradients: Vec::new(),
             meta: Vec::new(),
+            uid: next_store_uid(),
         }
     }
 
+    /// Process-unique identity of this store (fresh after deserialization).
+    ///
+    /// Layers use it to check that `ParamId`s they bound earlier belong to
+    /// the graph they are being called on.
+    pub fn uid(&self) -> u64 {
+        self.uid
+    }
+
     /// Registers a new parameter tensor and returns its identifier.
     ///
     /// # Arguments
     ///
     /// * `tensor` - Initial parameter values.
     /// * `name` - Human-readable name for debugging (e.g., "layer1.weight").
+    ///   Empty for an unnamed parameter; names starting with `param.` are
+    ///   reserved for unnamed parameters' keys and rejected by the state dict.
     pub fn register(&mut self, tensor: Tensor, name: &str) -> ParamId {
         let id = ParamId(self.tensors.len());
         self.tensors.push(tensor);
@@ -86,10 +140,27 @@ impl ParamStore {
         self.meta.push(ParamMeta {
             name: name.to_string(),
             frozen: false,
+            buffer: false,
         });
         id
     }
 
+    /// Registers a non-trainable buffer (e.g. a running mean) and returns its identifier.
+    ///
+    /// Buffers live alongside parameters so graph nodes can read them, and are
+    /// persisted by [`state_dict`](Self::state_dict), but they never receive
+    /// gradients and are excluded from optimizers and [`export_flat`](Self::export_flat).
+    pub fn register_buffer(&mut self, tensor: Tensor, name: &str) -> ParamId {
+        let id = self.register(tensor, name);
+        self.meta[id.0].buffer = true;
+        id
+    }
+
+    /// Returns true if the entry is a non-trainable buffer.
+    pub fn is_buffer(&self, id: ParamId) -> bool {
+        self.meta.get(id.0).is_some_and(|m| m.buffer)
+    }
+
     /// Returns the number of registered parameters.
     pub fn len(&self) -> usize {
         self.tensors.len()
@@ -200,6 +271,30 @@ impl ParamStore {
         self.meta.get(id.0).map_or("unknown", |m| m.name.as_str())
     }
 
+    /// Returns the checkpoint key of a parameter.
+    ///
+    /// This is the registration name, or `param.{index}` for unnamed parameters.
+    pub fn key(&self, id: ParamId) -> String {
+        match self.meta.get(id.0) {
+            Some(m) if !m.name.is_empty() => m.name.clone(),
+            _ => format!("param.{}", id.0),
+        }
+    }
+
+    /// Returns the checkpoint key of a parameter, rejecting names that could
+    /// collide with the `param.{index}` key of an unnamed parameter.
+    fn checked_key(&self, id: ParamId) -> GPResult<String> {
+        match self.meta.get(id.0) {
+            Some(m) if m.name.starts_with("param.") => Err(GPError::ReservedParamName(m.name.clone())),
+            _ => Ok(self.key(id)),
+        }
+    }
+
+    /// Looks up a parameter by its checkpoint key.
+    pub fn find(&self, key: &str) -> Option<ParamId> {
+        (0..self.len()).map(ParamId).find(|&id| self.key(id) == key)
+    }
+
     // ── Iteration ──────────────────────────────────────────────────────────
 
     /// Iterates over all (id, tensor) pairs.
@@ -214,11 +309,13 @@ impl ParamStore {
 
     /// Returns indices of all trainable parameters that have accumulated gradients.
     ///
+    /// Frozen parameters and buffers are excluded.
+    ///
     /// This is the primary interface for optimizers: iterate the returned IDs
     /// and call `tensor_mut()` / `gradient()` for each.
     pub fn trainable_param_ids(&self) -> Vec<ParamId> {
         (0..self.tensors.len())
-            .filter(|&i| !self.meta[i].frozen && self.gradients[i].is_some())
+            .filter(|&i| !self.meta[i].frozen && !self.meta[i].buffer && self.gradients[i].is_some())
             .map(ParamId)
             .collect()
     }
@@ -243,11 +340,12 @@ impl ParamStore {
     /// Exports all parameter values as a single flat `Vec<f32>`.
     ///
     /// Parameters are exported in registration order. The caller must know
-    /// the architecture to correctly interpret the flat vector.
+    /// the architecture to correctly interpret the flat vector. Buffers are
+    /// not weights and are skipped; use [`state_dict`](Self::state_dict) to
+    /// persist them.
     pub fn export_flat(&self) -> GPResult<Vec<f32>> {
-        let total: usize = self.tensors.iter().map(|t| t.len()).sum();
-        let mut weights = Vec::with_capacity(total);
-        for tensor in &self.tensors {
+        let mut weights = Vec::with_capacity(self.total_params());
+        for (_, tensor) in self.weights() {
             let slice = tensor.as_slice()?;
             weights.extend_from_slice(slice);
         }
@@ -257,9 +355,9 @@ impl ParamStore {
     /// Imports parameter values from a flat `Vec<f32>`.
     ///
     /// The flat vector must contain exactly the right number of values
-    /// for all parameters in registration order.
+    /// for all parameters in registration order. Buffers are left untouched.
     pub fn import_flat(&mut self, weights: &[f32]) -> GPResult<()> {
-        let total: usize = self.tensors.iter().map(|t| t.len()).sum();
+        let total = self.total_params();
         if weights.len() != total {
             return Err(GPError::WeightLengthMismatch {
                 expected: total,
@@ -268,7 +366,10 @@ impl ParamStore {
         }
 
         let mut offset = 0;
-        for tensor in &mut self.tensors {
+        for (tensor, meta) in self.tensors.iter_mut().zip(&self.meta) {
+            if meta.buffer {
+                continue;
+            }
             let count = tensor.len();
             let slice = &weights[offset..offset + count];
             let shape = tensor.shape().to_vec();
@@ -279,9 +380,104 @@ impl ParamStore {
         Ok(())
     }
 
+    // ── Serialization (Named State Dict) ───────────────────────────────────
+
+    /// Exports all parameters as a [`StateDict`] keyed by [`key`](Self::key).
+    ///
+    /// Unlike [`export_flat`](Self::export_flat), the result does not depend on
+    /// registration order, so it survives inserting or reordering layers.
+    ///
+    /// Returns [`GPError::DuplicateParamName`] if two parameters share a key,
+    /// and [`GPError::ReservedParamName`] for a name starting with `param.`.
+    pub fn state_dict(&self) -> GPResult<StateDict> {
+        let mut dict = StateDict::new();
+        for (id, tensor) in self.iter() {
+            let key = self.checked_key(id)?;
+            if dict.insert(key.clone(), tensor.clone()).is_some() {
+                return Err(GPError::DuplicateParamName(key));
+            }
+        }
+        Ok(dict)
+    }
+
+    /// Loads parameter values from a [`StateDict`] by name.
+    ///
+    /// * `strict = true` — every store key must be present in `dict` and vice
+    ///   versa, otherwise [`GPError::MissingKeys`] / [`GPError::UnexpectedKeys`]
+    ///   is returned.
+    /// * `strict = false` — matching keys are loaded; missing and unexpected
+    ///   keys are reported in the returned [`LoadReport`].
+    ///
+    /// Shape mismatches are always an error ([`GPError::ParamShapeMismatch`]).
+    /// All checks run before any tensor is written, so a failed load leaves
+    /// the store unchanged.
+    pub fn load_state_dict(&mut self, dict: &StateDict, strict: bool) -> GPResult<LoadReport> {
+        let (report, matched) = self.match_state_dict(dict, strict)?;
+        for (id, src) in matched {
+            self.tensors[id.0].copy_from(src)?;
+        }
+        Ok(report)
+    }
+
+    /// Runs the checks of [`load_state_dict`](Self::load_state_dict) without
+    /// writing anything, returning the report a load would produce.
+    pub fn check_state_dict(&self, dict: &StateDict, strict: bool) -> GPResult<LoadReport> {
+        self.match_state_dict(dict, strict).map(|(report, _)| report)
+    }
+
+    /// Matches `dict` entries to parameters by key, validating shapes and
+    /// (if `strict`) that the key sets agree.
+    fn match_state_dict<'a>(&self, dict: &'a StateDict, strict: bool) -> GPResult<(LoadReport, Vec<(ParamId, &'a Tensor)>)> {
+        let mut report = LoadReport::default();
+        let mut matched = Vec::new();
+        let mut seen = HashSet::new();
+
+        for i in 0..self.len() {
+            let id = ParamId(i);
+            let key = self.checked_key(id)?;
+            if !seen.insert(key.clone()) {
+                return Err(GPError::DuplicateParamName(key));
+            }
+            match dict.get(&key) {
+                Some(src) => {
+                    if src.shape() != self.tensors[i].shape() {
+                        return Err(GPError::ParamShapeMismatch {
+                            name: key,
+                            expected: self.tensors[i].shape().to_vec(),
+                            found: src.shape().to_vec(),
+                        });
+                    }
+                    matched.push((id, src));
+                }
+                None => report.missing_keys.push(key),
+            }
+        }
+        report.unexpected_keys = dict.keys()
+            .filter(|k| !seen.contains(k.as_str()))
+            .cloned()
+            .collect();
+
+        if strict {
+            if !report.missing_keys.is_empty() {
+                return Err(GPError::MissingKeys(report.missing_keys));
+            }
+            if !report.unexpected_keys.is_empty() {
+                return Err(GPError::UnexpectedKeys(report.unexpected_keys));
+            }
+        }
+        Ok((report, matched))
+    }
+
     /// Returns the total number of scalar parameters across all tensors.
+    ///
+    /// Buffers are not counted.
     pub fn total_params(&self) -> usize {
-        self.tensors.iter().map(|t| t.len()).sum()
+        self.weights().map(|(_, t)| t.len()).sum()
+    }
+
+    /// Iterates over (id, tensor) pairs of parameters, skipping buffers.
+    fn weights(&self) -> impl Iterator<Item = (ParamId, &Tensor)> {
+        self.iter().filter(|(id, _)| !self.meta[id.0].buffer)
     }
 
     /// Returns the sum of absolute gradient values for each parameter.
@@ -315,6 +511,8 @@ impl Clone for ParamStore {
             tensors: self.tensors.clone(),
             gradients: self.gradients.clone(),
             meta: self.meta.clone(),
+            // A clone is a distinct store: bindings to the original don't carry over.
+            uid: next_store_uid(),
         }
     }
 }
@@ -554,6 +752,110 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_key_and_find() {
+        let mut store = make_store();
+        let unnamed = store.register(Tensor::new_zeros(&[1]), "");
+        assert_eq!(store.key(ParamId(1)), "layer1.bias");
+        assert_eq!(store.key(unnamed), "param.4");
+        assert_eq!(store.find("layer2.weight"), Some(ParamId(2)));
+        assert_eq!(store.find("param.4"), Some(unnamed));
+        assert_eq!(store.find("nope"), None);
+    }
+
+    #[test]
+    fn test_state_dict_roundtrip_across_registration_order() {
+        let mut src = make_store();
+        src.tensor_mut(ParamId(2)).as_slice_mut().unwrap()[0] = 7.0;
+        let dict = src.state_dict().unwrap();
+        assert_eq!(dict.len(), 4);
+
+        // Same parameters, different registration order
+        let mut dst = ParamStore::new();
+        dst.register(Tensor::new_zeros(&[3, 4]), "layer2.weight");
+        dst.register(Tensor::new_zeros(&[1, 4]), "layer2.bias");
+        dst.register(Tensor::new_zeros(&[2, 3]), "layer1.weight");
+        dst.register(Tensor::new_zeros(&[1, 3]), "layer1.bias");
+
+        let report = dst.load_state_dict(&dict, true).unwrap();
+        assert!(report.is_exact());
+        assert_eq!(dst.tensor(ParamId(0)).as_slice().unwrap()[0], 7.0);
+    }
+
+    #[test]
+    fn test_state_dict_duplicate_names() {
+        let mut store = ParamStore::new();
+        store.register(Tensor::new_zeros(&[1]), "w");
+        store.register(Tensor::new_zeros(&[1]), "w");
+        assert!(matches!(store.state_dict(), Err(GPError::DuplicateParamName(k)) if k == "w"));
+    }
+
+    #[test]
+    fn test_state_dict_rejects_reserved_prefix() {
+        let mut store = ParamStore::new();
+        store.register(Tensor::new_zeros(&[1]), "");
+        store.register(Tensor::new_zeros(&[1]), "param.0");
+        assert!(matches!(store.state_dict(), Err(GPError::ReservedParamName(k)) if k == "param.0"));
+        let dict = StateDict::new();
+        assert!(matches!(store.load_state_dict(&dict, false), Err(GPError::ReservedParamName(_))));
+    }
+
+    #[test]
+    fn test_load_state_dict_strict_missing_and_unexpected() {
+        let mut store = make_store();
+        let mut dict = store.state_dict().unwrap();
+        dict.remove("layer2.bias");
+        let err = store.load_state_dict(&dict, true).unwrap_err();
+        assert!(matches!(err, GPError::MissingKeys(ref k) if k == &["layer2.bias".to_string()]));
+
+        let mut dict = store.state_dict().unwrap();
+        dict.insert("extra".to_string(), Tensor::new_zeros(&[1]));
+        let err = store.load_state_dict(&dict, true).unwrap_err();
+        assert!(matches!(err, GPError::UnexpectedKeys(ref k) if k == &["extra".to_string()]));
+    }
+
+    #[test]
+    fn test_load_state_dict_non_strict_partial() {
+        let mut store = make_store();
+        let mut dict = StateDict::new();
+        dict.insert("layer1.weight".to_string(), Tensor::from_elem(&[2, 3], 3.0));
+        dict.insert("head.weight".to_string(), Tensor::from_elem(&[4, 1], 1.0));
+
+        let report = store.load_state_dict(&dict, false).unwrap();
+        assert_eq!(report.unexpected_keys, vec!["head.weight".to_string()]);
+        assert_eq!(report.missing_keys.len(), 3);
+        assert_eq!(store.tensor(ParamId(0)).as_slice().unwrap(), &[3.0; 6]);
+    }
+
+    #[test]
+    fn test_load_state_dict_shape_mismatch_is_atomic() {
+        let mut store = make_store();
+        let mut dict = StateDict::new();
+        dict.insert("layer1.weight".to_string(), Tensor::from_elem(&[2, 3], 5.0));
+        dict.insert("layer2.weight".to_string(), Tensor::from_elem(&[4, 3], 1.0));
+
+        let err = store.load_state_dict(&dict, false).unwrap_err();
+        match err {
+            GPError::ParamShapeMismatch { name, expected, found } => {
+                assert_eq!(name, "layer2.weight");
+                assert_eq!(expected, vec![3, 4]);
+                assert_eq!(found, vec![4, 3]);
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        // Nothing was written
+        assert_eq!(store.tensor(ParamId(0)).as_slice().unwrap(), &[0.0; 6]);
+    }
+
+    #[test]
+    fn test_state_dict_json_roundtrip() {
+        let store = make_store();
+        let json = serde_json::to_string(&store.state_dict().unwrap()).unwrap();
+        let dict: StateDict = serde_json::from_str(&json).unwrap();
+        let mut other = make_store();
+        assert!(other.load_state_dict(&dict, true).unwrap().is_exact());
+    }
+
     #[test]
     fn test_serialization_roundtrip() {
         let mut store = make_store();
@@ -572,4 +874,31 @@ mod tests {
         // Gradients are skipped in serialization
         assert!(restored.gradient(ParamId(0)).is_none());
     }
+
+    #[test]
+    fn test_buffers_are_persisted_but_not_trained() {
+        let mut store = ParamStore::new();
+        let w = store.register(Tensor::new_ones(&[1, 2]), "bn.gamma");
+        let rm = store.register_buffer(Tensor::from_elem(&[1, 2], 7.0), "bn.running_mean");
+        assert!(store.is_buffer(rm));
+        assert!(!store.is_buffer(w));
+
+        // Flat export covers weights only
+        assert_eq!(store.total_params(), 2);
+        assert_eq!(store.export_flat().unwrap(), vec![1.0, 1.0]);
+        store.import_flat(&[2.0, 3.0]).unwrap();
+        assert_eq!(store.tensor(rm).as_slice().unwrap(), &[7.0, 7.0]);
+
+        // Even with a gradient, a buffer is not trainable
+        store.accumulate_gradient(w, Tensor::new_ones(&[1, 2])).unwrap();
+        store.accumulate_gradient(rm, Tensor::new_ones(&[1, 2])).unwrap();
+        assert_eq!(store.trainable_param_ids(), vec![w]);
+
+        // The state dict includes buffers, and the flag survives serde
+        let dict = store.state_dict().unwrap();
+        assert_eq!(dict["bn.running_mean"].as_slice().unwrap(), &[7.0, 7.0]);
+        let json = serde_json::to_string(&store).unwrap();
+        let restored: ParamStore = serde_json::from_str(&json).unwrap();
+        assert!(restored.is_buffer(rm));
+    }
 }
@@ -0,0 +1,396 @@
+//! Safetensors reader and writer for parameter checkpoints.
+//!
+//! Implements the [safetensors](https://github.com/huggingface/safetensors)
+//! container format so that [`ParamStore`] weights can be exchanged with
+//! Python tooling without going through the (large, slow) serde JSON path.
+//!
+//! # Layout
+//!
+//! ```text
+//! [u64 LE: header size N][N bytes: JSON header][raw tensor bytes]
+//! ```
+//!
+//! The header maps each tensor name to `{ "dtype", "shape", "data_offsets" }`,
+//! with offsets relative to the start of the byte buffer. An optional
+//! `"__metadata__"` entry holds free-form string pairs. The header is padded
+//! with spaces so the byte buffer starts on an 8-byte boundary, which keeps
+//! the payload memory-mappable.
+//!
+//! Reading always goes through a byte slice and copies each tensor into an
+//! owned `f32` buffer. There is no memory-mapped entry point: a caller can map
+//! the file itself and pass the mapped slice to [`deserialize`], which has no
+//! alignment requirements of its own.
+//!
+//! Tensors are always written as little-endian `F32`. `F32`, `F64`, `F16` and
+//! `BF16` payloads are accepted on read and converted to `f32`.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use serde::{Serialize, Deserialize};
+use crate::{GPError, GPResult, Tensor};
+use crate::params::{ParamStore, StateDict, LoadReport};
+
+/// Key reserved by the format for free-form metadata.
+const METADATA_KEY: &str = "__metadata__";
+
+/// Upper bound on the header size, guarding against corrupt length prefixes.
+const MAX_HEADER_SIZE: usize = 100_000_000;
+
+/// Free-form string metadata stored in the `__metadata__` header entry.
+pub type Metadata = BTreeMap<String, String>;
+
+/// Header entry describing a single tensor.
+#[derive(Serialize, Deserialize, Debug)]
+struct TensorInfo {
+    dtype: String,
+    shape: Vec<usize>,
+    data_offsets: (usize, usize),
+}
+
+/// Serializes a [`StateDict`] (plus optional metadata) into safetensors bytes.
+pub fn serialize(dict: &StateDict, metadata: Option<&Metadata>) -> GPResult<Vec<u8>> {
+    let mut header = serde_json::Map::new();
+    if let Some(meta) = metadata {
+        let value = serde_json::to_value(meta)
+            .map_err(|e| GPError::SerializationError(e.to_string()))?;
+        header.insert(METADATA_KEY.to_string(), value);
+    }
+
+    let mut offset = 0;
+    for (name, tensor) in dict {
+        if name == METADATA_KEY {
+            return Err(GPError::SerializationError(format!(
+                "Tensor name '{}' is reserved by the safetensors format", METADATA_KEY
+            )));
+        }
+        let size = tensor.len() * 4;
+        let info = TensorInfo {
+            dtype: "F32".to_string(),
+            shape: tensor.shape().to_vec(),
+            data_offsets: (offset, offset + size),
+        };
+        let value = serde_json::to_value(&info)
+            .map_err(|e| GPError::SerializationError(e.to_string()))?;
+        header.insert(name.clone(), value);
+        offset += size;
+    }
+
+    let mut header_bytes = serde_json::to_vec(&header)
+        .map_err(|e| GPError::SerializationError(e.to_string()))?;
+    // Pad with spaces so the payload is 8-byte aligned.
+    while (8 + header_bytes.len()) % 8 != 0 {
+        header_bytes.push(b' ');
+    }
+
+    let mut out = Vec::with_capacity(8 + header_bytes.len() + offset);
+    out.extend_from_slice(&(header_bytes.len() as u64).to_le_bytes());
+    out.extend_from_slice(&header_bytes);
+    for tensor in dict.values() {
+        // Contiguous copy; `as_slice` fails for non-standard layouts.
+        for v in tensor.to_host()?.as_slice()? {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+    Ok(out)
+}
+
+/// Parses safetensors bytes into a [`StateDict`] and its metadata.
+///
+/// Validates the header, dtypes, shapes and that data offsets are in bounds
+/// and consistent with each tensor's element count. The tensors must tile
+/// the payload exactly: no overlaps, no gaps, and nothing after the last one.
+pub fn deserialize(bytes: &[u8]) -> GPResult<(StateDict, Metadata)> {
+    if bytes.len() < 8 {
+        return Err(GPError::SerializationError(
+            "Safetensors buffer too small for header length".to_string()
+        ));
+    }
+    let mut len_bytes = [0u8; 8];
+    len_bytes.copy_from_slice(&bytes[..8]);
+    let header_len = u64::from_le_bytes(len_bytes) as usize;
+    if header_len > MAX_HEADER_SIZE || 8 + header_len > bytes.len() {
+        return Err(GPError::SerializationError(format!(
+            "Invalid safetensors header length {} (buffer has {} bytes)", header_len, bytes.len()
+        )));
+    }
+
+    let header: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_slice(&bytes[8..8 + header_len])
+            .map_err(|e| GPError::SerializationError(format!("Invalid safetensors header: {}", e)))?;
+    let data = &bytes[8 + header_len..];
+
+    let mut dict = StateDict::new();
+    let mut metadata = Metadata::new();
+    let mut ranges = Vec::new();
+    for (name, value) in header {
+        if name == METADATA_KEY {
+            metadata = serde_json::from_value(value)
+                .map_err(|e| GPError::SerializationError(format!("Invalid metadata: {}", e)))?;
+            continue;
+        }
+        let info: TensorInfo = serde_json::from_value(value)
+            .map_err(|e| GPError::SerializationError(format!("Invalid entry '{}': {}", name, e)))?;
+        let tensor = decode_tensor(&name, &info, data)?;
+        ranges.push((info.data_offsets, name.clone()));
+        dict.insert(name, tensor);
+    }
+    check_layout(ranges, data.len())?;
+    Ok((dict, metadata))
+}
+
+/// Checks that the tensors' byte ranges, taken in offset order, cover
+/// `[0, payload_len)` back to back.
+fn check_layout(mut ranges: Vec<((usize, usize), String)>, payload_len: usize) -> GPResult<()> {
+    ranges.sort();
+    let mut expected = 0;
+    for ((start, end), name) in ranges {
+        if start != expected {
+            return Err(GPError::SerializationError(format!(
+                "Tensor '{}': data starts at {} but the previous tensor ends at {} ({})",
+                name, start, expected, if start < expected { "overlap" } else { "gap" }
+            )));
+        }
+        expected = end;
+    }
+    if expected != payload_len {
+        return Err(GPError::SerializationError(format!(
+            "Safetensors payload has {} bytes but the tensors cover {}", payload_len, expected
+        )));
+    }
+    Ok(())
+}
+
+/// Decodes one tensor's payload into an `f32` tensor.
+fn decode_tensor(name: &str, info: &TensorInfo, data: &[u8]) -> GPResult<Tensor> {
+    let elem_size = match info.dtype.as_str() {
+        "F32" => 4,
+        "F64" => 8,
+        "F16" | "BF16" => 2,
+        other => return Err(GPError::NotImplemented(format!(
+            "safetensors dtype {} (tensor '{}')", other, name
+        ))),
+    };
+    let (start, end) = info.data_offsets;
+    // Untrusted header: shapes whose byte size overflows are rejected
+    let size = info.shape.iter()
+        .try_fold(elem_size, |acc: usize, &dim| acc.checked_mul(dim))
+        .ok_or_else(|| GPError::SerializationError(format!(
+            "Tensor '{}': shape {:?} overflows the addressable size", name, info.shape
+        )))?;
+    if start > end || end > data.len() || end - start != size {
+        return Err(GPError::SerializationError(format!(
+            "Tensor '{}': data offsets [{}, {}) invalid for shape {:?} ({} payload bytes)",
+            name, start, end, info.shape, data.len()
+        )));
+    }
+
+    let raw = &data[start..end];
+    let values: Vec<f32> = match info.dtype.as_str() {
+        "F32" => raw.chunks_exact(4)
+            .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect(),
+        "F64" => raw.chunks_exact(8)
+            .map(|c| {
+                let mut b = [0u8; 8];
+                b.copy_from_slice(c);
+                f64::from_le_bytes(b) as f32
+            })
+            .collect(),
+        "F16" => raw.chunks_exact(2)
+            .map(|c| f16_to_f32(u16::from_le_bytes([c[0], c[1]])))
+            .collect(),
+        // BF16 is the upper half of an f32.
+        _ => raw.chunks_exact(2)
+            .map(|c| f32::from_bits((u16::from_le_bytes([c[0], c[1]]) as u32) << 16))
+            .collect(),
+    };
+    Tensor::from_shape_vec(&info.shape, values)
+}
+
+/// Converts an IEEE 754 half-precision value to `f32`.
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = ((bits >> 15) as u32) << 31;
+    let exp = ((bits >> 10) & 0x1f) as u32;
+    let mant = (bits & 0x3ff) as u32;
+    let out = match (exp, mant) {
+        (0, 0) => sign,
+        (0, _) => {
+            // Subnormal: value = mant * 2^-24
+            let v = mant as f32 * (1.0 / (1u32 << 24) as f32);
+            return if sign != 0 { -v } else { v };
+        }
+        (0x1f, _) => sign | 0x7f80_0000 | (mant << 13),
+        _ => sign | ((exp + 127 - 15) << 23) | (mant << 13),
+    };
+    f32::from_bits(out)
+}
+
+// ── ParamStore Integration ─────────────────────────────────────────────────
+
+impl ParamStore {
+    /// Encodes all parameters as safetensors bytes, keyed by [`ParamStore::key`].
+    pub fn to_safetensors(&self, metadata: Option<&Metadata>) -> GPResult<Vec<u8>> {
+        serialize(&self.state_dict()?, metadata)
+    }
+
+    /// Loads parameters from safetensors bytes by name.
+    ///
+    /// See [`ParamStore::load_state_dict`] for the meaning of `strict`.
+    pub fn load_safetensors(&mut self, bytes: &[u8], strict: bool) -> GPResult<LoadReport> {
+        let (dict, _) = deserialize(bytes)?;
+        self.load_state_dict(&dict, strict)
+    }
+
+    /// Writes all parameters to a `.safetensors` file.
+    pub fn save_safetensors_file<P: AsRef<Path>>(&self, path: P, metadata: Option<&Metadata>) -> GPResult<()> {
+        std::fs::write(path, self.to_safetensors(metadata)?)?;
+        Ok(())
+    }
+
+    /// Reads parameters from a `.safetensors` file.
+    pub fn load_safetensors_file<P: AsRef<Path>>(&mut self, path: P, strict: bool) -> GPResult<LoadReport> {
+        let bytes = std::fs::read(path)?;
+        self.load_safetensors(&bytes, strict)
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParamId;
+
+    fn make_store() -> ParamStore {
+        let mut store = ParamStore::new();
+        store.register(Tensor::from_shape_vec(&[2, 3], vec![1.0, -2.0, 3.5, 0.0, 1e-3, -7.25]).unwrap(), "fc.weight");
+        store.register(Tensor::from_shape_vec(&[1, 3], vec![0.5, 0.25, -0.125]).unwrap(), "fc.bias");
+        store
+    }
+
+    #[test]
+    fn test_roundtrip_bytes() {
+        let src = make_store();
+        let mut meta = Metadata::new();
+        meta.insert("format".to_string(), "pt".to_string());
+        let bytes = src.to_safetensors(Some(&meta)).unwrap();
+
+        let (dict, restored_meta) = deserialize(&bytes).unwrap();
+        assert_eq!(restored_meta.get("format").map(String::as_str), Some("pt"));
+        assert_eq!(dict["fc.weight"].shape(), &[2, 3]);
+
+        let mut dst = ParamStore::new();
+        dst.register(Tensor::new_zeros(&[1, 3]), "fc.bias");
+        dst.register(Tensor::new_zeros(&[2, 3]), "fc.weight");
+        assert!(dst.load_safetensors(&bytes, true).unwrap().is_exact());
+        assert_eq!(dst.tensor(ParamId(1)), src.tensor(ParamId(0)));
+        assert_eq!(dst.tensor(ParamId(0)), src.tensor(ParamId(1)));
+    }
+
+    #[test]
+    fn test_layout_is_aligned_and_compact() {
+        let bytes = make_store().to_safetensors(None).unwrap();
+        let header_len = u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize;
+        assert_eq!((8 + header_len) % 8, 0);
+        // 9 f32 values of payload
+        assert_eq!(bytes.len() - 8 - header_len, 9 * 4);
+    }
+
+    #[test]
+    fn test_file_roundtrip() {
+        let path = std::env::temp_dir().join(format!("gp_params_{}.safetensors", std::process::id()));
+        let src = make_store();
+        src.save_safetensors_file(&path, None).unwrap();
+
+        let mut dst = make_store();
+        dst.tensor_mut(ParamId(0)).scale_inplace(0.0).unwrap();
+        dst.load_safetensors_file(&path, true).unwrap();
+        assert_eq!(dst.tensor(ParamId(0)), src.tensor(ParamId(0)));
+        std::fs::remove_file(&path).ok();
+    }
+
+    /// Hand-built buffer in the layout written by the Python `safetensors` package.
+    fn foreign_buffer(dtype: &str, payload: &[u8], shape: &[usize]) -> Vec<u8> {
+        let header = format!(
+            r#"{{"__metadata__":{{"format":"pt"}},"w":{{"dtype":"{}","shape":{:?},"data_offsets":[0,{}]}}}}"#,
+            dtype, shape, payload.len()
+        );
+        let mut out = (header.len() as u64).to_le_bytes().to_vec();
+        out.extend_from_slice(header.as_bytes());
+        out.extend_from_slice(payload);
+        out
+    }
+
+    #[test]
+    fn test_read_half_precision_dtypes() {
+        // 1.0, -2.0, 0.5 in F16
+        let f16: Vec<u8> = [0x3c00u16, 0xc000, 0x3800].iter().flat_map(|v| v.to_le_bytes()).collect();
+        let (dict, _) = deserialize(&foreign_buffer("F16", &f16, &[3])).unwrap();
+        assert_eq!(dict["w"].as_slice().unwrap(), &[1.0, -2.0, 0.5]);
+
+        // 1.0, -2.0, 0.5 in BF16
+        let bf16: Vec<u8> = [0x3f80u16, 0xc000, 0x3f00].iter().flat_map(|v| v.to_le_bytes()).collect();
+        let (dict, _) = deserialize(&foreign_buffer("BF16", &bf16, &[1, 3])).unwrap();
+        assert_eq!(dict["w"].as_slice().unwrap(), &[1.0, -2.0, 0.5]);
+
+        let f64s: Vec<u8> = [1.5f64, -0.25].iter().flat_map(|v| v.to_le_bytes()).collect();
+        let (dict, _) = deserialize(&foreign_buffer("F64", &f64s, &[2])).unwrap();
+        assert_eq!(dict["w"].as_slice().unwrap(), &[1.5, -0.25]);
+    }
+
+    #[test]
+    fn test_rejects_corrupt_buffers() {
+        assert!(deserialize(&[1, 2, 3]).is_err());
+
+        // Header length larger than the buffer
+        let mut bytes = make_store().to_safetensors(None).unwrap();
+        bytes[..8].copy_from_slice(&(u64::MAX).to_le_bytes());
+        assert!(deserialize(&bytes).is_err());
+
+        // Offsets don't match the shape
+        let bad = foreign_buffer("F32", &[0u8; 8], &[3]);
+        assert!(matches!(deserialize(&bad), Err(GPError::SerializationError(_))));
+
+        // Shape whose byte size overflows usize
+        let huge = foreign_buffer("F32", &[0u8; 8], &[usize::MAX / 2, 4]);
+        assert!(matches!(deserialize(&huge), Err(GPError::SerializationError(_))));
+
+        // Unsupported dtype
+        let int = foreign_buffer("I64", &[0u8; 8], &[1]);
+        assert!(matches!(deserialize(&int), Err(GPError::NotImplemented(_))));
+
+        // Trailing bytes after the last tensor
+        let trailing = foreign_buffer("F32", &[0u8; 8], &[1]);
+        assert!(matches!(deserialize(&trailing), Err(GPError::SerializationError(_))));
+    }
+
+    /// A buffer holding two one-element F32 tensors at the given offsets.
+    fn two_tensor_buffer(a: (usize, usize), b: (usize, usize), payload_len: usize) -> Vec<u8> {
+        let header = format!(
+            r#"{{"a":{{"dtype":"F32","shape":[1],"data_offsets":[{},{}]}},"b":{{"dtype":"F32","shape":[1],"data_offsets":[{},{}]}}}}"#,
+            a.0, a.1, b.0, b.1
+        );
+        let mut out = (header.len() as u64).to_le_bytes().to_vec();
+        out.extend_from_slice(header.as_bytes());
+        out.extend(vec![0u8; payload_len]);
+        out
+    }
+
+    #[test]
+    fn test_rejects_malformed_data_offsets() {
+        // Out of order in the header is fine as long as the ranges tile the payload
+        assert!(deserialize(&two_tensor_buffer((4, 8), (0, 4), 8)).is_ok());
+
+        // Overlapping ranges
+        let overlap = two_tensor_buffer((0, 4), (0, 4), 8);
+        assert!(matches!(deserialize(&overlap), Err(GPError::SerializationError(_))));
+
+        // A gap between the tensors
+        let gap = two_tensor_buffer((0, 4), (8, 12), 12);
+        assert!(matches!(deserialize(&gap), Err(GPError::SerializationError(_))));
+
+        // A gap before the first tensor
+        let leading = two_tensor_buffer((4, 8), (8, 12), 12);
+        assert!(matches!(deserialize(&leading), Err(GPError::SerializationError(_))));
+    }
+}
@@ -17,8 +17,36 @@
 //! // scheduler.step_optimizer(&mut optimizer);
 //! ```
 
+use serde::{Serialize, Deserialize};
+use crate::{GPError, GPResult};
 use crate::optim::Optimizer;
 
+/// Serializable snapshot of a scheduler's progress.
+///
+/// Hyperparameters (step size, gamma, ...) are construction-time settings and
+/// are not included; restore into a scheduler built with the same arguments.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SchedulerState {
+    /// Scheduler type name (e.g. `"StepLR"`), checked on restore.
+    pub kind: String,
+    /// Number of `step()` calls so far.
+    pub epoch: usize,
+    /// Learning rate after the last step.
+    pub lr: f32,
+}
+
+impl SchedulerState {
+    /// Returns an error unless this state was produced by a scheduler of `kind`.
+    pub(crate) fn expect_kind(&self, kind: &str) -> GPResult<()> {
+        if self.kind != kind {
+            return Err(GPError::SerializationError(format!(
+                "Scheduler state is for '{}', cannot restore into '{}'", self.kind, kind
+            )));
+        }
+        Ok(())
+    }
+}
+
 /// Trait for learning rate schedulers.
 pub trait LRScheduler {
     /// Advances the scheduler by one step and returns the new learning rate.
@@ -27,6 +55,23 @@ pub trait LRScheduler {
     /// Returns the current learning rate without advancing.
     fn current_lr(&self) -> f32;
 
+    /// Captures the epoch counter and current LR for checkpointing.
+    ///
+    /// The default records the type name and current LR with epoch 0;
+    /// schedulers override this and [`restore_state`](Self::restore_state).
+    fn save_state(&self) -> SchedulerState {
+        SchedulerState { kind: std::any::type_name::<Self>().to_string(), epoch: 0, lr: self.current_lr() }
+    }
+
+    /// Restores progress captured by [`save_state`](Self::save_state).
+    ///
+    /// The default returns [`GPError::NotImplemented`].
+    fn restore_state(&mut self, _state: &SchedulerState) -> GPResult<()> {
+        Err(GPError::NotImplemented(format!(
+            "restore_state for scheduler {}", std::any::type_name::<Self>()
+        )))
+    }
+
     /// Advances the scheduler and applies the new LR to the optimizer.
     fn step_optimizer(&mut self, optimizer: &mut dyn Optimizer) {
         let lr = self.step();
@@ -72,6 +117,17 @@ impl LRScheduler for StepLR {
     }
 
     fn current_lr(&self) -> f32 { self.lr }
+
+    fn save_state(&self) -> SchedulerState {
+        SchedulerState { kind: "StepLR".to_string(), epoch: self.current_epoch, lr: self.lr }
+    }
+
+    fn restore_state(&mut self, state: &SchedulerState) -> GPResult<()> {
+        state.expect_kind("StepLR")?;
+        self.current_epoch = state.epoch;
+        self.lr = state.lr;
+        Ok(())
+    }
 }
 
 /// Exponential decay: `lr = initial_lr * gamma^epoch`.
@@ -100,6 +156,17 @@ impl LRScheduler for ExponentialLR {
     }
 
     fn current_lr(&self) -> f32 { self.lr }
+
+    fn save_state(&self) -> SchedulerState {
+        SchedulerState { kind: "ExponentialLR".to_string(), epoch: self.current_epoch, lr: self.lr }
+    }
+
+    fn restore_state(&mut self, state: &SchedulerState) -> GPResult<()> {
+        state.expect_kind("ExponentialLR")?;
+        self.current_epoch = state.epoch;
+        self.lr = state.lr;
+        Ok(())
+    }
 }
 
 /// Cosine annealing: oscillates LR between `initial_lr` and `min_lr` over `t_max` steps.
@@ -135,6 +202,17 @@ impl LRScheduler for CosineAnnealingLR {
     }
 
     fn current_lr(&self) -> f32 { self.lr }
+
+    fn save_state(&self) -> SchedulerState {
+        SchedulerState { kind: "CosineAnnealingLR".to_string(), epoch: self.current_epoch, lr: self.lr }
+    }
+
+    fn restore_state(&mut self, state: &SchedulerState) -> GPResult<()> {
+        state.expect_kind("CosineAnnealingLR")?;
+        self.current_epoch = state.epoch;
+        self.lr = state.lr;
+        Ok(())
+    }
 }
 
 // ── Tests ──────────────────────────────────────────────────────────────────
@@ -186,6 +264,21 @@ mod tests {
         assert!((sgd.get_lr() - 0.05).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_save_restore_state() {
+        let mut sched = CosineAnnealingLR::new(1.0, 20, 0.1);
+        for _ in 0..7 { sched.step(); }
+        let state = sched.save_state();
+
+        let mut resumed = CosineAnnealingLR::new(1.0, 20, 0.1);
+        resumed.restore_state(&state).unwrap();
+        assert_eq!(resumed.current_lr(), sched.current_lr());
+        assert_eq!(resumed.step(), sched.step());
+
+        let mut wrong = StepLR::new(1.0, 5, 0.5);
+        assert!(wrong.restore_state(&state).is_err());
+    }
+
     #[test]
     fn test_step_optimizer_adam() {
         let mut adam = Adam::new(0.01);
@@ -320,3 +320,77 @@ impl Tensor {
         self.as_slice().map(|s| s.to_vec())
     }
 }
+
+/// Computes the NumPy-style broadcast of two shapes.
+///
+/// Shapes are aligned from the trailing dimension; each pair of dimensions
+/// must be equal or one of them must be 1. Missing leading dimensions are
+/// treated as 1.
+///
+/// ```rust
+/// # use gran_prix::tensor::broadcast_shapes;
+/// assert_eq!(broadcast_shapes(&[4, 3], &[1, 3]).unwrap(), vec![4, 3]);
+/// assert_eq!(broadcast_shapes(&[2, 1, 5], &[3, 1]).unwrap(), vec![2, 3, 5]);
+/// assert!(broadcast_shapes(&[4, 3], &[2, 3]).is_err());
+/// ```
+pub fn broadcast_shapes(a: &[usize], b: &[usize]) -> GPResult<Vec<usize>> {
+    let ndim = a.len().max(b.len());
+    let mut out = vec![0; ndim];
+    for i in 0..ndim {
+        let da = if i < a.len() { a[a.len() - 1 - i] } else { 1 };
+        let db = if i < b.len() { b[b.len() - 1 - i] } else { 1 };
+        out[ndim - 1 - i] = match (da, db) {
+            (x, y) if x == y => x,
+            (1, y) => y,
+            (x, 1) => x,
+            _ => {
+                return Err(GPError::IncompatibleShapes {
+                    expected: a.to_vec(),
+                    found: b.to_vec(),
+                    exp_len: a.iter().product(),
+                    found_len: b.iter().product(),
+                });
+            }
+        };
+    }
+    Ok(out)
+}
+
+/// Validates and normalizes reduction axes for a tensor of the given shape.
+///
+/// An empty `axes` list means "reduce over every axis". The result is sorted
+/// and deduplicated; any axis `>= shape.len()` is an error.
+pub(crate) fn reduction_axes(shape: &[usize], axes: &[usize]) -> GPResult<Vec<usize>> {
+    if axes.is_empty() {
+        return Ok((0..shape.len()).collect());
+    }
+    let mut axes = axes.to_vec();
+    axes.sort_unstable();
+    axes.dedup();
+    if let Some(&bad) = axes.iter().find(|&&a| a >= shape.len()) {
+        return Err(GPError::TensorError(format!(
+            "Reduction axis {} out of range for shape {:?}", bad, shape
+        )));
+    }
+    Ok(axes)
+}
+
+/// Shape of a reduction over `axes` (already normalized).
+///
+/// With `keep_dims` the reduced axes become 1; otherwise they are dropped.
+/// A full reduction without `keep_dims` yields `[1]` rather than a 0-d tensor.
+pub(crate) fn reduced_shape(shape: &[usize], axes: &[usize], keep_dims: bool) -> Vec<usize> {
+    let mut out = Vec::with_capacity(shape.len());
+    for (i, &d) in shape.iter().enumerate() {
+        if !axes.contains(&i) {
+            out.push(d);
+        } else if keep_dims {
+            out.push(1);
+        }
+    }
+    if out.is_empty() {
+        out.push(1);
+    }
+    out
+}
+
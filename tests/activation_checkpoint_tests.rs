@@ -0,0 +1,101 @@
+//! Tests for gradient checkpointing (activation recomputation during backward).
+
+use gran_prix::graph::{Graph, dsl::GraphBuilder};
+use gran_prix::backend::cpu::CPUBackend;
+use gran_prix::layers::BatchNorm;
+use gran_prix::{Layer, NodeId};
+
+mod common;
+use common::det;
+
+struct DeepNet {
+    graph: Graph,
+    loss: NodeId,
+    /// Output of each block: the segment boundaries.
+    blocks: Vec<NodeId>,
+    /// Activations inside the blocks.
+    interior: Vec<NodeId>,
+}
+
+/// `depth` blocks of linear → BatchNorm (first block) → tanh → dropout, then MSE.
+fn deep_net(depth: usize) -> DeepNet {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let x = gb.batched_val(det(&[4, 6], 1));
+    let target = gb.batched_val(det(&[4, 6], 2));
+
+    let (mut blocks, mut interior) = (Vec::new(), Vec::new());
+    let mut h = x;
+    for i in 0..depth {
+        let w = gb.param(det(&[6, 6], 10 + i));
+        let b = gb.param(det(&[1, 6], 20 + i));
+        let pre = gb.linear(h, w, b);
+        let pre = if i == 0 { BatchNorm::new(6).forward(pre, &mut gb) } else { pre };
+        let act = gb.tanh(pre);
+        h = gb.dropout(act, 0.25);
+        interior.extend([pre, act]);
+        blocks.push(h);
+    }
+    let loss = gb.mse_loss(h, target);
+    DeepNet { graph, loss, blocks, interior }
+}
+
+fn train_step(graph: &mut Graph, loss: NodeId) -> Vec<Vec<f32>> {
+    graph.set_training(true);
+    graph.clear_gradients();
+    graph.execute(loss).unwrap();
+    graph.backward_scalar(loss).unwrap();
+    graph.params().trainable_param_ids().into_iter()
+        .map(|id| graph.params().gradient(id).unwrap().as_slice().unwrap().to_vec())
+        .collect()
+}
+
+fn all_params(graph: &Graph) -> Vec<Vec<f32>> {
+    graph.params().iter()
+        .map(|(_, t)| t.as_slice().unwrap().to_vec())
+        .collect()
+}
+
+#[test]
+fn test_checkpointing_matches_full_cache_gradients() {
+    let mut full = deep_net(4);
+    let mut checkpointed = deep_net(4);
+    checkpointed.graph.set_activation_checkpoints(&checkpointed.blocks);
+
+    for _ in 0..2 {
+        let expected = train_step(&mut full.graph, full.loss);
+        let grads = train_step(&mut checkpointed.graph, checkpointed.loss);
+        assert_eq!(grads, expected);
+    }
+    // BatchNorm running statistics are updated once per forward, not again on recompute
+    assert_eq!(all_params(&checkpointed.graph), all_params(&full.graph));
+}
+
+#[test]
+fn test_checkpointing_releases_segment_interiors() {
+    let mut net = deep_net(3);
+    net.graph.set_activation_checkpoints(&net.blocks);
+    net.graph.set_training(true);
+    net.graph.execute(net.loss).unwrap();
+
+    for &id in &net.interior {
+        assert!(net.graph.values()[id.0].is_none(), "{:?} should be released after forward", id);
+    }
+    for &id in net.blocks.iter().chain([&net.loss]) {
+        assert!(net.graph.values()[id.0].is_some(), "{:?} is a boundary", id);
+    }
+
+    // Recomputed values are released again once backward leaves their segment
+    net.graph.backward_scalar(net.loss).unwrap();
+    for &id in &net.interior {
+        assert!(net.graph.values()[id.0].is_none());
+    }
+}
+
+#[test]
+fn test_checkpointing_is_inactive_in_inference() {
+    let mut net = deep_net(2);
+    net.graph.set_activation_checkpoints(&net.blocks);
+    net.graph.execute(net.loss).unwrap();
+    assert!(net.interior.iter().all(|id| net.graph.values()[id.0].is_some()));
+}
@@ -0,0 +1,196 @@
+//! Tests for backpropagation through time over unrolled recurrent cells.
+
+use gran_prix::graph::{Graph, dsl::GraphBuilder};
+use gran_prix::backend::cpu::CPUBackend;
+use gran_prix::layers::{RNNCell, GRUCell, LSTMCell, RecurrentCell, Unrolled, unroll};
+use gran_prix::optim::{Optimizer, SGD};
+use gran_prix::{Layer, NodeId, Tensor};
+
+const STEPS: usize = 4;
+const BATCH: usize = 2;
+
+fn sequence(features: usize) -> Tensor {
+    let n = STEPS * BATCH * features;
+    Tensor::from_shape_vec(
+        &[STEPS, BATCH, features],
+        (0..n).map(|i| ((i * 7 % 11) as f32 - 5.0) * 0.15).collect(),
+    ).unwrap()
+}
+
+/// Unrolls `cell` and attaches an MSE loss on the last hidden state.
+fn build<C: RecurrentCell>(cell: &mut C, truncation: Option<usize>) -> (Graph, Unrolled, NodeId) {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let unrolled = unroll(cell, &mut gb, STEPS, BATCH, truncation).unwrap();
+    let last = *unrolled.outputs.last().unwrap();
+    let target = gb.val(Tensor::from_elem(&[BATCH, cell.hidden_size()], 0.25));
+    let loss = gb.mse_loss(last, target);
+    unrolled.feed(&mut graph, &sequence(cell.input_size())).unwrap();
+    (graph, unrolled, loss)
+}
+
+fn loss_value(graph: &mut Graph, loss: NodeId) -> f32 {
+    graph.execute(loss).unwrap().as_slice().unwrap()[0]
+}
+
+/// Compares BPTT gradients of every shared parameter with finite differences.
+fn check_bptt_gradients<C: RecurrentCell>(cell: &mut C) {
+    let (mut graph, _, loss) = build(cell, None);
+    graph.execute(loss).unwrap();
+    graph.backward_scalar(loss).unwrap();
+
+    let eps = 1e-2;
+    for id in graph.params().trainable_param_ids() {
+        let analytic = graph.params().gradient(id).unwrap().clone();
+        for i in 0..analytic.len() {
+            *graph.params_mut().tensor_mut(id).get_flat_mut(i).unwrap() += eps;
+            let plus = loss_value(&mut graph, loss);
+            *graph.params_mut().tensor_mut(id).get_flat_mut(i).unwrap() -= 2.0 * eps;
+            let minus = loss_value(&mut graph, loss);
+            *graph.params_mut().tensor_mut(id).get_flat_mut(i).unwrap() += eps;
+
+            let numeric = (plus - minus) / (2.0 * eps);
+            let got = analytic.get_flat(i).unwrap();
+            assert!(
+                (numeric - got).abs() < 2e-3 + 2e-2 * numeric.abs(),
+                "param {:?}[{}]: analytic {} vs numeric {}", id, i, got, numeric
+            );
+        }
+    }
+}
+
+#[test]
+fn test_unrolled_steps_share_parameters() {
+    let mut cell = GRUCell::new(3, 5);
+    let (graph, unrolled, _) = build(&mut cell, None);
+    assert_eq!(unrolled.steps(), STEPS);
+    assert_eq!(unrolled.params.len(), 12);
+    // Weights are registered once, not once per step.
+    assert_eq!(graph.params().len(), 12);
+}
+
+#[test]
+fn test_rnn_bptt_gradients() {
+    check_bptt_gradients(&mut RNNCell::new(3, 4));
+}
+
+#[test]
+fn test_gru_bptt_gradients() {
+    check_bptt_gradients(&mut GRUCell::new(3, 4));
+}
+
+#[test]
+fn test_lstm_bptt_gradients() {
+    check_bptt_gradients(&mut LSTMCell::new(3, 4));
+}
+
+#[test]
+fn test_run_matches_stepping_the_layer() {
+    let mut cell = RNNCell::new(2, 3);
+    let (mut graph, unrolled, _) = build(&mut cell, None);
+    let seq = sequence(2);
+    let outputs = unrolled.run(&mut graph, &seq).unwrap();
+    assert_eq!(outputs.len(), STEPS);
+
+    // Stepping the single-step layer row by row (batch 1) gives the same states.
+    let data = seq.as_slice().unwrap();
+    for t in 0..STEPS {
+        let mut step_graph = Graph::new(Box::new(CPUBackend));
+        let mut gb = GraphBuilder::new(&mut step_graph);
+        let x = gb.val(Tensor::from_shape_vec(&[1, 2], data[t * 4..t * 4 + 2].to_vec()).unwrap());
+        let h = gran_prix::Layer::forward(&mut cell, x, &mut gb);
+        let out = step_graph.execute(h).unwrap();
+        cell.hidden_state = Some(out.clone());
+        let expected = &outputs[t].as_slice().unwrap()[..3];
+        for (a, b) in out.as_slice().unwrap().iter().zip(expected) {
+            assert!((a - b).abs() < 1e-5, "step {}: {} vs {}", t, a, b);
+        }
+    }
+}
+
+#[test]
+fn test_truncation_blocks_gradient_to_early_steps() {
+    let mut cell = LSTMCell::new(2, 3);
+    let (mut graph, unrolled, loss) = build(&mut cell, Some(2));
+    let (mut full_graph, _, full_loss) = build(&mut cell, None);
+
+    // Truncation does not change forward values.
+    assert_eq!(loss_value(&mut graph, loss), loss_value(&mut full_graph, full_loss));
+
+    graph.backward_scalar(loss).unwrap();
+    let grad_norm = |id: NodeId| -> f32 {
+        graph.get_gradient(id).map_or(0.0, |g| g.as_slice().unwrap().iter().map(|v| v.abs()).sum())
+    };
+    assert_eq!(grad_norm(unrolled.inputs[0]), 0.0);
+    assert_eq!(grad_norm(unrolled.inputs[1]), 0.0);
+    assert!(grad_norm(unrolled.inputs[2]) > 0.0);
+    assert!(grad_norm(unrolled.inputs[3]) > 0.0);
+}
+
+#[test]
+fn test_zero_truncation_is_rejected() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    assert!(unroll(&mut RNNCell::new(2, 3), &mut gb, STEPS, BATCH, Some(0)).is_err());
+}
+
+#[test]
+fn test_feed_rejects_wrong_shape() {
+    let mut cell = RNNCell::new(2, 3);
+    let (mut graph, unrolled, _) = build(&mut cell, None);
+    assert!(unrolled.feed(&mut graph, &Tensor::new_zeros(&[STEPS + 1, BATCH, 2])).is_err());
+    assert!(unrolled.set_initial_states(&mut graph, &[]).is_err());
+}
+
+#[test]
+fn test_rnn_learns_to_remember_first_input() {
+    // Target: the final hidden state should reproduce the sign of the first
+    // input, which is only reachable through the recurrence.
+    let mut cell = RNNCell::new(1, 4);
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let unrolled = unroll(&mut cell, &mut gb, 3, 2, None).unwrap();
+    let w_out = gb.param(Tensor::from_elem(&[4, 1], 0.1));
+    let pred = gb.matmul(*unrolled.outputs.last().unwrap(), w_out);
+    let target = gb.val(Tensor::from_shape_vec(&[2, 1], vec![0.5, -0.5]).unwrap());
+    let loss = gb.mse_loss(pred, target);
+    let seq = Tensor::from_shape_vec(&[3, 2, 1], vec![1.0, -1.0, 0.0, 0.0, 0.0, 0.0]).unwrap();
+    unrolled.feed(&mut graph, &seq).unwrap();
+
+    let mut optimizer = SGD::new(0.5, 0.0, 0.0);
+    let first = loss_value(&mut graph, loss);
+    let mut last = first;
+    for _ in 0..200 {
+        graph.clear_gradients();
+        last = loss_value(&mut graph, loss);
+        graph.backward_scalar(loss).unwrap();
+        optimizer.step(graph.params_mut()).unwrap();
+    }
+    assert!(last < first * 0.1, "loss should drop: {} -> {}", first, last);
+}
+
+#[test]
+fn test_unrolled_cell_pulls_trained_weights() {
+    let mut cell = GRUCell::new(3, 4);
+    let initial = cell.wz_ih.clone();
+    let (mut graph, unrolled, loss) = build(&mut cell, None);
+    assert_eq!(cell.binding.ids().len(), unrolled.params.len());
+
+    let mut optimizer = SGD::new(0.1, 0.0, 0.0);
+    for _ in 0..3 {
+        graph.clear_gradients();
+        graph.execute(loss).unwrap();
+        graph.backward_scalar(loss).unwrap();
+        optimizer.step(graph.params_mut()).unwrap();
+    }
+    cell.pull_params(graph.params()).unwrap();
+    assert_ne!(cell.wz_ih.as_slice().unwrap(), initial.as_slice().unwrap());
+    assert_eq!(cell.wz_ih.as_slice().unwrap(), graph.params().tensor(cell.binding.ids()[0]).as_slice().unwrap());
+
+    // Unrolling again in the same graph shares the bound weights
+    let count = graph.params().len();
+    let mut gb = GraphBuilder::new(&mut graph);
+    let again = unroll(&mut cell, &mut gb, 2, BATCH, None).unwrap();
+    assert_eq!(again.params.len(), unrolled.params.len());
+    assert_eq!(graph.params().len(), count);
+}
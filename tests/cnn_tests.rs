@@ -173,3 +173,46 @@ fn test_max_pool2d_autograd_complex() {
     println!("Analytical Pool X Grad: {}, Numerical: {}, Diff: {}", analytical_grad_x, numerical_grad_x, diff);
     assert!(diff < 5e-2);
 }
+
+#[test]
+fn test_mnist_tiny_from_json() {
+    use gran_prix::network_def::NetworkDef;
+
+    // The architecture of examples/mnist_tiny.rs, declared as JSON.
+    let json = r#"{
+        "input_shape": [1, 10, 10],
+        "layers": [
+            {"type": "conv2d", "in_channels": 1, "out_channels": 4, "kernel_size": 3, "stride": 1, "padding": 1},
+            {"type": "activation", "function": "relu"},
+            {"type": "maxpool2d", "kernel_size": 2, "stride": 2},
+            {"type": "flatten"},
+            {"type": "linear", "in_features": 100, "out_features": 1},
+            {"type": "activation", "function": "sigmoid"}
+        ]
+    }"#;
+    let net = NetworkDef::from_json(json).unwrap();
+    assert_eq!(net.output_shape().unwrap(), vec![1]);
+
+    let mut compiled = net.compile(Box::new(CPUBackend)).unwrap();
+    let graph = &mut compiled.graph;
+    // conv weight + bias, linear weight + bias
+    assert_eq!(graph.params().len(), 4);
+
+    // Vertical bar in column 3
+    let mut data = vec![0.0f32; 100];
+    for r in 0..10 {
+        data[r * 10 + 3] = 1.0;
+    }
+    graph.feed(compiled.input_node, &Tensor::from_shape_vec(&[1, 1, 10, 10], data).unwrap()).unwrap();
+
+    let out = graph.execute(compiled.output_node).unwrap();
+    assert_eq!(out.shape(), &[1, 1]);
+    let p = out.get_flat(0).unwrap();
+    assert!(p > 0.0 && p < 1.0);
+
+    graph.backward(compiled.output_node, Tensor::from_elem(&[1, 1], 1.0)).unwrap();
+    let grads: Vec<bool> = graph.params().iter()
+        .map(|(id, t)| graph.params().gradient(id).is_some_and(|g| g.shape() == t.shape()))
+        .collect();
+    assert!(grads.iter().all(|&ok| ok), "every parameter should receive a gradient");
+}
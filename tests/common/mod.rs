@@ -0,0 +1,65 @@
+//! Helpers shared by the integration test files.
+
+// Each test binary compiles this module and uses only some of the helpers
+#![allow(dead_code)]
+
+use gran_prix::graph::OpType;
+use gran_prix::backend::cpu::CPUBackend;
+use gran_prix::Tensor;
+
+/// Compares `OpType::backward` against central finite differences of
+/// `sum(forward(inputs) * grad_output)` for every input element.
+pub fn check_gradients(op: OpType, inputs: Vec<Tensor>) {
+    let all: Vec<usize> = (0..inputs.len()).collect();
+    check_gradients_wrt(op, inputs, &all);
+}
+
+/// Like [`check_gradients`], but only checks the inputs listed in `wrt`.
+pub fn check_gradients_wrt(op: OpType, inputs: Vec<Tensor>, wrt: &[usize]) {
+    let backend = CPUBackend;
+    let refs: Vec<&Tensor> = inputs.iter().collect();
+    let output = op.forward(&refs, &backend, false, 0).unwrap();
+    let grad_output = Tensor::from_shape_vec(
+        output.shape(),
+        (0..output.len()).map(|i| 0.5 + 0.25 * i as f32).collect(),
+    ).unwrap();
+    let analytic = op.backward_in_mode(&refs, Some(&output), &grad_output, &backend, false).unwrap();
+    assert_eq!(analytic.len(), inputs.len());
+
+    let objective = |inputs: &[Tensor]| -> f32 {
+        let refs: Vec<&Tensor> = inputs.iter().collect();
+        let out = op.forward(&refs, &backend, false, 0).unwrap();
+        out.as_slice().unwrap().iter()
+            .zip(grad_output.as_slice().unwrap())
+            .map(|(y, g)| y * g)
+            .sum()
+    };
+
+    let eps = 1e-3;
+    for (k, grad) in analytic.iter().enumerate().filter(|(k, _)| wrt.contains(k)) {
+        assert_eq!(grad.shape(), inputs[k].shape(), "{} grad {} shape", op.name(), k);
+        for i in 0..inputs[k].len() {
+            let mut plus = inputs.clone();
+            *plus[k].get_flat_mut(i).unwrap() += eps;
+            let mut minus = inputs.clone();
+            *minus[k].get_flat_mut(i).unwrap() -= eps;
+            let numeric = (objective(&plus) - objective(&minus)) / (2.0 * eps);
+            let got = grad.get_flat(i).unwrap();
+            assert!(
+                (numeric - got).abs() < 1e-2 * (1.0 + numeric.abs()),
+                "{} grad {}[{}]: analytic {} vs numeric {}", op.name(), k, i, got, numeric
+            );
+        }
+    }
+}
+
+pub fn t(shape: &[usize], data: Vec<f32>) -> Tensor {
+    Tensor::from_shape_vec(shape, data).unwrap()
+}
+
+/// A deterministic tensor of small values in `[-0.55, 0.55]`, varied by `seed`.
+pub fn det(shape: &[usize], seed: usize) -> Tensor {
+    let len: usize = shape.iter().product();
+    let data = (0..len).map(|i| (((i + seed) * 7919 % 23) as f32 - 11.0) * 0.05).collect();
+    Tensor::from_shape_vec(shape, data).unwrap()
+}
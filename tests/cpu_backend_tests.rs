@@ -42,3 +42,49 @@ fn test_cpu_add_relu_fused() {
     let expected = Tensor::from_shape_vec(&[1, 2], vec![0.0, 2.0]).unwrap();
     assert_eq!(res, expected);
 }
+
+#[test]
+fn test_cpu_add_broadcasts_row_bias() {
+    let backend = CPUBackend;
+    let a = Tensor::from_shape_vec(&[2, 3], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+    let bias = Tensor::from_shape_vec(&[1, 3], vec![10.0, 20.0, 30.0]).unwrap();
+
+    let expected = Tensor::from_shape_vec(&[2, 3], vec![11.0, 22.0, 33.0, 14.0, 25.0, 36.0]).unwrap();
+    assert_eq!(backend.add(&a, &bias).unwrap(), expected);
+    // Broadcasting is symmetric: the smaller operand may come first.
+    assert_eq!(backend.add(&bias, &a).unwrap(), expected);
+
+    let mut out = Tensor::new_zeros(&[2, 3]);
+    backend.add_into(&bias, &a, &mut out).unwrap();
+    assert_eq!(out, expected);
+}
+
+#[test]
+fn test_cpu_mul_broadcasts_both_operands() {
+    let backend = CPUBackend;
+    let col = Tensor::from_shape_vec(&[2, 1], vec![1.0, 2.0]).unwrap();
+    let row = Tensor::from_shape_vec(&[3], vec![1.0, 10.0, 100.0]).unwrap();
+
+    let res = backend.mul(&col, &row).unwrap();
+    let expected = Tensor::from_shape_vec(&[2, 3], vec![1.0, 10.0, 100.0, 2.0, 20.0, 200.0]).unwrap();
+    assert_eq!(res, expected);
+
+    let mut out = Tensor::new_zeros(&[2, 3]);
+    backend.mul_into(&col, &row, &mut out).unwrap();
+    assert_eq!(out, expected);
+}
+
+#[test]
+fn test_cpu_broadcast_rejects_incompatible_shapes() {
+    let backend = CPUBackend;
+    let a = Tensor::new_zeros(&[4, 3]);
+    let b = Tensor::new_zeros(&[2, 3]);
+    assert!(backend.add(&a, &b).is_err());
+    assert!(backend.mul(&a, &b).is_err());
+    assert!(backend.add_relu(&a, &b).is_err());
+
+    // `out` must have the broadcast shape, not just the right element count.
+    let bias = Tensor::new_zeros(&[1, 3]);
+    let mut out = Tensor::new_zeros(&[3, 4]);
+    assert!(backend.add_into(&a, &bias, &mut out).is_err());
+}
@@ -3,7 +3,12 @@
 use gran_prix::graph::{Graph, dsl::GraphBuilder, OpType};
 use gran_prix::backend::cpu::CPUBackend;
 use gran_prix::loss::{Loss, MSE};
-use gran_prix::Tensor;
+use gran_prix::layers::BatchNorm;
+use gran_prix::optim::{Optimizer, SGD};
+use gran_prix::{GPError, Layer, ParamId, Tensor};
+
+mod common;
+use common::check_gradients_wrt;
 
 // ────────────────────────────────────────────────────────────────────────────
 // DROPOUT TESTS
@@ -96,7 +101,7 @@ fn test_batchnorm_normalizes_to_zero_mean() {
     // gamma=1, beta=0 → output should be normalized (mean≈0, var≈1)
     let gamma = gb.param(Tensor::new_ones(&[1, 2]));
     let beta = gb.param(Tensor::new_zeros(&[1, 2]));
-    let output = gb.node(OpType::BatchNorm { epsilon: 1e-5 }, vec![input, gamma, beta]);
+    let output = gb.node(OpType::BatchNorm { epsilon: 1e-5, momentum: 0.1 }, vec![input, gamma, beta]);
 
     let result = graph.execute(output).unwrap();
     let s = result.as_slice().unwrap();
@@ -125,7 +130,7 @@ fn test_batchnorm_gamma_beta_learnable() {
     let mut gb = GraphBuilder::new(&mut graph);
     let gamma = gb.param(Tensor::from_shape_vec(&[1, 1], vec![2.0]).unwrap());
     let beta = gb.param(Tensor::from_shape_vec(&[1, 1], vec![3.0]).unwrap());
-    let output = gb.node(OpType::BatchNorm { epsilon: 1e-5 }, vec![input, gamma, beta]);
+    let output = gb.node(OpType::BatchNorm { epsilon: 1e-5, momentum: 0.1 }, vec![input, gamma, beta]);
 
     let result = graph.execute(output).unwrap();
     let s = result.as_slice().unwrap();
@@ -146,7 +151,7 @@ fn test_batchnorm_backward_produces_gradients() {
     let mut gb = GraphBuilder::new(&mut graph);
     let gamma = gb.param(Tensor::new_ones(&[1, 2]));
     let beta = gb.param(Tensor::new_zeros(&[1, 2]));
-    let bn = gb.node(OpType::BatchNorm { epsilon: 1e-5 }, vec![input, gamma, beta]);
+    let bn = gb.node(OpType::BatchNorm { epsilon: 1e-5, momentum: 0.1 }, vec![input, gamma, beta]);
 
     // Forward pass
     let pred = graph.execute(bn).unwrap();
@@ -160,8 +165,8 @@ fn test_batchnorm_backward_produces_gradients() {
     graph.backward(bn, grad).unwrap();
 
     // gamma and beta should have gradients
-    let gamma_grad = graph.params().gradient(gran_prix::ParamId(0));
-    let beta_grad = graph.params().gradient(gran_prix::ParamId(1));
+    let gamma_grad = graph.params().gradient(ParamId(0));
+    let beta_grad = graph.params().gradient(ParamId(1));
     assert!(gamma_grad.is_some(), "Gamma should have gradient");
     assert!(beta_grad.is_some(), "Beta should have gradient");
 
@@ -178,12 +183,34 @@ fn test_batchnorm_preserves_shape() {
     let mut gb = GraphBuilder::new(&mut graph);
     let gamma = gb.param(Tensor::new_ones(&[1, 4]));
     let beta = gb.param(Tensor::new_zeros(&[1, 4]));
-    let output = gb.node(OpType::BatchNorm { epsilon: 1e-5 }, vec![input, gamma, beta]);
+    let output = gb.node(OpType::BatchNorm { epsilon: 1e-5, momentum: 0.1 }, vec![input, gamma, beta]);
 
     let result = graph.execute(output).unwrap();
     assert_eq!(result.shape(), &[8, 4]);
 }
 
+#[test]
+fn test_batchnorm_rejects_non_matrix_inputs() {
+    let backend = CPUBackend;
+    let bn = OpType::BatchNorm { epsilon: 1e-5, momentum: 0.1 };
+    let gamma = Tensor::new_ones(&[1, 4]);
+    let beta = Tensor::new_zeros(&[1, 4]);
+    fn incompatible<T>(result: Result<T, GPError>) -> bool {
+        matches!(result, Err(GPError::IncompatibleShapes { .. }))
+    }
+
+    let flat = Tensor::new_zeros(&[4]);
+    assert!(incompatible(bn.output_shape(&[vec![4], vec![1, 4], vec![1, 4]])));
+    assert!(incompatible(bn.forward(&[&flat, &gamma, &beta], &backend, true, 0)));
+    let image = Tensor::new_zeros(&[2, 4, 3, 3]);
+    assert!(incompatible(bn.forward(&[&image, &gamma, &beta], &backend, false, 0)));
+    assert!(incompatible(bn.backward(&[&image, &gamma, &beta], None, &image, &backend)));
+
+    // Per-feature parameters must match the feature count
+    assert!(incompatible(bn.output_shape(&[vec![8, 3], vec![1, 4], vec![1, 4]])));
+    assert_eq!(bn.output_shape(&[vec![8, 4], vec![1, 4], vec![1, 4]]).unwrap(), vec![8, 4]);
+}
+
 // ────────────────────────────────────────────────────────────────────────────
 // TRAINING MODE INTEGRATION
 // ────────────────────────────────────────────────────────────────────────────
@@ -199,3 +226,147 @@ fn test_training_mode_toggle() {
     graph.set_training(false);
     assert!(!graph.is_training());
 }
+
+// ────────────────────────────────────────────────────────────────────────────
+// BATCHNORM RUNNING STATISTICS
+// ────────────────────────────────────────────────────────────────────────────
+
+/// Builds `x → BatchNorm layer` and returns (graph, input, output).
+fn batchnorm_graph(x: Tensor) -> (Graph, gran_prix::NodeId, gran_prix::NodeId) {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let input = graph.batched_input(x);
+    let mut gb = GraphBuilder::new(&mut graph);
+    let mut bn = BatchNorm::new(2);
+    bn.momentum = 0.5;
+    let output = bn.forward(input, &mut gb);
+    (graph, input, output)
+}
+
+fn set_input(graph: &mut Graph, input: gran_prix::NodeId, x: Tensor) {
+    graph.feed(input, &x).unwrap();
+}
+
+#[test]
+fn test_batchnorm_training_updates_running_stats() {
+    // Feature 0: [1, 3] → mean 2, unbiased var 2. Feature 1: [4, 4] → mean 4, var 0.
+    let (mut graph, _, output) = batchnorm_graph(
+        Tensor::from_shape_vec(&[2, 2], vec![1.0, 4.0, 3.0, 4.0]).unwrap(),
+    );
+    // ParamIds: 0 gamma, 1 beta, 2 running_mean, 3 running_var
+    graph.set_training(true);
+    graph.execute(output).unwrap();
+
+    // momentum 0.5: running = 0.5 * running + 0.5 * batch
+    assert_eq!(graph.params().tensor(ParamId(2)).as_slice().unwrap(), &[1.0, 2.0]);
+    assert_eq!(graph.params().tensor(ParamId(3)).as_slice().unwrap(), &[1.5, 0.5]);
+
+    graph.execute(output).unwrap();
+    assert_eq!(graph.params().tensor(ParamId(2)).as_slice().unwrap(), &[1.5, 3.0]);
+
+    // Inference passes never touch the running statistics
+    graph.set_training(false);
+    graph.execute(output).unwrap();
+    assert_eq!(graph.params().tensor(ParamId(2)).as_slice().unwrap(), &[1.5, 3.0]);
+}
+
+#[test]
+fn test_batchnorm_inference_uses_running_stats() {
+    let (mut graph, input, output) = batchnorm_graph(Tensor::new_zeros(&[1, 2]));
+    *graph.params_mut().tensor_mut(ParamId(2)) = Tensor::from_shape_vec(&[1, 2], vec![1.0, -2.0]).unwrap();
+    *graph.params_mut().tensor_mut(ParamId(3)) = Tensor::from_shape_vec(&[1, 2], vec![4.0, 0.25]).unwrap();
+
+    // Single sample: normalized by running stats, not passed through as affine
+    set_input(&mut graph, input, Tensor::from_shape_vec(&[1, 2], vec![5.0, -1.0]).unwrap());
+    let single = graph.execute(output).unwrap();
+    let s = single.as_slice().unwrap();
+    assert!((s[0] - 2.0).abs() < 1e-4, "expected (5-1)/2 = 2, got {}", s[0]);
+    assert!((s[1] - 2.0).abs() < 1e-3, "expected (-1+2)/0.5 = 2, got {}", s[1]);
+
+    // The same sample inside a larger batch produces the same output
+    graph.clear_values();
+    set_input(&mut graph, input, Tensor::from_shape_vec(&[3, 2], vec![
+        5.0, -1.0,
+        100.0, 100.0,
+        -50.0, 7.0,
+    ]).unwrap());
+    let batched = graph.execute(output).unwrap();
+    assert_eq!(&batched.as_slice().unwrap()[..2], s);
+}
+
+#[test]
+fn test_batchnorm_inference_gradients() {
+    let op = OpType::BatchNorm { epsilon: 1e-5, momentum: 0.1 };
+    let gamma = Tensor::from_shape_vec(&[1, 2], vec![1.5, -0.5]).unwrap();
+    let beta = Tensor::from_shape_vec(&[1, 2], vec![0.2, 0.1]).unwrap();
+    let mean = Tensor::from_shape_vec(&[1, 2], vec![1.0, -2.0]).unwrap();
+    let var = Tensor::from_shape_vec(&[1, 2], vec![4.0, 0.25]).unwrap();
+    for x in [
+        Tensor::from_shape_vec(&[3, 2], vec![5.0, -1.0, 0.5, 2.0, -3.0, 0.7]).unwrap(),
+        Tensor::from_shape_vec(&[1, 2], vec![5.0, -1.0]).unwrap(),
+    ] {
+        let inputs = vec![x, gamma.clone(), beta.clone(), mean.clone(), var.clone()];
+        check_gradients_wrt(op.clone(), inputs, &[0, 1, 2]);
+    }
+}
+
+#[test]
+fn test_batchnorm_inference_backward_uses_running_stats() {
+    // A single sample in inference mode still gets grad_x = gamma / sqrt(var + eps)
+    let (mut graph, _, output) = batchnorm_graph(Tensor::from_shape_vec(&[1, 2], vec![5.0, -1.0]).unwrap());
+    *graph.params_mut().tensor_mut(ParamId(3)) = Tensor::from_shape_vec(&[1, 2], vec![4.0, 0.25]).unwrap();
+    graph.execute(output).unwrap();
+    graph.backward(output, Tensor::new_ones(&[1, 2])).unwrap();
+    let grad_gamma = graph.params().gradient(ParamId(0)).unwrap();
+    assert!((grad_gamma.as_slice().unwrap()[0] - 2.5).abs() < 1e-4, "expected (5-0)/2 = 2.5");
+}
+
+#[test]
+fn test_batchnorm_buffers_untouched_by_optimizer() {
+    let (mut graph, _, output) = batchnorm_graph(
+        Tensor::from_shape_vec(&[4, 2], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 9.0]).unwrap(),
+    );
+    graph.set_training(true);
+    let pred = graph.execute(output).unwrap();
+    let stats_before: Vec<Vec<f32>> = (2..4)
+        .map(|i| graph.params().tensor(ParamId(i)).to_vec().unwrap())
+        .collect();
+
+    let grad = MSE.gradient(&pred, &Tensor::new_ones(&[4, 2])).unwrap();
+    graph.backward(output, grad).unwrap();
+    assert!(graph.params().gradient(ParamId(2)).is_none());
+    assert!(graph.params().gradient(ParamId(3)).is_none());
+
+    let mut sgd = SGD::new(0.1, 0.9, 0.5);
+    sgd.step_graph(&mut graph).unwrap();
+    for (i, before) in (2..4).zip(stats_before) {
+        assert_eq!(graph.params().tensor(ParamId(i)).to_vec().unwrap(), before);
+    }
+}
+
+#[test]
+fn test_batchnorm_running_stats_persist_in_state_dict() {
+    let (mut graph, _, output) = batchnorm_graph(
+        Tensor::from_shape_vec(&[2, 2], vec![1.0, 4.0, 3.0, 8.0]).unwrap(),
+    );
+    graph.set_training(true);
+    graph.execute(output).unwrap();
+    let dict = graph.params().state_dict().unwrap();
+
+    let (mut fresh, _, _) = batchnorm_graph(Tensor::new_zeros(&[1, 2]));
+    fresh.params_mut().load_state_dict(&dict, true).unwrap();
+    for i in 2..4 {
+        assert_eq!(fresh.params().tensor(ParamId(i)), graph.params().tensor(ParamId(i)));
+    }
+}
+
+#[test]
+fn test_batchnorm_op_deserializes_without_momentum() {
+    let op: OpType = serde_json::from_str(r#"{"BatchNorm":{"epsilon":0.001}}"#).unwrap();
+    match op {
+        OpType::BatchNorm { epsilon, momentum } => {
+            assert_eq!(epsilon, 0.001);
+            assert_eq!(momentum, 0.1);
+        }
+        other => panic!("unexpected op {:?}", other),
+    }
+}
@@ -0,0 +1,148 @@
+use gran_prix::graph::{Graph, OpType};
+use gran_prix::graph::dsl::GraphBuilder;
+use gran_prix::backend::cpu::CPUBackend;
+use gran_prix::optim::{Optimizer, SGD};
+use gran_prix::{GPError, Tensor};
+
+mod common;
+use common::{check_gradients_wrt, t};
+
+#[test]
+fn test_backward_scalar_matches_explicit_seed() {
+    let build = |graph: &mut Graph| {
+        let mut gb = GraphBuilder::new(graph);
+        let x = gb.val(Tensor::from_shape_vec(&[2, 2], vec![1.0, -1.0, 0.5, 2.0]).unwrap());
+        let w = gb.param(Tensor::from_shape_vec(&[2, 1], vec![0.3, -0.2]).unwrap());
+        let y = gb.val(Tensor::from_shape_vec(&[2, 1], vec![1.0, 0.0]).unwrap());
+        let pred = gb.matmul(x, w);
+        let loss = gb.mse_loss(pred, y);
+        (w, loss)
+    };
+
+    let mut implicit = Graph::new(Box::new(CPUBackend));
+    let (w, loss) = build(&mut implicit);
+    implicit.execute(loss).unwrap();
+    implicit.backward_scalar(loss).unwrap();
+
+    let mut explicit = Graph::new(Box::new(CPUBackend));
+    let (w2, loss2) = build(&mut explicit);
+    explicit.execute(loss2).unwrap();
+    explicit.backward(loss2, Tensor::new_ones(&[1])).unwrap();
+
+    assert_eq!(implicit.get_gradient(w).unwrap(), explicit.get_gradient(w2).unwrap());
+}
+
+#[test]
+fn test_composite_objective_with_l2_regularization() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+
+    // loss = bce_with_logits(x·w, y) + 0.1 * sum(w²)
+    let x = gb.val(Tensor::from_shape_vec(&[1, 2], vec![1.0, 2.0]).unwrap());
+    let w = gb.param(Tensor::from_shape_vec(&[2, 1], vec![0.5, -0.25]).unwrap());
+    let y = gb.val(Tensor::from_elem(&[1, 1], 1.0));
+    let logits = gb.matmul(x, w);
+    let data_loss = gb.bce_with_logits_loss(logits, y);
+    let w_sq = gb.pow(w, 2.0);
+    let l2 = gb.reduce_sum(w_sq, &[], false);
+    let lambda = gb.val(Tensor::from_elem(&[1], 0.1));
+    let penalty = gb.mul(l2, lambda);
+    let total = gb.add(data_loss, penalty);
+
+    let value = graph.execute(total).unwrap();
+    assert_eq!(value.shape(), &[1]);
+    graph.backward_scalar(total).unwrap();
+
+    // logit = 0.5 - 0.5 = 0 → sigmoid = 0.5; d/dw = (σ - y) * x + 0.2 * w
+    let grad = graph.get_gradient(w).unwrap().to_vec().unwrap();
+    let expected = [-0.5 * 1.0 + 0.2 * 0.5, -0.5 * 2.0 + 0.2 * -0.25];
+    for (g, e) in grad.iter().zip(expected.iter()) {
+        assert!((g - e).abs() < 1e-6, "{} vs {}", g, e);
+    }
+}
+
+#[test]
+fn test_training_loop_from_loss_node() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+
+    let x = gb.val(Tensor::from_shape_vec(&[4, 1], vec![-1.0, 0.0, 1.0, 2.0]).unwrap());
+    let y = gb.val(Tensor::from_shape_vec(&[4, 1], vec![-1.0, 1.0, 3.0, 5.0]).unwrap());
+    let w = gb.param(Tensor::new_zeros(&[1, 1]));
+    let b = gb.param(Tensor::new_zeros(&[1, 1]));
+    let pred = gb.linear(x, w, b);
+    let loss = gb.mse_loss(pred, y);
+
+    let mut opt = SGD::new(0.1, 0.0, 0.0);
+    let initial = graph.execute(loss).unwrap().get_flat(0).unwrap();
+    let mut last = initial;
+    for _ in 0..200 {
+        graph.clear_gradients();
+        last = graph.execute(loss).unwrap().get_flat(0).unwrap();
+        graph.backward_scalar(loss).unwrap();
+        opt.step_graph(&mut graph).unwrap();
+    }
+    assert!(last < initial * 1e-3, "loss did not converge: {} -> {}", initial, last);
+}
+
+#[test]
+fn test_backward_scalar_rejects_non_scalar() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let w = gb.param(Tensor::new_ones(&[1, 2]));
+    let y = gb.relu(w);
+
+    // Not evaluated yet
+    assert!(matches!(graph.backward_scalar(y), Err(GPError::InferenceError(_))));
+    graph.execute(y).unwrap();
+    assert!(matches!(graph.backward_scalar(y), Err(GPError::IncompatibleShapes { .. })));
+}
+
+#[test]
+fn test_loss_op_gradients() {
+    let logits = t(&[2, 3], vec![0.3, -1.2, 2.0, 0.7, 0.1, -0.4]);
+    let probs = t(&[2, 3], vec![0.2, 0.7, 0.1, 0.6, 0.3, 0.1]);
+    let binary = t(&[2, 3], vec![1.0, 0.0, 1.0, 0.0, 0.0, 1.0]);
+    let one_hot = t(&[2, 3], vec![0.0, 1.0, 0.0, 1.0, 0.0, 0.0]);
+
+    check_gradients_wrt(OpType::MSELoss, vec![logits.clone(), probs.clone()], &[0]);
+    check_gradients_wrt(OpType::BinaryCrossEntropyLoss, vec![probs.clone(), binary.clone()], &[0]);
+    check_gradients_wrt(OpType::BCEWithLogitsLoss, vec![logits.clone(), binary], &[0]);
+    check_gradients_wrt(OpType::CategoricalCrossEntropyLoss, vec![probs, one_hot.clone()], &[0]);
+    check_gradients_wrt(OpType::CrossEntropyWithLogitsLoss, vec![logits, one_hot], &[0]);
+}
+
+#[test]
+fn test_loss_ops_match_loss_trait() {
+    use gran_prix::loss::{Loss, MSE, CrossEntropyWithLogits};
+
+    let backend = CPUBackend;
+    let pred = t(&[2, 2], vec![0.5, -1.0, 2.0, 0.0]);
+    let target = t(&[2, 2], vec![1.0, 0.0, 0.0, 1.0]);
+
+    let mse = OpType::MSELoss.forward(&[&pred, &target], &backend, false, 0).unwrap();
+    assert_eq!(mse.shape(), &[1]);
+    assert_eq!(mse.get_flat(0).unwrap(), MSE.calculate(&pred, &target).unwrap());
+
+    let ce = OpType::CrossEntropyWithLogitsLoss.forward(&[&pred, &target], &backend, false, 0).unwrap();
+    assert_eq!(ce.get_flat(0).unwrap(), CrossEntropyWithLogits.calculate(&pred, &target).unwrap());
+
+    let mismatched = t(&[1, 2], vec![1.0, 0.0]);
+    assert!(OpType::MSELoss.forward(&[&pred, &mismatched], &backend, false, 0).is_err());
+    assert!(OpType::MSELoss.output_shape(&[vec![2, 2], vec![1, 2]]).is_err());
+}
+
+#[test]
+fn test_cross_entropy_ops_require_batch_and_class_axes() {
+    let backend = CPUBackend;
+    let logits = t(&[3], vec![0.5, -1.0, 2.0]);
+    let target = t(&[3], vec![0.0, 0.0, 1.0]);
+    for op in [OpType::CrossEntropyWithLogitsLoss, OpType::CategoricalCrossEntropyLoss] {
+        assert!(matches!(op.output_shape(&[vec![3], vec![3]]), Err(GPError::InferenceError(_))));
+        assert!(matches!(op.forward(&[&logits, &target], &backend, false, 0), Err(GPError::InferenceError(_))));
+        assert!(op.output_shape(&[vec![2, 2, 3], vec![2, 2, 3]]).is_err());
+        assert_eq!(op.output_shape(&[vec![1, 3], vec![1, 3]]).unwrap(), vec![1]);
+    }
+    // Element-wise losses take any rank
+    assert!(OpType::MSELoss.forward(&[&logits, &target], &backend, false, 0).is_ok());
+}
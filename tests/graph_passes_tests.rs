@@ -0,0 +1,45 @@
+//! Tests for the fused ops produced by graph optimization passes.
+
+use gran_prix::graph::OpType;
+use gran_prix::backend::cpu::CPUBackend;
+use gran_prix::Tensor;
+
+mod common;
+use common::{check_gradients, t};
+
+#[test]
+fn test_fused_linear_matches_unfused_ops() {
+    let backend = CPUBackend;
+    let x = t(&[2, 3], vec![0.5, -1.0, 2.0, 1.5, 0.2, -0.7]);
+    let w = t(&[3, 2], vec![0.3, -0.6, 0.9, 0.1, -0.4, 0.8]);
+    let b = t(&[1, 2], vec![0.1, -0.3]);
+
+    for activation in [None, Some(OpType::Tanh), Some(OpType::ReLU), Some(OpType::LeakyReLU { slope: 0.1 })] {
+        let fused = OpType::FusedLinear { activation: activation.clone().map(Box::new) };
+        let out = fused.forward(&[&x, &w, &b], &backend, false, 0).unwrap();
+
+        let mm = OpType::MatMul.forward(&[&x, &w], &backend, false, 0).unwrap();
+        let mut expected = OpType::Add.forward(&[&mm, &b], &backend, false, 0).unwrap();
+        if let Some(act) = &activation {
+            expected = act.forward(&[&expected], &backend, false, 0).unwrap();
+        }
+        assert_eq!(out.shape(), &[2, 2]);
+        for (a, e) in out.as_slice().unwrap().iter().zip(expected.as_slice().unwrap()) {
+            assert!((a - e).abs() < 1e-6);
+        }
+
+        let mut inplace = Tensor::new_zeros(&[2, 2]);
+        fused.forward_inplace(&[&x, &w, &b], &mut inplace, &backend, false, 0).unwrap();
+        assert_eq!(inplace.as_slice().unwrap(), out.as_slice().unwrap());
+
+        if !matches!(activation, Some(OpType::ReLU) | Some(OpType::LeakyReLU { .. })) {
+            check_gradients(fused.clone(), vec![x.clone(), w.clone(), b.clone()]);
+        }
+    }
+
+    // A bias that isn't a row vector still broadcasts
+    let col = t(&[2, 1], vec![1.0, -1.0]);
+    let fused = OpType::FusedLinear { activation: Some(Box::new(OpType::Sigmoid)) };
+    check_gradients(fused.clone(), vec![x.clone(), w.clone(), col.clone()]);
+    assert_eq!(fused.output_shape(&[vec![2, 3], vec![3, 2], vec![2, 1]]).unwrap(), vec![2, 2]);
+}
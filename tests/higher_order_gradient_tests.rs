@@ -0,0 +1,308 @@
+//! Tests for symbolic differentiation: gradients emitted as graph nodes.
+
+use gran_prix::graph::{Graph, OpType, dsl::GraphBuilder};
+use gran_prix::backend::cpu::CPUBackend;
+use gran_prix::{NodeId, Tensor};
+
+mod common;
+use common::det;
+
+fn values(graph: &mut Graph, ids: &[NodeId]) -> Vec<Vec<f32>> {
+    graph.run(&[], ids).unwrap().iter().map(|t| t.as_slice().unwrap().to_vec()).collect()
+}
+
+fn assert_close(actual: &[f32], expected: &[f32], tolerance: f32) {
+    assert_eq!(actual.len(), expected.len());
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() <= tolerance * (1.0 + e.abs()), "{:?} != {:?}", actual, expected);
+    }
+}
+
+#[test]
+fn test_derivatives_of_cube() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let x = graph.input(Tensor::from_shape_vec(&[1, 3], vec![-1.0, 0.5, 2.0]).unwrap());
+    let y = graph.op(OpType::Pow { exponent: 3.0 }, vec![x]);
+
+    let dy = graph.gradients(y, &[x]).unwrap()[0];
+    let d2y = graph.gradients(dy, &[x]).unwrap()[0];
+    let d3y = graph.gradients(d2y, &[x]).unwrap()[0];
+
+    let result = values(&mut graph, &[dy, d2y, d3y]);
+    assert_close(&result[0], &[3.0, 0.75, 12.0], 1e-6);
+    assert_close(&result[1], &[-6.0, 3.0, 12.0], 1e-6);
+    assert_close(&result[2], &[6.0, 6.0, 6.0], 1e-6);
+}
+
+#[test]
+fn test_symbolic_gradients_match_backward() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let x = gb.batched_val(det(&[4, 3], 1));
+    let target = gb.batched_val(det(&[4, 2], 2));
+    let w1 = gb.param(det(&[3, 5], 3));
+    let b1 = gb.param(det(&[1, 5], 4));
+    let w2 = gb.param(det(&[5, 2], 5));
+    let b2 = gb.param(det(&[1, 2], 6));
+
+    let h = gb.linear(x, w1, b1);
+    let h = gb.tanh(h);
+    let scale = gb.reduce_mean(h, &[1], true);
+    let gate = gb.sigmoid(scale);
+    let h = gb.mul(h, gate);
+    let h = gb.relu(h);
+    let out = gb.linear(h, w2, b2);
+    // ReduceMax has no symbolic rule and goes through a first-order Gradient node
+    let peak = gb.reduce_max(out, &[1], true);
+    let out = gb.sub(out, peak);
+    let out = gb.softmax(out);
+    let loss = gb.mse_loss(out, target);
+
+    let params = [w1, b1, w2, b2];
+    let grads = graph.gradients(loss, &params).unwrap();
+    assert!(graph.nodes().iter().any(|n| matches!(n.op(), Some(OpType::Gradient { .. }))));
+
+    // Evaluated at several batch sizes, including the one the graph was built with
+    for batch in [4, 7] {
+        graph.feed(x, &det(&[batch, 3], 11)).unwrap();
+        graph.feed(target, &det(&[batch, 2], 12)).unwrap();
+        let symbolic = values(&mut graph, &grads);
+
+        graph.clear_gradients();
+        graph.execute(loss).unwrap();
+        graph.backward_scalar(loss).unwrap();
+        for (id, grad) in params.iter().zip(&symbolic) {
+            assert_close(grad, graph.get_gradient(*id).unwrap().as_slice().unwrap(), 1e-5);
+        }
+    }
+}
+
+#[test]
+fn test_hessian_vector_product_matches_finite_differences() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let x = gb.val(det(&[3, 4], 1));
+    let target = gb.val(det(&[3, 2], 2));
+    let w = gb.placeholder(&[4, 2]);
+    let b = gb.param(det(&[1, 2], 3));
+    let v = gb.val(det(&[4, 2], 4));
+    let pre = gb.linear(x, w, b);
+    let act = gb.sigmoid(pre);
+    let loss = gb.mse_loss(act, target);
+
+    // H·v = d/dw (∇w · v)
+    let grad_w = graph.gradients(loss, &[w]).unwrap()[0];
+    let dot = graph.op(OpType::Mul, vec![grad_w, v]);
+    let hvp = graph.gradients(dot, &[w]).unwrap()[0];
+
+    let w0 = det(&[4, 2], 5);
+    graph.feed(w, &w0).unwrap();
+    let symbolic = values(&mut graph, &[hvp]).remove(0);
+
+    // Central differences of the gradient along v
+    let eps = 1e-2;
+    let direction = det(&[4, 2], 4);
+    let mut grad_at = |sign: f32| {
+        let shifted = &w0 + &(&direction * (sign * eps));
+        graph.feed(w, &shifted).unwrap();
+        values(&mut graph, &[grad_w]).remove(0)
+    };
+    let (plus, minus) = (grad_at(1.0), grad_at(-1.0));
+    let numeric: Vec<f32> = plus.iter().zip(&minus).map(|(p, m)| (p - m) / (2.0 * eps)).collect();
+    assert_close(&symbolic, &numeric, 1e-2);
+}
+
+#[test]
+fn test_gradient_penalty_and_first_order_only_ops() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let x = gb.val(det(&[2, 3], 1));
+    let w = gb.param(det(&[3, 3], 2));
+    let out = gb.matmul(x, w);
+    let act = gb.tanh(out);
+    let score = gb.reduce_sum(act, &[], false);
+
+    // Penalize the input gradient's squared norm, then differentiate w.r.t. the weights
+    let grad_x = graph.gradients(score, &[x]).unwrap()[0];
+    let sq = graph.op(OpType::Mul, vec![grad_x, grad_x]);
+    let penalty = graph.op(OpType::ReduceSum { axes: Vec::new(), keep_dims: false }, vec![sq]);
+    let grad_w = graph.gradients(penalty, &[w]).unwrap()[0];
+    let result = values(&mut graph, &[penalty, grad_w]);
+    assert_eq!(result[0].len(), 1);
+    assert!(result[1].iter().all(|g| g.is_finite()) && result[1].iter().any(|&g| g != 0.0));
+
+    // Nodes the output does not depend on get zeros
+    let unused = graph.input(Tensor::new_ones(&[2, 2]));
+    let zero = graph.gradients(score, &[unused]).unwrap()[0];
+    assert_eq!(values(&mut graph, &[zero])[0], vec![0.0; 4]);
+
+    // Ops without a symbolic rule are differentiable once
+    let peak = graph.op(OpType::ReduceMax { axes: vec![1], keep_dims: true }, vec![out]);
+    let grad_peak = graph.gradients(peak, &[w]).unwrap()[0];
+    assert!(graph.gradients(grad_peak, &[w]).is_err());
+}
+
+#[test]
+fn test_activation_second_derivatives_match_finite_differences() {
+    let ops = [
+        OpType::LeakyReLU { slope: 0.1 },
+        OpType::ELU { alpha: 0.7 },
+        OpType::GELU,
+        OpType::SiLU,
+        OpType::HardTanh { min: -0.3, max: 0.3 },
+        OpType::Clamp { min: -0.2, max: 0.4 },
+        OpType::Abs,
+    ];
+    // Kept away from every kink by more than eps
+    let x0 = det(&[2, 5], 1).mapv(|v| v + 0.013);
+    let eps = 5e-3;
+    for op in ops {
+        let mut graph = Graph::new(Box::new(CPUBackend));
+        let x = GraphBuilder::new(&mut graph).placeholder(&[2, 5]);
+        let y = graph.op(op.clone(), vec![x]);
+        let dy = graph.gradients(y, &[x]).unwrap()[0];
+        let d2y = graph.gradients(dy, &[x]).unwrap()[0];
+        assert!(!graph.nodes().iter().any(|n| matches!(n.op(), Some(OpType::Gradient { .. }))));
+
+        // Element-wise ops: shifting every element at once differentiates each
+        let mut at = |shift: f32| {
+            graph.feed(x, &x0.mapv(|v| v + shift)).unwrap();
+            values(&mut graph, &[y, dy, d2y])
+        };
+        let (plus, minus, exact) = (at(eps), at(-eps), at(0.0));
+        for order in 0..2 {
+            let numeric: Vec<f32> = plus[order].iter().zip(&minus[order])
+                .map(|(p, m)| (p - m) / (2.0 * eps))
+                .collect();
+            assert_close(&exact[order + 1], &numeric, 1e-2);
+        }
+    }
+}
+
+#[test]
+fn test_gradient_norm_through_cross_entropy_with_logits() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let x = gb.val(det(&[4, 3], 1));
+    let labels = [2, 0, 3, 1];
+    let one_hot = (0..16).map(|i| if labels[i / 4] == i % 4 { 1.0 } else { 0.0 }).collect();
+    let target = gb.val(Tensor::from_shape_vec(&[4, 4], one_hot).unwrap());
+    let w = gb.placeholder(&[3, 4]);
+    let logits = gb.matmul(x, w);
+    let loss = gb.cross_entropy_with_logits_loss(logits, target);
+
+    // d/dw ‖∇w L‖², as in meta-learning through an inner step
+    let grad_w = graph.gradients(loss, &[w]).unwrap()[0];
+    let sq = graph.op(OpType::Mul, vec![grad_w, grad_w]);
+    let norm = graph.op(OpType::ReduceSum { axes: Vec::new(), keep_dims: false }, vec![sq]);
+    let meta = graph.gradients(norm, &[w]).unwrap()[0];
+    assert!(!graph.nodes().iter().any(|n| matches!(n.op(), Some(OpType::Gradient { .. }))));
+
+    let w0 = det(&[3, 4], 5);
+    graph.feed(w, &w0).unwrap();
+    let symbolic = values(&mut graph, &[meta]).remove(0);
+
+    let eps = 1e-2;
+    let w_values = w0.as_slice().unwrap().to_vec();
+    let mut norm_at = |i: usize, shift: f32| {
+        let mut shifted = w_values.clone();
+        shifted[i] += shift;
+        graph.feed(w, &Tensor::from_shape_vec(&[3, 4], shifted).unwrap()).unwrap();
+        values(&mut graph, &[norm])[0][0]
+    };
+    let numeric: Vec<f32> = (0..w_values.len())
+        .map(|i| (norm_at(i, eps) - norm_at(i, -eps)) / (2.0 * eps))
+        .collect();
+    assert_close(&symbolic, &numeric, 1e-2);
+}
+
+#[test]
+fn test_ops_without_a_rule_run_their_backward_once() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let x = gb.val(det(&[3, 4], 1));
+    let gamma = gb.param(det(&[1, 4], 2));
+    let beta = gb.param(det(&[1, 4], 3));
+    let out = gb.layer_norm(x, gamma, beta, 1e-5);
+    let loss = gb.reduce_sum(out, &[], false);
+    let loss = gb.tanh(loss);
+
+    let grads = graph.gradients(loss, &[x, gamma, beta]).unwrap();
+    let count = |graph: &Graph, name: &str| graph.nodes().iter()
+        .filter(|n| n.op().is_some_and(|op| op.name() == name))
+        .count();
+    assert_eq!(count(&graph, "Gradient"), 1);
+    assert_eq!(count(&graph, "SelectGradient"), 3);
+
+    let symbolic = values(&mut graph, &grads);
+    graph.clear_gradients();
+    graph.execute(loss).unwrap();
+    graph.backward_scalar(loss).unwrap();
+    for (id, grad) in [gamma, beta].iter().zip(&symbolic[1..]) {
+        assert_close(grad, graph.get_gradient(*id).unwrap().as_slice().unwrap(), 1e-5);
+    }
+    assert_eq!(symbolic[0].len(), 12);
+}
+
+/// `sum(concat(reshape(tanh(x·w + b)), skip)^2)`, returning the graph, `w`
+/// and the loss.
+fn reshaped_mlp() -> (Graph, NodeId, NodeId) {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let x = gb.batched_val(det(&[2, 3], 1));
+    let w = gb.param(det(&[3, 4], 2));
+    let b = gb.param(det(&[1, 4], 3));
+    let skip = gb.val(det(&[4, 1], 4));
+    let h = gb.linear(x, w, b);
+    let h = gb.tanh(h);
+    let rows = gb.reshape(h, vec![4, 2]);
+    let joined = gb.concat(vec![rows, skip], 1);
+    let sq = gb.mul(joined, joined);
+    let loss = gb.reduce_sum(sq, &[], false);
+    (graph, w, loss)
+}
+
+/// The gradient of `|d loss / d w|^2` w.r.t. `w`, built symbolically.
+fn gradient_penalty_grad(graph: &mut Graph, w: NodeId, loss: NodeId) -> Vec<f32> {
+    let grad_w = graph.gradients(loss, &[w]).unwrap()[0];
+    let sq = graph.op(OpType::Mul, vec![grad_w, grad_w]);
+    let penalty = graph.op(OpType::ReduceSum { axes: Vec::new(), keep_dims: false }, vec![sq]);
+    let grad = graph.gradients(penalty, &[w]).unwrap()[0];
+    values(graph, &[grad]).remove(0)
+}
+
+#[test]
+fn test_second_order_gradients_of_an_optimized_graph() {
+    let (mut plain, w, loss) = reshaped_mlp();
+    let expected = gradient_penalty_grad(&mut plain, w, loss);
+
+    let (mut graph, w, loss) = reshaped_mlp();
+    let map = graph.optimize(&[loss], &[]).unwrap();
+    let (w, loss) = (map.get(w).unwrap(), map.get(loss).unwrap());
+    let has = |graph: &Graph, name: &str| graph.nodes().iter().any(|n| n.op().is_some_and(|op| op.name() == name));
+    assert!(has(&graph, "FusedLinear"));
+
+    let actual = gradient_penalty_grad(&mut graph, w, loss);
+    assert!(!has(&graph, "Gradient"));
+    assert_close(&actual, &expected, 1e-4);
+}
+
+#[test]
+fn test_dropout_gradient_masks_like_backward() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let x = gb.val(det(&[4, 3], 1));
+    let w = gb.param(det(&[3, 8], 2));
+    let h = gb.matmul(x, w);
+    let h = gb.dropout(h, 0.5);
+    let h = gb.tanh(h);
+    let loss = gb.reduce_sum(h, &[], false);
+    graph.set_training(true);
+
+    let grad_w = graph.gradients(loss, &[w]).unwrap()[0];
+    assert!(!graph.nodes().iter().any(|n| n.op().is_some_and(|op| op.name() == "Gradient")));
+    let symbolic = values(&mut graph, &[loss, grad_w]).remove(1);
+    graph.clear_gradients();
+    graph.backward_scalar(loss).unwrap();
+    assert_close(&symbolic, graph.get_gradient(w).unwrap().as_slice().unwrap(), 1e-5);
+}
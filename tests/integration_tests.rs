@@ -243,9 +243,7 @@ fn train_xor(mut optimizer: Box<dyn Optimizer>, epochs: usize) -> f32 {
 
     for _epoch in 0..epochs {
         // Set input
-        if let Some(gran_prix::graph::Node::Input(ref mut t)) = graph.nodes_mut().get_mut(input_id.0) {
-            *t = xor_inputs.clone();
-        }
+        graph.feed(input_id, &xor_inputs).unwrap();
         graph.clear_values();
         graph.clear_gradients();
 
@@ -300,9 +298,7 @@ fn test_adam_loss_decreases_monotonically_early() {
 
     let mut losses = Vec::new();
     for _ in 0..50 {
-        if let Some(gran_prix::graph::Node::Input(ref mut t)) = graph.nodes_mut().get_mut(input_id.0) {
-            *t = Tensor::from_shape_vec(&[1, 2], inp.clone()).unwrap();
-        }
+        graph.feed(input_id, &Tensor::from_shape_vec(&[1, 2], inp.clone()).unwrap()).unwrap();
         graph.clear_values();
         graph.clear_gradients();
 
@@ -402,9 +398,7 @@ fn test_network_def_compile_train_infer() {
 
     // Train on a simple pattern: [1, 0] → 1.0
     for _ in 0..200 {
-        if let Some(gran_prix::graph::Node::Input(ref mut t)) = graph.nodes_mut().get_mut(input_id.0) {
-            *t = Tensor::from_shape_vec(&[1, 2], vec![1.0, 0.0]).unwrap();
-        }
+        graph.feed(input_id, &Tensor::from_shape_vec(&[1, 2], vec![1.0, 0.0]).unwrap()).unwrap();
         graph.clear_values();
         graph.clear_gradients();
 
@@ -416,9 +410,7 @@ fn test_network_def_compile_train_infer() {
     }
 
     // Inference
-    if let Some(gran_prix::graph::Node::Input(ref mut t)) = graph.nodes_mut().get_mut(input_id.0) {
-        *t = Tensor::from_shape_vec(&[1, 2], vec![1.0, 0.0]).unwrap();
-    }
+    graph.feed(input_id, &Tensor::from_shape_vec(&[1, 2], vec![1.0, 0.0]).unwrap()).unwrap();
     graph.clear_values();
     let pred = graph.execute(output_id).unwrap();
     let val = pred.get_2d(0, 0).unwrap();
@@ -563,9 +555,7 @@ fn test_multiclass_training_convergence() {
     let mut last_loss = 0.0;
 
     for epoch in 0..500 {
-        if let Some(gran_prix::graph::Node::Input(ref mut t)) = graph.nodes_mut().get_mut(input_id.0) {
-            *t = inputs.clone();
-        }
+        graph.feed(input_id, &inputs).unwrap();
         graph.clear_values();
         graph.clear_gradients();
 
@@ -586,9 +576,7 @@ fn test_multiclass_training_convergence() {
         "Multi-class training should converge. Final loss: {}", last_loss);
 
     // Verify predictions: argmax of logits should match class
-    if let Some(gran_prix::graph::Node::Input(ref mut t)) = graph.nodes_mut().get_mut(input_id.0) {
-        *t = inputs.clone();
-    }
+    graph.feed(input_id, &inputs).unwrap();
     graph.clear_values();
     let logits = graph.execute(output_id).unwrap();
     let s = logits.as_slice().unwrap();
@@ -0,0 +1,111 @@
+//! Tests for pulling trained parameters back into layer structs.
+
+use gran_prix::graph::{Graph, dsl::GraphBuilder};
+use gran_prix::backend::cpu::CPUBackend;
+use gran_prix::layers::{BatchNorm, GRUCell, Linear};
+use gran_prix::optim::{Optimizer, SGD};
+use gran_prix::{GPError, Layer, NodeId, Tensor};
+
+fn x() -> Tensor {
+    Tensor::from_shape_vec(&[2, 3], vec![0.5, -1.0, 2.0, 1.5, 0.0, -0.5]).unwrap()
+}
+
+/// Builds `layer(x)` with an MSE loss against zeros and takes a few SGD steps.
+fn train(layer: &mut dyn Layer, out_dim: usize) -> Graph {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let input = gb.val(x());
+    let out = layer.forward(input, &mut gb);
+    let target = gb.val(Tensor::new_zeros(&[2, out_dim]));
+    let loss = gb.mse_loss(out, target);
+
+    let mut optimizer = SGD::new(0.1, 0.0, 0.0);
+    graph.set_training(true);
+    for _ in 0..3 {
+        graph.clear_gradients();
+        graph.execute(loss).unwrap();
+        graph.backward_scalar(loss).unwrap();
+        optimizer.step(graph.params_mut()).unwrap();
+    }
+    graph
+}
+
+fn output(graph: &mut Graph, node: NodeId) -> Vec<f32> {
+    graph.execute(node).unwrap().as_slice().unwrap().to_vec()
+}
+
+#[test]
+fn test_linear_pull_params_persists_training() {
+    let mut layer = Linear::new(3, 2);
+    let initial = layer.weights.clone();
+    let graph = train(&mut layer, 2);
+    let trained = graph.params().tensor(layer.binding.ids()[0]).clone();
+    assert_ne!(initial.as_slice().unwrap(), trained.as_slice().unwrap());
+
+    layer.pull_params(graph.params()).unwrap();
+    assert_eq!(layer.weights.as_slice().unwrap(), trained.as_slice().unwrap());
+
+    // typetag round trip now carries the trained weights.
+    let boxed: Box<dyn Layer> = Box::new(layer);
+    let json = serde_json::to_string(&boxed).unwrap();
+    let mut restored: Box<dyn Layer> = serde_json::from_str(&json).unwrap();
+
+    let mut fresh = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut fresh);
+    let input = gb.val(x());
+    let out = restored.forward(input, &mut gb);
+    let w = fresh.params().tensor(gran_prix::ParamId(0)).clone();
+    assert_eq!(w.as_slice().unwrap(), trained.as_slice().unwrap());
+    assert_eq!(output(&mut fresh, out).len(), 4);
+}
+
+#[test]
+fn test_push_params_writes_layer_fields_into_store() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let input = gb.val(x());
+    let mut layer = Linear::new(3, 2);
+    let out = layer.forward(input, &mut gb);
+
+    layer.weights = Tensor::new_ones(&[3, 2]);
+    layer.biases = Tensor::from_elem(&[1, 2], 0.5);
+    layer.push_params(graph.params_mut()).unwrap();
+    assert_eq!(output(&mut graph, out), vec![2.0, 2.0, 1.5, 1.5]);
+}
+
+#[test]
+fn test_pull_params_requires_a_binding_to_the_store() {
+    let mut layer = Linear::new(3, 2);
+    let other = Graph::new(Box::new(CPUBackend));
+    assert!(matches!(
+        layer.pull_params(other.params()),
+        Err(GPError::UnboundParams { expected: 2, bound: 0 })
+    ));
+
+    let _graph = train(&mut layer, 2);
+    // Bound, but to a different graph's store.
+    assert!(layer.pull_params(other.params()).is_err());
+}
+
+#[test]
+fn test_batchnorm_pull_params_includes_running_stats() {
+    let mut layer = BatchNorm::new(3);
+    let graph = train(&mut layer, 3);
+    layer.pull_params(graph.params()).unwrap();
+
+    let ids = layer.binding.ids().to_vec();
+    assert_eq!(ids.len(), 4);
+    assert_eq!(layer.running_mean.as_slice().unwrap(), graph.params().tensor(ids[2]).as_slice().unwrap());
+    assert_ne!(layer.running_mean.as_slice().unwrap(), &[0.0, 0.0, 0.0]);
+    assert_ne!(layer.gamma.as_slice().unwrap(), &[1.0, 1.0, 1.0]);
+}
+
+#[test]
+fn test_gru_pull_params_updates_gate_weights() {
+    let mut layer = GRUCell::new(3, 2);
+    let initial = layer.wn_ih.clone();
+    let graph = train(&mut layer, 2);
+    layer.pull_params(graph.params()).unwrap();
+    assert_ne!(layer.wn_ih.as_slice().unwrap(), initial.as_slice().unwrap());
+    assert_eq!(layer.wn_ih.as_slice().unwrap(), graph.params().tensor(layer.binding.ids()[8]).as_slice().unwrap());
+}
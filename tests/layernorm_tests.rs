@@ -0,0 +1,90 @@
+//! Tests for LayerNorm correctness.
+
+use gran_prix::graph::{Graph, dsl::GraphBuilder, OpType};
+use gran_prix::backend::cpu::CPUBackend;
+use gran_prix::layers::LayerNorm;
+use gran_prix::{Layer, Tensor};
+
+mod common;
+use common::{check_gradients, t};
+
+#[test]
+fn test_layernorm_normalizes_each_sample() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let x = gb.val(Tensor::from_shape_vec(&[2, 4], vec![
+        1.0, 2.0, 3.0, 4.0,
+        10.0, 30.0, 20.0, 40.0,
+    ]).unwrap());
+    let mut ln = LayerNorm::new(4);
+    let y = ln.forward(x, &mut gb);
+
+    let out = graph.execute(y).unwrap();
+    let slice = out.as_slice().unwrap();
+    for row in slice.chunks(4) {
+        let mean: f32 = row.iter().sum::<f32>() / 4.0;
+        let var: f32 = row.iter().map(|v| (v - mean) * (v - mean)).sum::<f32>() / 4.0;
+        assert!(mean.abs() < 1e-5, "row mean should be ~0, got {}", mean);
+        assert!((var - 1.0).abs() < 1e-3, "row variance should be ~1, got {}", var);
+    }
+}
+
+#[test]
+fn test_layernorm_batch_size_one_is_not_degenerate() {
+    // BatchNorm collapses to an affine transform at batch size 1; LayerNorm
+    // must still normalize, and give the same row as in a larger batch.
+    let single = Tensor::from_shape_vec(&[1, 3], vec![2.0, 4.0, 9.0]).unwrap();
+    let batch = Tensor::from_shape_vec(&[2, 3], vec![2.0, 4.0, 9.0, -1.0, 0.0, 1.0]).unwrap();
+    let gamma = Tensor::from_shape_vec(&[1, 3], vec![1.0, 2.0, 0.5]).unwrap();
+    let beta = Tensor::from_shape_vec(&[1, 3], vec![0.0, 1.0, -1.0]).unwrap();
+    let op = OpType::LayerNorm { epsilon: 1e-5 };
+    let backend = CPUBackend;
+
+    let out_single = op.forward(&[&single, &gamma, &beta], &backend, false, 0).unwrap();
+    let out_batch = op.forward(&[&batch, &gamma, &beta], &backend, false, 0).unwrap();
+
+    let s = out_single.as_slice().unwrap();
+    assert_ne!(s, &[2.0, 9.0, 3.5], "must not fall back to gamma * x + beta");
+    for (a, b) in s.iter().zip(&out_batch.as_slice().unwrap()[..3]) {
+        assert!((a - b).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn test_layernorm_backward_reaches_gamma_and_beta() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let x = gb.val(Tensor::from_shape_vec(&[1, 3], vec![0.5, -1.0, 2.0]).unwrap());
+    let gamma = gb.param(Tensor::new_ones(&[1, 3]));
+    let beta = gb.param(Tensor::new_zeros(&[1, 3]));
+    let y = gb.layer_norm(x, gamma, beta, 1e-5);
+
+    graph.execute(y).unwrap();
+    graph.backward(y, Tensor::from_shape_vec(&[1, 3], vec![1.0, 2.0, 3.0]).unwrap()).unwrap();
+
+    assert_eq!(graph.get_gradient(beta).unwrap().as_slice().unwrap(), &[1.0, 2.0, 3.0]);
+    assert_eq!(graph.get_gradient(gamma).unwrap().shape(), &[1, 3]);
+}
+
+#[test]
+fn test_layernorm_rejects_mismatched_affine() {
+    let op = OpType::LayerNorm { epsilon: 1e-5 };
+    assert!(op.output_shape(&[vec![2, 4], vec![1, 3], vec![1, 4]]).is_err());
+    assert_eq!(op.output_shape(&[vec![2, 4], vec![1, 4], vec![1, 4]]).unwrap(), vec![2, 4]);
+}
+
+#[test]
+fn test_layernorm_gradients() {
+    let x = t(&[3, 4], vec![
+        0.3, -1.2, 2.0, 0.7,
+        1.0, 1.5, -0.5, 0.2,
+        -2.0, 0.4, 0.9, -0.1,
+    ]);
+    let gamma = t(&[1, 4], vec![1.0, 0.5, -1.5, 2.0]);
+    let beta = t(&[1, 4], vec![0.1, -0.2, 0.0, 0.3]);
+    check_gradients(OpType::LayerNorm { epsilon: 1e-5 }, vec![x.clone(), gamma.clone(), beta.clone()]);
+
+    // Single sample: still a real normalization, so gradients must match too.
+    let row = t(&[1, 4], vec![0.3, -1.2, 2.0, 0.7]);
+    check_gradients(OpType::LayerNorm { epsilon: 1e-5 }, vec![row, gamma, beta]);
+}
@@ -0,0 +1,96 @@
+//! Tests for the LSTM cell: gate math and hidden/cell state handling.
+
+use gran_prix::graph::{Graph, dsl::GraphBuilder};
+use gran_prix::backend::cpu::CPUBackend;
+use gran_prix::layers::LSTMCell;
+use gran_prix::{Layer, Tensor};
+
+fn scalar(v: f32) -> Tensor {
+    Tensor::from_elem(&[1, 1], v)
+}
+
+/// A 1→1 cell with distinct per-gate weights so gate mix-ups show up.
+fn scalar_cell() -> LSTMCell {
+    let mut cell = LSTMCell::new(1, 1);
+    cell.wi_ih = scalar(0.5); cell.wi_hh = scalar(-0.3); cell.bi_ih = scalar(0.1); cell.bi_hh = scalar(0.0);
+    cell.wf_ih = scalar(-0.4); cell.wf_hh = scalar(0.2); cell.bf_ih = scalar(1.0); cell.bf_hh = scalar(0.0);
+    cell.wg_ih = scalar(0.9); cell.wg_hh = scalar(0.6); cell.bg_ih = scalar(-0.2); cell.bg_hh = scalar(0.0);
+    cell.wo_ih = scalar(0.7); cell.wo_hh = scalar(-0.8); cell.bo_ih = scalar(0.3); cell.bo_hh = scalar(0.0);
+    cell
+}
+
+fn reference_step(x: f32, h: f32, c: f32) -> (f32, f32) {
+    let sig = |v: f32| 1.0 / (1.0 + (-v).exp());
+    let i = sig(0.5 * x - 0.3 * h + 0.1);
+    let f = sig(-0.4 * x + 0.2 * h + 1.0);
+    let g = (0.9 * x + 0.6 * h - 0.2).tanh();
+    let o = sig(0.7 * x - 0.8 * h + 0.3);
+    let c_next = f * c + i * g;
+    (o * c_next.tanh(), c_next)
+}
+
+/// Runs one step in a fresh graph and writes both states back into the cell.
+fn run_step(cell: &mut LSTMCell, x: f32) -> f32 {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let input = gb.val(scalar(x));
+    let h = cell.forward(input, &mut gb);
+    let out = graph.execute(h).unwrap();
+
+    let states: Vec<Tensor> = cell.state_nodes().iter()
+        .map(|id| graph.values()[id.0].clone().unwrap())
+        .collect();
+    assert_eq!(states.len(), 2, "LSTM exposes hidden and cell state");
+    cell.update_states(states);
+    out.as_slice().unwrap()[0]
+}
+
+#[test]
+fn test_lstm_matches_reference_over_steps() {
+    let mut cell = scalar_cell();
+    let (mut h, mut c) = (0.0, 0.0);
+    for &x in &[1.0, -0.5, 2.0] {
+        let got = run_step(&mut cell, x);
+        (h, c) = reference_step(x, h, c);
+        assert!((got - h).abs() < 1e-5, "h: got {} want {}", got, h);
+        let cell_state = cell.cell_state.as_ref().unwrap().as_slice().unwrap()[0];
+        assert!((cell_state - c).abs() < 1e-5, "c: got {} want {}", cell_state, c);
+    }
+}
+
+#[test]
+fn test_lstm_reset_clears_both_states() {
+    let mut cell = scalar_cell();
+    let first = run_step(&mut cell, 1.0);
+    run_step(&mut cell, 1.0);
+    assert!(cell.hidden_state.is_some() && cell.cell_state.is_some());
+
+    cell.reset_state();
+    assert!(cell.hidden_state.is_none() && cell.cell_state.is_none());
+    assert_eq!(run_step(&mut cell, 1.0), first);
+}
+
+#[test]
+fn test_lstm_gradients_reach_all_gates() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let input = gb.val(Tensor::new_ones(&[1, 3]));
+    let mut cell = LSTMCell::new(3, 4);
+    cell.hidden_state = Some(Tensor::from_elem(&[1, 4], 0.5));
+    cell.cell_state = Some(Tensor::from_elem(&[1, 4], -0.5));
+    let h = cell.forward(input, &mut gb);
+
+    graph.execute(h).unwrap();
+    graph.backward(h, Tensor::new_ones(&[1, 4])).unwrap();
+    let params = graph.params();
+    // Weight and bias of both projections for each of the four gates
+    let ids = cell.binding.ids();
+    assert_eq!(ids.len(), 16);
+    assert_eq!(params.len(), 16);
+    for &id in ids {
+        let grad = params.gradient(id)
+            .unwrap_or_else(|| panic!("gate parameter {} has no gradient", params.name(id)));
+        let norm = grad.as_slice().unwrap().iter().map(|g| g * g).sum::<f32>().sqrt();
+        assert!(norm > 0.0, "gate parameter {} has a zero gradient", params.name(id));
+    }
+}
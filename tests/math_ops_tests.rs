@@ -0,0 +1,240 @@
+use gran_prix::graph::{Graph, OpType};
+use gran_prix::graph::dsl::GraphBuilder;
+use gran_prix::backend::cpu::CPUBackend;
+use gran_prix::Tensor;
+
+mod common;
+use common::{check_gradients, t};
+
+#[test]
+fn test_binary_op_gradients_with_broadcasting() {
+    let a = t(&[2, 3], vec![1.0, -2.0, 3.0, 0.5, 1.5, -0.7]);
+    let b = t(&[1, 3], vec![2.0, -1.5, 0.8]);
+    check_gradients(OpType::Sub, vec![a.clone(), b.clone()]);
+    check_gradients(OpType::Sub, vec![b.clone(), a.clone()]);
+    check_gradients(OpType::Div, vec![a.clone(), b.clone()]);
+    check_gradients(OpType::Div, vec![b, a]);
+}
+
+#[test]
+fn test_unary_op_gradients() {
+    let any = t(&[2, 2], vec![-1.3, 0.4, 2.0, -0.2]);
+    let positive = t(&[2, 2], vec![0.5, 1.2, 3.0, 0.9]);
+
+    check_gradients(OpType::Neg, vec![any.clone()]);
+    check_gradients(OpType::Exp, vec![any.clone()]);
+    check_gradients(OpType::Abs, vec![any.clone()]);
+    check_gradients(OpType::Clamp { min: -1.0, max: 1.0 }, vec![any.clone()]);
+    check_gradients(OpType::Pow { exponent: 3.0 }, vec![any]);
+    check_gradients(OpType::Pow { exponent: -0.5 }, vec![positive.clone()]);
+    check_gradients(OpType::Log, vec![positive.clone()]);
+    check_gradients(OpType::Sqrt, vec![positive]);
+}
+
+#[test]
+fn test_activation_gradients() {
+    let x = t(&[2, 3], vec![-2.5, -0.7, -0.1, 0.3, 1.1, 3.0]);
+
+    check_gradients(OpType::LeakyReLU { slope: 0.1 }, vec![x.clone()]);
+    check_gradients(OpType::ELU { alpha: 1.5 }, vec![x.clone()]);
+    check_gradients(OpType::GELU, vec![x.clone()]);
+    check_gradients(OpType::SiLU, vec![x.clone()]);
+    check_gradients(OpType::Softplus, vec![x.clone()]);
+    check_gradients(OpType::HardTanh { min: -1.0, max: 1.0 }, vec![x]);
+}
+
+#[test]
+fn test_activation_forward_values() {
+    let backend = Box::new(CPUBackend);
+    let mut graph = Graph::new(backend);
+    let mut gb = GraphBuilder::new(&mut graph);
+
+    let x = gb.val(t(&[1, 4], vec![-2.0, -0.5, 0.5, 2.0]));
+    let big = gb.val(t(&[1, 2], vec![-100.0, 100.0]));
+    let leaky = gb.leaky_relu(x, 0.1);
+    let elu = gb.elu(x, 1.0);
+    let gelu = gb.gelu(x);
+    let silu = gb.silu(x);
+    let softplus = gb.softplus(big);
+    let hardtanh = gb.hardtanh(x, -1.0, 1.0);
+
+    let sig = |v: f32| 1.0 / (1.0 + (-v).exp());
+    let cases = [
+        (leaky, vec![-0.2, -0.05, 0.5, 2.0]),
+        (elu, vec![(-2.0f32).exp_m1(), (-0.5f32).exp_m1(), 0.5, 2.0]),
+        (gelu, vec![-0.045_402, -0.154_286, 0.345_714, 1.954_598]),
+        (silu, vec![-2.0 * sig(-2.0), -0.5 * sig(-0.5), 0.5 * sig(0.5), 2.0 * sig(2.0)]),
+        (softplus, vec![0.0, 100.0]),
+        (hardtanh, vec![-1.0, -0.5, 0.5, 1.0]),
+    ];
+    // Run twice so the in-place path is exercised as well.
+    for _ in 0..2 {
+        for (node, expected) in &cases {
+            let out = graph.execute(*node).unwrap();
+            for (got, want) in out.as_slice().unwrap().iter().zip(expected) {
+                assert!((got - want).abs() < 1e-4, "got {} want {}", got, want);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_elementwise_forward_values() {
+    let backend = Box::new(CPUBackend);
+    let mut graph = Graph::new(backend);
+    let mut gb = GraphBuilder::new(&mut graph);
+
+    let x = gb.val(t(&[1, 4], vec![-4.0, -1.0, 1.0, 4.0]));
+    let two = gb.val(Tensor::from_elem(&[1, 1], 2.0));
+    let abs = gb.abs(x);
+    let sqrt = gb.sqrt(abs);
+    let clamp = gb.clamp(x, -2.0, 2.0);
+    let neg = gb.neg(x);
+    let sub = gb.sub(x, two);
+    let div = gb.div(x, two);
+    let pow = gb.pow(x, 2.0);
+    let zero = gb.val(Tensor::new_zeros(&[1, 1]));
+    let exp = gb.exp(zero);
+    let log = gb.log(exp);
+
+    let cases = [
+        (sqrt, vec![2.0, 1.0, 1.0, 2.0]),
+        (clamp, vec![-2.0, -1.0, 1.0, 2.0]),
+        (neg, vec![4.0, 1.0, -1.0, -4.0]),
+        (sub, vec![-6.0, -3.0, -1.0, 2.0]),
+        (div, vec![-2.0, -0.5, 0.5, 2.0]),
+        (pow, vec![16.0, 1.0, 1.0, 16.0]),
+        (exp, vec![1.0]),
+        (log, vec![0.0]),
+    ];
+    // Run twice so the in-place path is exercised as well.
+    for _ in 0..2 {
+        for (node, expected) in &cases {
+            let out = graph.execute(*node).unwrap();
+            assert_eq!(out.as_slice().unwrap(), expected.as_slice());
+        }
+    }
+}
+
+#[test]
+fn test_sub_gradient_reduces_broadcast_operand() {
+    let backend = Box::new(CPUBackend);
+    let mut graph = Graph::new(backend);
+    let mut gb = GraphBuilder::new(&mut graph);
+
+    let x = gb.val(t(&[3, 2], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]));
+    let b = gb.param(t(&[1, 2], vec![0.5, -0.5]));
+    let y = gb.sub(x, b);
+
+    graph.execute(y).unwrap();
+    graph.backward(y, Tensor::new_ones(&[3, 2])).unwrap();
+    assert_eq!(*graph.get_gradient(b).unwrap(), t(&[1, 2], vec![-3.0, -3.0]));
+}
+
+#[test]
+fn test_reduction_gradients() {
+    let x = t(&[2, 3, 2], vec![
+        1.0, -2.0, 3.0, 0.5, 1.5, -0.7,
+        2.5, 0.1, -1.0, 4.0, 0.3, 0.2,
+    ]);
+    for (axes, keep_dims) in [(vec![], false), (vec![1], false), (vec![0, 2], true), (vec![2, 0], false)] {
+        check_gradients(OpType::ReduceSum { axes: axes.clone(), keep_dims }, vec![x.clone()]);
+        check_gradients(OpType::ReduceMean { axes: axes.clone(), keep_dims }, vec![x.clone()]);
+        check_gradients(OpType::ReduceMax { axes, keep_dims }, vec![x.clone()]);
+    }
+}
+
+#[test]
+fn test_reduction_forward_values_and_shapes() {
+    let backend = Box::new(CPUBackend);
+    let mut graph = Graph::new(backend);
+    let mut gb = GraphBuilder::new(&mut graph);
+
+    let x = gb.val(t(&[2, 3], vec![1.0, 5.0, 3.0, 4.0, 2.0, 6.0]));
+    let total = gb.reduce_sum(x, &[], false);
+    let col_sum = gb.reduce_sum(x, &[0], true);
+    let row_mean = gb.reduce_mean(x, &[1], false);
+    let row_max = gb.reduce_max(x, &[1], true);
+    let row_argmax = gb.argmax(x, 1, false);
+    let col_argmax = gb.argmax(x, 0, true);
+
+    let cases = [
+        (total, vec![1], vec![21.0]),
+        (col_sum, vec![1, 3], vec![5.0, 7.0, 9.0]),
+        (row_mean, vec![2], vec![3.0, 4.0]),
+        (row_max, vec![2, 1], vec![5.0, 6.0]),
+        (row_argmax, vec![2], vec![1.0, 2.0]),
+        (col_argmax, vec![1, 3], vec![1.0, 0.0, 1.0]),
+    ];
+    let predicted = gran_prix::graph::verifier::Verifier::verify(&graph).unwrap();
+    for (node, shape, expected) in &cases {
+        let out = graph.execute(*node).unwrap();
+        assert_eq!(out.shape(), shape.as_slice());
+        assert_eq!(&predicted[node], shape);
+        assert_eq!(out.as_slice().unwrap(), expected.as_slice());
+    }
+}
+
+#[test]
+fn test_reduce_max_routes_gradient_to_first_max() {
+    let backend = CPUBackend;
+    let x = t(&[1, 4], vec![2.0, 7.0, 7.0, 1.0]);
+    let op = OpType::ReduceMax { axes: vec![1], keep_dims: false };
+    let out = op.forward(&[&x], &backend, false, 0).unwrap();
+    let grads = op.backward(&[&x], Some(&out), &t(&[1], vec![3.0]), &backend).unwrap();
+    assert_eq!(grads[0].as_slice().unwrap(), &[0.0, 3.0, 0.0, 0.0]);
+
+    let argmax = OpType::ArgMax { axis: 1, keep_dims: false };
+    let idx = argmax.forward(&[&x], &backend, false, 0).unwrap();
+    assert_eq!(idx.as_slice().unwrap(), &[1.0]);
+    let zero = argmax.backward(&[&x], Some(&idx), &t(&[1], vec![1.0]), &backend).unwrap();
+    assert_eq!(zero[0], Tensor::new_zeros(&[1, 4]));
+}
+
+#[test]
+fn test_reduction_rejects_out_of_range_axis() {
+    let backend = CPUBackend;
+    let x = Tensor::new_zeros(&[2, 3]);
+    assert!(OpType::ReduceSum { axes: vec![2], keep_dims: false }.forward(&[&x], &backend, false, 0).is_err());
+    assert!(OpType::ArgMax { axis: 5, keep_dims: false }.output_shape(&[vec![2, 3]]).is_err());
+}
+
+#[test]
+fn test_concat_forward_and_gradients() {
+    let backend = CPUBackend;
+    let a = t(&[2, 2], vec![1.0, 2.0, 3.0, 4.0]);
+    let b = t(&[2, 1], vec![5.0, 6.0]);
+
+    let out = OpType::Concat { axis: 1 }.forward(&[&a, &b], &backend, false, 0).unwrap();
+    assert_eq!(out.shape(), &[2, 3]);
+    assert_eq!(out.as_slice().unwrap(), &[1.0, 2.0, 5.0, 3.0, 4.0, 6.0]);
+
+    let rows = OpType::Concat { axis: 0 }.forward(&[&a, &a], &backend, false, 0).unwrap();
+    assert_eq!(rows.shape(), &[4, 2]);
+    assert_eq!(rows.as_slice().unwrap(), &[1.0, 2.0, 3.0, 4.0, 1.0, 2.0, 3.0, 4.0]);
+
+    check_gradients(OpType::Concat { axis: 1 }, vec![a.clone(), b.clone(), a.clone()]);
+    check_gradients(OpType::Concat { axis: 0 }, vec![a.clone(), t(&[1, 2], vec![7.0, 8.0])]);
+
+    assert_eq!(OpType::Concat { axis: 1 }.output_shape(&[vec![2, 2], vec![2, 1]]).unwrap(), vec![2, 3]);
+    assert!(OpType::Concat { axis: 1 }.output_shape(&[vec![2, 2], vec![3, 1]]).is_err());
+    assert!(OpType::Concat { axis: 2 }.output_shape(&[vec![2, 2], vec![2, 2]]).is_err());
+}
+
+#[test]
+fn test_reshape_infers_minus_one() {
+    let backend = CPUBackend;
+    let x = t(&[2, 2, 3], (0..12).map(|i| i as f32).collect());
+
+    let out = OpType::Reshape { target_shape: vec![-1, 6] }.forward(&[&x], &backend, false, 0).unwrap();
+    assert_eq!(out.shape(), &[2, 6]);
+    assert_eq!(out.as_slice().unwrap(), x.as_slice().unwrap());
+    check_gradients(OpType::Reshape { target_shape: vec![3, -1] }, vec![x.clone()]);
+
+    let reshape = |target: Vec<isize>| OpType::Reshape { target_shape: target };
+    assert_eq!(reshape(vec![-1, 4]).output_shape(&[vec![5, 2, 2]]).unwrap(), vec![5, 4]);
+    assert_eq!(reshape(vec![2, 6]).output_shape(&[vec![4, 3]]).unwrap(), vec![2, 6]);
+    assert!(reshape(vec![-1, -1]).output_shape(&[vec![4, 3]]).is_err());
+    assert!(reshape(vec![-1, 5]).output_shape(&[vec![4, 3]]).is_err());
+    assert!(reshape(vec![-2, 6]).output_shape(&[vec![4, 3]]).is_err());
+}
@@ -0,0 +1,94 @@
+//! Tests for level-parallel execution of independent branches (`rayon`
+//! feature). Without the feature both runs are serial and must still agree.
+
+use gran_prix::graph::{Graph, dsl::GraphBuilder, engine::PARALLEL_MIN_ELEMENTS};
+use gran_prix::backend::cpu::CPUBackend;
+use gran_prix::layers::GRUCell;
+use gran_prix::{Layer, NodeId, Tensor};
+
+/// A GRU step (three independent gate projections) with dropout and an MSE loss.
+fn gru_graph() -> (Graph, NodeId, NodeId) {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let x = gb.val(Tensor::from_shape_vec(&[1, 3], vec![0.5, -1.0, 2.0]).unwrap());
+    let target = gb.val(Tensor::from_shape_vec(&[1, 4], vec![0.1, 0.2, -0.3, 0.4]).unwrap());
+    let mut cell = GRUCell::new(3, 4);
+    let h = cell.forward(x, &mut gb);
+    let h = gb.dropout(h, 0.3);
+    let loss = gb.mse_loss(h, target);
+    // Far below the default work threshold: fan out every level regardless
+    graph.set_parallel_threshold(0);
+    (graph, h, loss)
+}
+
+fn train_step(graph: &mut Graph, output: NodeId, loss: NodeId) -> (Vec<f32>, Vec<Vec<f32>>) {
+    graph.clear_gradients();
+    graph.execute(loss).unwrap();
+    graph.backward_scalar(loss).unwrap();
+    let out = graph.values()[output.0].as_ref().unwrap().to_vec().unwrap();
+    let grads = graph.params().trainable_param_ids().into_iter()
+        .map(|id| graph.params().gradient(id).unwrap().to_vec().unwrap())
+        .collect();
+    (out, grads)
+}
+
+#[test]
+fn test_gru_gates_form_parallel_levels() {
+    let (mut graph, _, loss) = gru_graph();
+    let plan = graph.plan(&[loss]).unwrap();
+    // The three input projections (and the two hidden ones) share a level
+    assert!(plan.width() >= 3);
+    for (depth, level) in plan.levels().iter().enumerate() {
+        for &step in level {
+            for input in plan.inputs(step) {
+                let producer = plan.order().iter().position(|id| id == input).unwrap();
+                assert!(plan.levels()[..depth].iter().any(|l| l.contains(&producer)));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_parallel_matches_serial_exactly() {
+    let (mut graph, output, loss) = gru_graph();
+    graph.set_training(true);
+
+    let mut runs = Vec::new();
+    for parallel in [true, false, true] {
+        graph.set_parallel(parallel);
+        graph.engine_mut().unwrap().set_rng_counter(0);
+        runs.push(train_step(&mut graph, output, loss));
+    }
+    assert_eq!(runs[0], runs[1]);
+    assert_eq!(runs[0], runs[2]);
+}
+
+#[test]
+fn test_small_levels_stay_serial_by_default() {
+    let (mut graph, output, loss) = gru_graph();
+    graph.set_training(true);
+
+    let mut runs = Vec::new();
+    for threshold in [0, PARALLEL_MIN_ELEMENTS] {
+        graph.set_parallel_threshold(threshold);
+        assert_eq!(graph.engine_mut().unwrap().parallel_threshold(), threshold);
+        graph.engine_mut().unwrap().set_rng_counter(3);
+        runs.push(train_step(&mut graph, output, loss));
+    }
+    assert_eq!(runs[0], runs[1]);
+}
+
+#[test]
+fn test_parallel_checkpointing_matches_serial() {
+    let (mut graph, output, loss) = gru_graph();
+    graph.set_training(true);
+    graph.set_activation_checkpoints(&[output]);
+
+    let mut runs = Vec::new();
+    for parallel in [true, false] {
+        graph.set_parallel(parallel);
+        graph.engine_mut().unwrap().set_rng_counter(7);
+        runs.push(train_step(&mut graph, output, loss));
+    }
+    assert_eq!(runs[0], runs[1]);
+}
@@ -1,7 +1,8 @@
 use gran_prix::graph::{Graph, Operation};
 use gran_prix::graph::dsl::GraphBuilder;
 use gran_prix::backend::cpu::CPUBackend;
-use gran_prix::{Tensor, GPResult};
+use gran_prix::layers::Linear;
+use gran_prix::{GPResult, Layer, Tensor};
 
 use serde::{Serialize, Deserialize};
 
@@ -48,3 +49,40 @@ fn test_full_persistence_with_custom_op() {
     let result_loaded = new_graph.execute(node).unwrap();
     assert_eq!(result_loaded, Tensor::from_shape_vec(&[1, 2], vec![3.0, 3.0]).unwrap());
 }
+
+/// Registers scoped `Linear`s named `scopes` and returns the graph with its layers.
+fn scoped_linears(scopes: &[&str]) -> (Graph, Vec<Linear>) {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let mut node = gb.val(Tensor::new_zeros(&[2, 3]));
+    let mut layers = Vec::new();
+    for scope in scopes {
+        let mut layer = Linear::new(3, 3);
+        node = gb.scoped(scope, |gb| layer.forward(node, gb));
+        layers.push(layer);
+    }
+    (graph, layers)
+}
+
+#[test]
+fn test_layer_keys_survive_inserting_a_layer() {
+    let (trained, layers) = scoped_linears(&["enc", "head"]);
+    let dict = trained.params().state_dict().unwrap();
+    assert!(dict.contains_key("enc.weight") && dict.contains_key("head.bias"));
+
+    let (mut grown, _) = scoped_linears(&["enc", "extra", "head"]);
+    let report = grown.params_mut().load_state_dict(&dict, false).unwrap();
+    assert_eq!(report.missing_keys, vec!["extra.weight".to_string(), "extra.bias".to_string()]);
+    assert!(report.unexpected_keys.is_empty());
+    let head = grown.params().state_dict().unwrap()["head.weight"].clone();
+    assert_eq!(head.as_slice().unwrap(), layers[1].weights.as_slice().unwrap());
+
+    // Unscoped layers are named by kind and numbered in registration order
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let input = gb.val(Tensor::new_zeros(&[2, 3]));
+    let hidden = Linear::new(3, 3).forward(input, &mut gb);
+    Linear::new(3, 2).forward(hidden, &mut gb);
+    let keys: Vec<String> = graph.params().state_dict().unwrap().into_keys().collect();
+    assert!(keys.contains(&"linear.weight".to_string()) && keys.contains(&"linear_1.weight".to_string()));
+}
@@ -1,4 +1,5 @@
 use gran_prix::graph::Graph;
+use gran_prix::graph::OpType;
 use gran_prix::graph::dsl::GraphBuilder;
 use gran_prix::backend::cpu::CPUBackend;
 use gran_prix::Tensor;
@@ -85,3 +86,54 @@ fn test_deep_sequential_chain() {
     let grad = graph.get_gradient(start_node).unwrap();
     assert_eq!(*grad, Tensor::from_shape_vec(&[1, 2], vec![1.0, 0.0]).unwrap());
 }
+
+#[test]
+fn test_broadcast_bias_add_repeated_execution() {
+    let backend = Box::new(CPUBackend);
+    let mut graph = Graph::new(backend);
+    let mut gb = GraphBuilder::new(&mut graph);
+
+    // [3, 2] batch + [1, 2] bias. The second execution reuses the cached
+    // output buffer (in-place path), which must broadcast as well.
+    let x = gb.val(Tensor::from_shape_vec(&[3, 2], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap());
+    let b = gb.param(Tensor::from_shape_vec(&[1, 2], vec![-2.0, 0.5]).unwrap());
+    let y = gb.add(x, b);
+    let z = gb.node(OpType::AddReLU, vec![b, x]);
+
+    let expected_y = Tensor::from_shape_vec(&[3, 2], vec![-1.0, 2.5, 1.0, 4.5, 3.0, 6.5]).unwrap();
+    let expected_z = Tensor::from_shape_vec(&[3, 2], vec![0.0, 2.5, 1.0, 4.5, 3.0, 6.5]).unwrap();
+    for _ in 0..2 {
+        assert_eq!(graph.execute(y).unwrap(), expected_y);
+        assert_eq!(graph.execute(z).unwrap(), expected_z);
+    }
+
+    // The bias gradient is reduced back over the broadcast batch dimension.
+    graph.backward(y, Tensor::new_ones(&[3, 2])).unwrap();
+    assert_eq!(*graph.get_gradient(b).unwrap(), Tensor::from_shape_vec(&[1, 2], vec![3.0, 3.0]).unwrap());
+}
+
+#[test]
+fn test_run_feeds_and_fetches() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let a = gb.placeholder(&[1, 2]);
+    let b = gb.placeholder(&[1, 2]);
+    let sum = gb.add(a, b);
+    let prod = gb.mul(a, b);
+    let out = gb.relu(sum);
+
+    let x = Tensor::from_shape_vec(&[1, 2], vec![1.0, -3.0]).unwrap();
+    let y = Tensor::from_shape_vec(&[1, 2], vec![2.0, 1.0]).unwrap();
+    let values = graph.run(&[(a, &x), (b, &y)], &[out, prod]).unwrap();
+    assert_eq!(values[0].as_slice().unwrap(), &[3.0, 0.0]);
+    assert_eq!(values[1].as_slice().unwrap(), &[2.0, -3.0]);
+
+    // Inputs keep their last fed value
+    let values = graph.run(&[(b, &x)], &[prod]).unwrap();
+    assert_eq!(values[0].as_slice().unwrap(), &[1.0, 9.0]);
+
+    // Feeding the wrong shape or a non-input node fails
+    assert!(graph.run(&[(a, &Tensor::new_zeros(&[2, 2]))], &[out]).is_err());
+    assert!(graph.run(&[(sum, &x)], &[out]).is_err());
+    assert!(graph.run(&[], &[]).unwrap().is_empty());
+}
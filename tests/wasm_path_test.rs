@@ -10,7 +10,7 @@ fn test_wasm_brain_execution_path() {
     // Same as NeuralBrain::new(0, 4, vec![8], 2)
     let net = NetworkDef::mlp(4, &[8], 2, ActivationType::ReLU, Some(ActivationType::Sigmoid));
     let compiled = net.compile(Box::new(CPUBackend)).unwrap();
-    let input_node = compiled.input_node.0;
+    let input_node = compiled.input_node;
     let output_node = compiled.output_node.0;
     let mut graph = compiled.graph;
 
@@ -27,9 +27,7 @@ fn test_wasm_brain_execution_path() {
 
     // === Test 1: Forward pass produces valid outputs ===
     let inputs = vec![0.5, -0.3, 0.8, -0.1];
-    if let Some(gran_prix::graph::Node::Input(ref mut t)) = graph.nodes_mut().get_mut(input_node) {
-        *t = Tensor::from_shape_vec(&[1, 4], inputs.clone()).unwrap();
-    }
+    graph.feed(input_node, &Tensor::from_shape_vec(&[1, 4], inputs.clone()).unwrap()).unwrap();
 
     graph.sync_params().unwrap();
     let output_id = gran_prix::NodeId(output_node);
@@ -48,9 +46,7 @@ fn test_wasm_brain_execution_path() {
     assert!(out[1] >= 0.0 && out[1] <= 1.0, "out[1] = {}", out[1]);
 
     // === Test 2: Different inputs → different outputs ===
-    if let Some(gran_prix::graph::Node::Input(ref mut t)) = graph.nodes_mut().get_mut(input_node) {
-        *t = Tensor::from_shape_vec(&[1, 4], vec![-0.5, 0.3, -0.8, 0.1]).unwrap();
-    }
+    graph.feed(input_node, &Tensor::from_shape_vec(&[1, 4], vec![-0.5, 0.3, -0.8, 0.1]).unwrap()).unwrap();
     graph.sync_params().unwrap();
     for node_id in &order {
         graph.execute_single_node(*node_id).unwrap();
@@ -70,9 +66,7 @@ fn test_wasm_brain_execution_path() {
         params.import_flat(&flat).unwrap();
     }
 
-    if let Some(gran_prix::graph::Node::Input(ref mut t)) = graph.nodes_mut().get_mut(input_node) {
-        *t = Tensor::from_shape_vec(&[1, 4], inputs).unwrap();
-    }
+    graph.feed(input_node, &Tensor::from_shape_vec(&[1, 4], inputs).unwrap()).unwrap();
     graph.sync_params().unwrap();
     for node_id in &order {
         graph.execute_single_node(*node_id).unwrap();
@@ -123,9 +117,7 @@ fn test_wasm_population_compute_evolve_cycle() {
     let mut outputs = Vec::new();
 
     for (idx, graph) in graphs.iter_mut().enumerate() {
-        if let Some(gran_prix::graph::Node::Input(ref mut t)) = graph.nodes_mut().get_mut(input_nodes[idx].0) {
-            *t = Tensor::from_shape_vec(&[1, num_inputs], sensor_data.clone()).unwrap();
-        }
+        graph.feed(input_nodes[idx], &Tensor::from_shape_vec(&[1, num_inputs], sensor_data.clone()).unwrap()).unwrap();
         let result = graph.execute(output_nodes[idx]).unwrap();
         outputs.push(result.as_slice().unwrap().to_vec());
     }
@@ -149,9 +141,7 @@ fn test_wasm_population_compute_evolve_cycle() {
     // After evolution, all agents should produce same output (same weights)
     let mut outputs_after = Vec::new();
     for (idx, graph) in graphs.iter_mut().enumerate() {
-        if let Some(gran_prix::graph::Node::Input(ref mut t)) = graph.nodes_mut().get_mut(input_nodes[idx].0) {
-            *t = Tensor::from_shape_vec(&[1, num_inputs], sensor_data.clone()).unwrap();
-        }
+        graph.feed(input_nodes[idx], &Tensor::from_shape_vec(&[1, num_inputs], sensor_data.clone()).unwrap()).unwrap();
         graph.clear_values();
         let result = graph.execute(output_nodes[idx]).unwrap();
         outputs_after.push(result.as_slice().unwrap().to_vec());
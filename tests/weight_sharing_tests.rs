@@ -0,0 +1,106 @@
+//! Tests for weight sharing: several graph nodes backed by one ParamId.
+
+use gran_prix::graph::{Graph, dsl::GraphBuilder};
+use gran_prix::backend::cpu::CPUBackend;
+use gran_prix::layers::{Linear, ParamBinding};
+use gran_prix::{Layer, Tensor};
+
+fn x() -> Tensor {
+    Tensor::from_shape_vec(&[2, 3], vec![0.5, -1.0, 2.0, 1.5, 0.0, -0.5]).unwrap()
+}
+
+#[test]
+fn test_param_ref_accumulates_gradients_from_all_uses() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let input = gb.val(x());
+    let w = gb.param(Tensor::new_random(&[3, 2]));
+    let id = gb.param_id(w).unwrap();
+    let w_again = gb.param_ref(id);
+    assert_ne!(w, w_again);
+
+    let a = gb.matmul(input, w);
+    let b = gb.matmul(input, w_again);
+    let single = gb.matmul(input, w);
+    let both = gb.add(a, b);
+    assert_eq!(graph.params().len(), 1);
+
+    graph.execute(single).unwrap();
+    graph.backward(single, Tensor::new_ones(&[2, 2])).unwrap();
+    let single_grad = graph.params().gradient(id).unwrap().clone();
+
+    graph.clear_gradients();
+    graph.execute(both).unwrap();
+    graph.backward(both, Tensor::new_ones(&[2, 2])).unwrap();
+    let shared_grad = graph.params().gradient(id).unwrap();
+
+    for (s, g) in single_grad.as_slice().unwrap().iter().zip(shared_grad.as_slice().unwrap()) {
+        assert!((2.0 * s - g).abs() < 1e-5, "shared grad {} should be twice {}", g, s);
+    }
+}
+
+#[test]
+fn test_calling_a_layer_twice_shares_its_weights() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let left = gb.val(x());
+    let right = gb.val(x());
+    let mut tower = Linear::new(3, 4);
+    let out_left = tower.forward(left, &mut gb);
+    let out_right = tower.forward(right, &mut gb);
+
+    assert_eq!(graph.params().len(), 2, "weights and biases registered once");
+    assert_eq!(tower.binding.ids().len(), 2);
+    let a = graph.execute(out_left).unwrap();
+    let b = graph.execute(out_right).unwrap();
+    assert_eq!(a.as_slice().unwrap(), b.as_slice().unwrap());
+}
+
+#[test]
+fn test_layer_registers_afresh_in_a_new_graph() {
+    let mut tower = Linear::new(3, 4);
+    for _ in 0..2 {
+        let mut graph = Graph::new(Box::new(CPUBackend));
+        let mut gb = GraphBuilder::new(&mut graph);
+        let input = gb.val(x());
+        let out = tower.forward(input, &mut gb);
+        assert!(tower.binding.is_bound_to(graph.params()));
+        assert_eq!(graph.execute(out).unwrap().shape(), &[2, 4]);
+    }
+}
+
+#[test]
+fn test_copied_binding_ties_two_layers() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let mut gb = GraphBuilder::new(&mut graph);
+    let input = gb.val(x());
+    let mut encoder = Linear::new(3, 3);
+    let mut decoder = Linear::new(3, 3);
+    let hidden = encoder.forward(input, &mut gb);
+
+    decoder.binding = encoder.binding.clone();
+    let out = decoder.forward(hidden, &mut gb);
+    assert_eq!(graph.params().len(), 2);
+
+    graph.execute(out).unwrap();
+    graph.backward(out, Tensor::new_ones(&[2, 3])).unwrap();
+    assert!(graph.params().gradient(encoder.binding.ids()[0]).is_some());
+}
+
+#[test]
+fn test_explicit_binding_to_existing_params() {
+    let mut graph = Graph::new(Box::new(CPUBackend));
+    let w_id = graph.params_mut().register(Tensor::new_ones(&[3, 2]), "shared.weight");
+    let b_id = graph.params_mut().register(Tensor::new_zeros(&[1, 2]), "shared.bias");
+
+    let mut layer = Linear::new(3, 2);
+    layer.binding = ParamBinding::new(graph.params(), vec![w_id, b_id]);
+    let mut gb = GraphBuilder::new(&mut graph);
+    let input = gb.val(x());
+    let out = layer.forward(input, &mut gb);
+
+    // Uses the registered ones-matrix, not the layer's own random weights.
+    let result = graph.execute(out).unwrap();
+    assert_eq!(result.as_slice().unwrap(), &[1.5, 1.5, 1.0, 1.0]);
+    assert_eq!(graph.params().len(), 2);
+}